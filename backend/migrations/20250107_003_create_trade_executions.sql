@@ -0,0 +1,32 @@
+-- Create trade executions table
+CREATE TABLE IF NOT EXISTS trade_executions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    trade_id UUID NOT NULL REFERENCES trades(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+
+    -- Fill Details
+    side VARCHAR(10) NOT NULL CHECK (side IN ('buy', 'sell')),
+    price DECIMAL(20, 8) NOT NULL,
+    quantity DECIMAL(20, 8) NOT NULL CHECK (quantity > 0),
+    executed_at TIMESTAMPTZ NOT NULL,
+    fee DECIMAL(20, 8) NOT NULL DEFAULT 0,
+
+    created_at TIMESTAMPTZ DEFAULT NOW()
+);
+
+-- Track remaining position size on the trade itself
+ALTER TABLE trades ADD COLUMN IF NOT EXISTS open_quantity DECIMAL(20, 8);
+UPDATE trades
+SET open_quantity = CASE WHEN exit_price IS NULL THEN quantity ELSE 0 END
+WHERE open_quantity IS NULL;
+ALTER TABLE trades ALTER COLUMN open_quantity SET DEFAULT 0;
+ALTER TABLE trades ALTER COLUMN open_quantity SET NOT NULL;
+
+-- Create indexes
+CREATE INDEX IF NOT EXISTS idx_trade_executions_trade_id ON trade_executions(trade_id, executed_at);
+CREATE INDEX IF NOT EXISTS idx_trade_executions_user_id ON trade_executions(user_id);
+
+-- Add comments
+COMMENT ON TABLE trade_executions IS 'Individual fills (scale-in / scale-out) belonging to a trade';
+COMMENT ON COLUMN trade_executions.side IS 'buy or sell';
+COMMENT ON COLUMN trades.open_quantity IS 'Quantity still open after all recorded exits';
@@ -0,0 +1,10 @@
+-- Stable broker-side identifier used to skip duplicate imports
+ALTER TABLE trades ADD COLUMN IF NOT EXISTS external_id VARCHAR(255);
+
+-- Create indexes
+CREATE UNIQUE INDEX IF NOT EXISTS idx_trades_external_id
+    ON trades(user_id, broker, external_id)
+    WHERE external_id IS NOT NULL;
+
+-- Add comments
+COMMENT ON COLUMN trades.external_id IS 'Broker execution/ticket ID of the opening fill, set by imports';
@@ -0,0 +1,15 @@
+-- Planned risk and R-multiples
+ALTER TABLE trades ADD COLUMN IF NOT EXISTS stop_loss DECIMAL(20, 8);
+ALTER TABLE trades ADD COLUMN IF NOT EXISTS initial_stop DECIMAL(20, 8);
+ALTER TABLE trades ADD COLUMN IF NOT EXISTS take_profit DECIMAL(20, 8)[] NOT NULL DEFAULT '{}';
+ALTER TABLE trades ADD COLUMN IF NOT EXISTS planned_risk DECIMAL(20, 8);
+ALTER TABLE trades ADD COLUMN IF NOT EXISTS r_multiple DECIMAL(12, 4);
+ALTER TABLE trades ADD COLUMN IF NOT EXISTS planned_r_multiple DECIMAL(12, 4);
+
+-- Add comments
+COMMENT ON COLUMN trades.stop_loss IS 'Current stop price, may be trailed';
+COMMENT ON COLUMN trades.initial_stop IS 'Stop price at entry, defines 1R';
+COMMENT ON COLUMN trades.take_profit IS 'Target prices in order; the last one is the full exit';
+COMMENT ON COLUMN trades.planned_risk IS 'Amount risked (1R) in account currency; overrides the stop distance';
+COMMENT ON COLUMN trades.r_multiple IS 'Realized P&L in R, set once the trade is closed';
+COMMENT ON COLUMN trades.planned_r_multiple IS 'Reward at the final target in R';
@@ -0,0 +1,65 @@
+-- Create accounts table
+CREATE TABLE IF NOT EXISTS accounts (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name VARCHAR(100) NOT NULL,
+    broker VARCHAR(100),
+    base_currency VARCHAR(3) NOT NULL DEFAULT 'USD',
+    starting_balance DECIMAL(20, 8) NOT NULL DEFAULT 0,
+    created_at TIMESTAMPTZ DEFAULT NOW(),
+    updated_at TIMESTAMPTZ DEFAULT NOW(),
+    UNIQUE (user_id, name)
+);
+
+-- Create cash movements table
+CREATE TABLE IF NOT EXISTS cash_movements (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    account_id UUID NOT NULL REFERENCES accounts(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    kind VARCHAR(20) NOT NULL CHECK (kind IN ('deposit', 'withdrawal', 'fee', 'interest')),
+    amount DECIMAL(20, 8) NOT NULL CHECK (amount > 0),
+    occurred_at TIMESTAMPTZ NOT NULL,
+    notes TEXT,
+    created_at TIMESTAMPTZ DEFAULT NOW()
+);
+
+-- Link trades to accounts. The old free-text account_id is converted once:
+-- every distinct value becomes an account of the same name.
+DO $$
+BEGIN
+    IF EXISTS (
+        SELECT 1 FROM information_schema.columns
+        WHERE table_name = 'trades' AND column_name = 'account_id' AND data_type <> 'uuid'
+    ) THEN
+        INSERT INTO accounts (user_id, name, broker)
+        SELECT DISTINCT ON (user_id, account_id) user_id, account_id, broker
+        FROM trades
+        WHERE account_id IS NOT NULL
+        ORDER BY user_id, account_id, created_at
+        ON CONFLICT (user_id, name) DO NOTHING;
+
+        ALTER TABLE trades ADD COLUMN account_uuid UUID;
+
+        UPDATE trades t SET account_uuid = a.id
+        FROM accounts a
+        WHERE a.user_id = t.user_id AND a.name = t.account_id;
+
+        ALTER TABLE trades DROP COLUMN account_id;
+        ALTER TABLE trades RENAME COLUMN account_uuid TO account_id;
+        ALTER TABLE trades
+            ADD CONSTRAINT trades_account_id_fkey
+            FOREIGN KEY (account_id) REFERENCES accounts(id) ON DELETE SET NULL;
+    END IF;
+END $$;
+
+-- Create indexes
+CREATE INDEX IF NOT EXISTS idx_accounts_user_id ON accounts(user_id);
+CREATE INDEX IF NOT EXISTS idx_cash_movements_account ON cash_movements(account_id, occurred_at);
+CREATE INDEX IF NOT EXISTS idx_trades_account_id ON trades(account_id);
+
+-- Add comments
+COMMENT ON TABLE accounts IS 'Brokerage accounts trades are booked to';
+COMMENT ON COLUMN accounts.starting_balance IS 'Cash balance before the first recorded trade or movement';
+COMMENT ON TABLE cash_movements IS 'Deposits, withdrawals, fees and interest of an account';
+COMMENT ON COLUMN cash_movements.kind IS 'deposit, withdrawal, fee, interest';
+COMMENT ON COLUMN cash_movements.amount IS 'Always positive; the kind gives the direction';
@@ -0,0 +1,68 @@
+-- Create instruments table
+CREATE TABLE IF NOT EXISTS instruments (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID REFERENCES users(id) ON DELETE CASCADE,
+    symbol VARCHAR(50) NOT NULL,
+    asset_class VARCHAR(20) NOT NULL CHECK (asset_class IN ('stock', 'etf', 'future', 'option', 'forex', 'crypto', 'cfd')),
+    multiplier DECIMAL(20, 8) NOT NULL DEFAULT 1 CHECK (multiplier > 0),
+    tick_size DECIMAL(20, 10),
+    tick_value DECIMAL(20, 8),
+    quote_currency VARCHAR(3) NOT NULL DEFAULT 'USD',
+    exchange VARCHAR(50),
+    created_at TIMESTAMPTZ DEFAULT NOW(),
+    updated_at TIMESTAMPTZ DEFAULT NOW()
+);
+
+-- Snapshot of the multiplier a trade's P&L was computed with
+ALTER TABLE trades ADD COLUMN IF NOT EXISTS multiplier DECIMAL(20, 8) NOT NULL DEFAULT 1;
+
+-- OCC option symbols are 21 characters
+ALTER TABLE trades ALTER COLUMN symbol TYPE VARCHAR(50);
+
+-- Create indexes
+CREATE UNIQUE INDEX IF NOT EXISTS idx_instruments_global_symbol
+    ON instruments(symbol)
+    WHERE user_id IS NULL;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_instruments_user_symbol
+    ON instruments(user_id, symbol)
+    WHERE user_id IS NOT NULL;
+
+-- Seed the shared catalog
+INSERT INTO instruments (symbol, asset_class, multiplier, tick_size, tick_value, quote_currency, exchange)
+VALUES
+    ('ES', 'future', 50, 0.25, 12.5, 'USD', 'CME'),
+    ('MES', 'future', 5, 0.25, 1.25, 'USD', 'CME'),
+    ('NQ', 'future', 20, 0.25, 5, 'USD', 'CME'),
+    ('MNQ', 'future', 2, 0.25, 0.5, 'USD', 'CME'),
+    ('RTY', 'future', 50, 0.1, 5, 'USD', 'CME'),
+    ('M2K', 'future', 5, 0.1, 0.5, 'USD', 'CME'),
+    ('YM', 'future', 5, 1, 5, 'USD', 'CBOT'),
+    ('MYM', 'future', 0.5, 1, 0.5, 'USD', 'CBOT'),
+    ('ZB', 'future', 1000, 0.03125, 31.25, 'USD', 'CBOT'),
+    ('ZN', 'future', 1000, 0.015625, 15.625, 'USD', 'CBOT'),
+    ('ZC', 'future', 50, 0.25, 12.5, 'USD', 'CBOT'),
+    ('ZS', 'future', 50, 0.25, 12.5, 'USD', 'CBOT'),
+    ('CL', 'future', 1000, 0.01, 10, 'USD', 'NYMEX'),
+    ('MCL', 'future', 100, 0.01, 1, 'USD', 'NYMEX'),
+    ('NG', 'future', 10000, 0.001, 10, 'USD', 'NYMEX'),
+    ('GC', 'future', 100, 0.1, 10, 'USD', 'COMEX'),
+    ('MGC', 'future', 10, 0.1, 1, 'USD', 'COMEX'),
+    ('SI', 'future', 5000, 0.005, 25, 'USD', 'COMEX'),
+    ('HG', 'future', 25000, 0.0005, 12.5, 'USD', 'COMEX'),
+    ('6E', 'future', 125000, 0.00005, 6.25, 'USD', 'CME'),
+    ('6B', 'future', 62500, 0.0001, 6.25, 'USD', 'CME'),
+    ('6J', 'future', 12500000, 0.0000005, 6.25, 'USD', 'CME'),
+    ('EURUSD', 'forex', 100000, 0.00001, 1, 'USD', NULL),
+    ('GBPUSD', 'forex', 100000, 0.00001, 1, 'USD', NULL),
+    ('AUDUSD', 'forex', 100000, 0.00001, 1, 'USD', NULL),
+    ('NZDUSD', 'forex', 100000, 0.00001, 1, 'USD', NULL),
+    ('USDJPY', 'forex', 100000, 0.001, 100, 'JPY', NULL),
+    ('USDCAD', 'forex', 100000, 0.00001, 1, 'CAD', NULL),
+    ('USDCHF', 'forex', 100000, 0.00001, 1, 'CHF', NULL)
+ON CONFLICT (symbol) WHERE user_id IS NULL DO NOTHING;
+
+-- Add comments
+COMMENT ON TABLE instruments IS 'Contract specifications; rows with a user_id override the shared catalog for that user';
+COMMENT ON COLUMN instruments.multiplier IS 'Currency value of a 1.0 price move per unit of quantity (futures point value, 100 for options, lot size for forex)';
+COMMENT ON COLUMN instruments.asset_class IS 'stock, etf, future, option, forex, crypto, cfd';
+COMMENT ON COLUMN trades.multiplier IS 'Instrument multiplier at the time the trade was created or its symbol changed';
@@ -0,0 +1,54 @@
+-- Create FX rates table
+CREATE TABLE IF NOT EXISTS fx_rates (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    base_currency VARCHAR(3) NOT NULL,
+    quote_currency VARCHAR(3) NOT NULL,
+    rate_date DATE NOT NULL,
+    rate DECIMAL(20, 10) NOT NULL CHECK (rate > 0),
+    source VARCHAR(20) NOT NULL DEFAULT 'manual' CHECK (source IN ('manual', 'import')),
+    created_at TIMESTAMPTZ DEFAULT NOW(),
+    updated_at TIMESTAMPTZ DEFAULT NOW(),
+    CHECK (base_currency <> quote_currency),
+    UNIQUE (user_id, base_currency, quote_currency, rate_date)
+);
+
+-- Currency of trade prices and P&L. Existing trades take the quote currency
+-- of their instrument, then the base currency of their account.
+DO $$
+BEGIN
+    IF NOT EXISTS (
+        SELECT 1 FROM information_schema.columns
+        WHERE table_name = 'trades' AND column_name = 'currency'
+    ) THEN
+        ALTER TABLE trades ADD COLUMN currency VARCHAR(3) NOT NULL DEFAULT 'USD';
+
+        UPDATE trades t
+        SET currency = COALESCE(
+            (SELECT i.quote_currency FROM instruments i
+             WHERE i.symbol = t.symbol AND (i.user_id = t.user_id OR i.user_id IS NULL)
+             ORDER BY i.user_id IS NULL
+             LIMIT 1),
+            (SELECT a.base_currency FROM accounts a WHERE a.id = t.account_id),
+            'USD'
+        );
+    END IF;
+END $$;
+
+-- Fees may be charged in another currency; they are converted into the
+-- trade currency at the rate recorded next to them
+ALTER TABLE trades ADD COLUMN IF NOT EXISTS fee_currency VARCHAR(3);
+UPDATE trades SET fee_currency = currency WHERE fee_currency IS NULL;
+ALTER TABLE trades ALTER COLUMN fee_currency SET NOT NULL;
+ALTER TABLE trades ADD COLUMN IF NOT EXISTS fee_fx_rate DECIMAL(20, 10) NOT NULL DEFAULT 1;
+ALTER TABLE trades ADD COLUMN IF NOT EXISTS fee_fx_date DATE;
+
+-- Create indexes
+CREATE INDEX IF NOT EXISTS idx_fx_rates_pair_date ON fx_rates(user_id, base_currency, quote_currency, rate_date DESC);
+
+-- Add comments
+COMMENT ON TABLE fx_rates IS 'Daily FX rates: 1 unit of base_currency is worth rate units of quote_currency';
+COMMENT ON COLUMN trades.currency IS 'Currency of prices and P&L';
+COMMENT ON COLUMN trades.fee_currency IS 'Currency fees were charged in';
+COMMENT ON COLUMN trades.fee_fx_rate IS 'Rate converting fees into the trade currency; 1 when both match';
+COMMENT ON COLUMN trades.fee_fx_date IS 'Date of the FX rate applied to fees';
@@ -0,0 +1,51 @@
+-- Create option legs table
+CREATE TABLE IF NOT EXISTS option_legs (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    trade_id UUID NOT NULL REFERENCES trades(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+
+    -- Contract
+    underlying VARCHAR(20) NOT NULL,
+    option_type VARCHAR(10) NOT NULL CHECK (option_type IN ('call', 'put', 'stock')),
+    expiry DATE,
+    strike DECIMAL(20, 8) CHECK (strike > 0),
+    multiplier DECIMAL(20, 8) NOT NULL DEFAULT 100 CHECK (multiplier > 0),
+
+    -- Position
+    side VARCHAR(10) NOT NULL CHECK (side IN ('buy', 'sell')),
+    quantity DECIMAL(20, 8) NOT NULL CHECK (quantity > 0),
+    premium DECIMAL(20, 8) NOT NULL CHECK (premium >= 0),
+    opened_at TIMESTAMPTZ NOT NULL,
+    fees DECIMAL(20, 8) NOT NULL DEFAULT 0,
+
+    -- Close
+    close_premium DECIMAL(20, 8) CHECK (close_premium >= 0),
+    closed_at TIMESTAMPTZ,
+    close_reason VARCHAR(20) CHECK (close_reason IN ('closed', 'expired', 'assigned', 'exercised')),
+
+    created_at TIMESTAMPTZ DEFAULT NOW(),
+    CHECK ((option_type = 'stock') = (expiry IS NULL AND strike IS NULL)),
+    CHECK ((close_premium IS NULL) = (closed_at IS NULL))
+);
+
+-- Strategy grouping on trades
+ALTER TABLE trades ADD COLUMN IF NOT EXISTS strategy VARCHAR(30);
+ALTER TABLE trades ADD COLUMN IF NOT EXISTS underlying VARCHAR(20);
+
+-- Single options entered by OCC symbol roll up by their root
+UPDATE trades
+SET underlying = substring(regexp_replace(symbol, '\s', '', 'g') FROM '^([A-Z0-9]+)\d{6}[CP]\d{8}$')
+WHERE underlying IS NULL
+    AND regexp_replace(symbol, '\s', '', 'g') ~ '^[A-Z0-9]+\d{6}[CP]\d{8}$';
+
+-- Create indexes
+CREATE INDEX IF NOT EXISTS idx_option_legs_trade_id ON option_legs(trade_id, opened_at);
+CREATE INDEX IF NOT EXISTS idx_option_legs_open_expiry ON option_legs(user_id, expiry) WHERE closed_at IS NULL;
+CREATE INDEX IF NOT EXISTS idx_trades_underlying ON trades(user_id, underlying);
+
+-- Add comments
+COMMENT ON TABLE option_legs IS 'Option and stock legs of a multi-leg trade';
+COMMENT ON COLUMN option_legs.premium IS 'Opening price per share (per unit for stock legs)';
+COMMENT ON COLUMN option_legs.close_reason IS 'closed, expired (worthless), assigned (short leg) or exercised (long leg)';
+COMMENT ON COLUMN trades.strategy IS 'Option strategy the legs form, e.g. vertical or iron_condor';
+COMMENT ON COLUMN trades.underlying IS 'Underlying symbol of option trades';
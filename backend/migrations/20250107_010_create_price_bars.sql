@@ -0,0 +1,33 @@
+-- Create price bars table
+CREATE TABLE IF NOT EXISTS price_bars (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    symbol VARCHAR(50) NOT NULL,
+    timeframe VARCHAR(10) NOT NULL CHECK (timeframe IN ('1m', '5m', '15m', '30m', '1h', '4h', '1d')),
+    bar_time TIMESTAMPTZ NOT NULL,
+    open DECIMAL(20, 8) NOT NULL,
+    high DECIMAL(20, 8) NOT NULL,
+    low DECIMAL(20, 8) NOT NULL,
+    close DECIMAL(20, 8) NOT NULL,
+    volume DECIMAL(20, 8),
+    created_at TIMESTAMPTZ DEFAULT NOW(),
+    UNIQUE (user_id, symbol, timeframe, bar_time),
+    CHECK (low <= high AND open BETWEEN low AND high AND close BETWEEN low AND high)
+);
+
+-- Excursions of closed trades, derived from price bars
+ALTER TABLE trades ADD COLUMN IF NOT EXISTS mae DECIMAL(20, 8);
+ALTER TABLE trades ADD COLUMN IF NOT EXISTS mfe DECIMAL(20, 8);
+ALTER TABLE trades ADD COLUMN IF NOT EXISTS entry_efficiency DECIMAL(8, 4);
+ALTER TABLE trades ADD COLUMN IF NOT EXISTS exit_efficiency DECIMAL(8, 4);
+
+-- Create indexes
+CREATE INDEX IF NOT EXISTS idx_price_bars_symbol_time ON price_bars(user_id, symbol, bar_time);
+
+-- Add comments
+COMMENT ON TABLE price_bars IS 'OHLC bars imported per symbol and timeframe';
+COMMENT ON COLUMN price_bars.bar_time IS 'Start of the bar; the timeframe cast to INTERVAL gives its length';
+COMMENT ON COLUMN trades.mae IS 'Maximum adverse excursion: furthest price move against the trade while it was open';
+COMMENT ON COLUMN trades.mfe IS 'Maximum favorable excursion: furthest price move in the trade''s favor while it was open';
+COMMENT ON COLUMN trades.entry_efficiency IS 'Percent of the price range while open that lay beyond the entry in the trade''s favor';
+COMMENT ON COLUMN trades.exit_efficiency IS 'Percent of the price range while open captured by the exit';
@@ -0,0 +1,25 @@
+-- Create trade attachments table
+CREATE TABLE IF NOT EXISTS trade_attachments (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    trade_id UUID NOT NULL REFERENCES trades(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    file_name VARCHAR(255) NOT NULL,
+    content_type VARCHAR(100) NOT NULL,
+    size_bytes BIGINT NOT NULL CHECK (size_bytes >= 0),
+    width INTEGER,
+    height INTEGER,
+    storage_key VARCHAR(255) NOT NULL UNIQUE,
+    thumbnail_key VARCHAR(255),
+    created_at TIMESTAMPTZ DEFAULT NOW()
+);
+
+-- Create indexes
+CREATE INDEX IF NOT EXISTS idx_trade_attachments_trade ON trade_attachments(trade_id, created_at);
+CREATE INDEX IF NOT EXISTS idx_trade_attachments_user ON trade_attachments(user_id);
+
+-- Add comments
+COMMENT ON TABLE trade_attachments IS 'Files uploaded to trades; the bytes live in the configured blob store';
+COMMENT ON COLUMN trade_attachments.content_type IS 'Type sniffed from the file contents, not the one the client sent';
+COMMENT ON COLUMN trade_attachments.size_bytes IS 'Size of the stored file after metadata was stripped';
+COMMENT ON COLUMN trade_attachments.thumbnail_key IS 'Blob key of the downscaled preview; set for images only';
+COMMENT ON COLUMN trades.screenshots IS 'Download paths of the trade''s image attachments, in upload order';
@@ -0,0 +1,37 @@
+-- Create journal entries table
+CREATE TABLE IF NOT EXISTS journal_entries (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    entry_date DATE NOT NULL,
+
+    -- Session
+    pre_market_plan TEXT,
+    post_session_review TEXT,
+    market_notes TEXT,
+    plan_followed BOOLEAN,
+
+    -- State of mind
+    mood SMALLINT CHECK (mood BETWEEN 1 AND 5),
+    energy SMALLINT CHECK (energy BETWEEN 1 AND 5),
+
+    created_at TIMESTAMPTZ DEFAULT NOW(),
+    updated_at TIMESTAMPTZ DEFAULT NOW(),
+    UNIQUE (user_id, entry_date)
+);
+
+-- Trades a journal day covers
+CREATE TABLE IF NOT EXISTS journal_entry_trades (
+    entry_id UUID NOT NULL REFERENCES journal_entries(id) ON DELETE CASCADE,
+    trade_id UUID NOT NULL REFERENCES trades(id) ON DELETE CASCADE,
+    PRIMARY KEY (entry_id, trade_id)
+);
+
+-- Create indexes
+CREATE INDEX IF NOT EXISTS idx_journal_entry_trades_trade ON journal_entry_trades(trade_id);
+
+-- Add comments
+COMMENT ON TABLE journal_entries IS 'One journal page per user and trading day, independent of trades';
+COMMENT ON COLUMN journal_entries.plan_followed IS 'Whether the day went according to the pre-market plan; NULL when not rated';
+COMMENT ON COLUMN journal_entries.mood IS '1 (poor) to 5 (excellent)';
+COMMENT ON COLUMN journal_entries.energy IS '1 (exhausted) to 5 (fully rested)';
+COMMENT ON TABLE journal_entry_trades IS 'Links journal days to the trades taken on them';
@@ -0,0 +1,50 @@
+-- Create playbooks table
+CREATE TABLE IF NOT EXISTS playbooks (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name VARCHAR(100) NOT NULL,
+    description TEXT,
+    entry_criteria TEXT,
+    exit_criteria TEXT,
+    checklist TEXT[] NOT NULL DEFAULT '{}',
+    created_at TIMESTAMPTZ DEFAULT NOW(),
+    updated_at TIMESTAMPTZ DEFAULT NOW()
+);
+
+-- Names are unique per user regardless of case
+CREATE UNIQUE INDEX IF NOT EXISTS idx_playbooks_user_name ON playbooks(user_id, LOWER(name));
+
+-- Link trades to playbooks. Free-text setup types are converted once: each
+-- distinct value, ignoring case and surrounding spaces, becomes a playbook
+-- named after its most used spelling.
+DO $$
+BEGIN
+    IF NOT EXISTS (
+        SELECT 1 FROM information_schema.columns
+        WHERE table_name = 'trades' AND column_name = 'playbook_id'
+    ) THEN
+        ALTER TABLE trades ADD COLUMN playbook_id UUID REFERENCES playbooks(id) ON DELETE SET NULL;
+        ALTER TABLE trades ADD COLUMN checklist_met TEXT[] NOT NULL DEFAULT '{}';
+
+        INSERT INTO playbooks (user_id, name)
+        SELECT DISTINCT ON (user_id, LOWER(TRIM(setup_type))) user_id, TRIM(setup_type)
+        FROM trades
+        WHERE TRIM(setup_type) <> ''
+        GROUP BY user_id, TRIM(setup_type)
+        ORDER BY user_id, LOWER(TRIM(setup_type)), COUNT(*) DESC, TRIM(setup_type)
+        ON CONFLICT (user_id, LOWER(name)) DO NOTHING;
+
+        UPDATE trades t SET playbook_id = p.id, setup_type = p.name
+        FROM playbooks p
+        WHERE p.user_id = t.user_id AND LOWER(p.name) = LOWER(TRIM(t.setup_type));
+    END IF;
+END $$;
+
+-- Create indexes
+CREATE INDEX IF NOT EXISTS idx_trades_playbook_id ON trades(playbook_id);
+
+-- Add comments
+COMMENT ON TABLE playbooks IS 'Managed setup definitions with their rules and checklist';
+COMMENT ON COLUMN playbooks.checklist IS 'Conditions to confirm before taking a trade';
+COMMENT ON COLUMN trades.playbook_id IS 'Playbook the trade was taken from; setup_type mirrors its name';
+COMMENT ON COLUMN trades.checklist_met IS 'Checklist items of the playbook that were met';
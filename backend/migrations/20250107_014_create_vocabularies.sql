@@ -0,0 +1,31 @@
+-- Create vocabulary terms table
+CREATE TABLE IF NOT EXISTS vocabulary_terms (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    kind VARCHAR(20) NOT NULL CHECK (kind IN ('tags', 'mistakes', 'emotions')),
+    name VARCHAR(100) NOT NULL,
+    color VARCHAR(7) CHECK (color ~ '^#[0-9a-f]{6}$'),
+    category VARCHAR(50),
+    created_at TIMESTAMPTZ DEFAULT NOW(),
+    updated_at TIMESTAMPTZ DEFAULT NOW()
+);
+
+-- Per-user options of each vocabulary
+CREATE TABLE IF NOT EXISTS vocabulary_settings (
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    kind VARCHAR(20) NOT NULL CHECK (kind IN ('tags', 'mistakes', 'emotions')),
+    strict BOOLEAN NOT NULL DEFAULT FALSE,
+    PRIMARY KEY (user_id, kind)
+);
+
+-- Create indexes
+CREATE UNIQUE INDEX IF NOT EXISTS idx_vocabulary_terms_name ON vocabulary_terms(user_id, kind, LOWER(name));
+CREATE INDEX IF NOT EXISTS idx_trades_tags ON trades USING GIN(tags);
+CREATE INDEX IF NOT EXISTS idx_trades_mistakes ON trades USING GIN(mistakes);
+CREATE INDEX IF NOT EXISTS idx_trades_emotions ON trades USING GIN(emotions);
+
+-- Add comments
+COMMENT ON TABLE vocabulary_terms IS 'Known values of the tags, mistakes and emotions arrays of trades';
+COMMENT ON COLUMN vocabulary_terms.kind IS 'Trade column the term belongs to: tags, mistakes, emotions';
+COMMENT ON COLUMN vocabulary_terms.color IS 'Display color as #rrggbb';
+COMMENT ON COLUMN vocabulary_settings.strict IS 'Reject values missing from the vocabulary when saving trades';
@@ -0,0 +1,21 @@
+-- Create user settings table
+CREATE TABLE IF NOT EXISTS user_settings (
+    user_id UUID PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    timezone VARCHAR(64) NOT NULL DEFAULT 'UTC',
+    day_rollover_hour SMALLINT NOT NULL DEFAULT 0 CHECK (day_rollover_hour BETWEEN 0 AND 23),
+    reporting_currency VARCHAR(3),
+    default_broker VARCHAR(100),
+    default_account_id UUID REFERENCES accounts(id) ON DELETE SET NULL,
+    default_fees DECIMAL(20, 8) CHECK (default_fees >= 0),
+    risk_per_trade DECIMAL(20, 8) CHECK (risk_per_trade > 0),
+    created_at TIMESTAMPTZ DEFAULT NOW(),
+    updated_at TIMESTAMPTZ DEFAULT NOW()
+);
+
+-- Add comments
+COMMENT ON TABLE user_settings IS 'Per-user preferences for analytics and new trades';
+COMMENT ON COLUMN user_settings.timezone IS 'IANA name analytics cut days, weeks and months in';
+COMMENT ON COLUMN user_settings.day_rollover_hour IS 'Local hour a trading day ends; later times count toward the next day';
+COMMENT ON COLUMN user_settings.reporting_currency IS 'Currency analytics are converted into unless another is requested';
+COMMENT ON COLUMN user_settings.default_fees IS 'Fees of new trades entered without fees or fills';
+COMMENT ON COLUMN user_settings.risk_per_trade IS 'Planned risk (1R) of new trades entered without one or a stop';
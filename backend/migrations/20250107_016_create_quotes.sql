@@ -0,0 +1,15 @@
+-- Create quotes table
+CREATE TABLE IF NOT EXISTS quotes (
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    symbol VARCHAR(50) NOT NULL,
+    price DECIMAL(20, 8) NOT NULL CHECK (price > 0),
+    as_of TIMESTAMPTZ NOT NULL,
+    source VARCHAR(20) NOT NULL DEFAULT 'manual',
+    updated_at TIMESTAMPTZ DEFAULT NOW(),
+    PRIMARY KEY (user_id, symbol)
+);
+
+-- Add comments
+COMMENT ON TABLE quotes IS 'Latest price of each symbol entered or imported by the user, used to mark open trades';
+COMMENT ON COLUMN quotes.as_of IS 'Time the price was observed; older quotes never replace newer ones';
+COMMENT ON COLUMN quotes.source IS 'manual, import';
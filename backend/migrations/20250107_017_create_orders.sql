@@ -0,0 +1,43 @@
+-- Orders that haven't filled end as cancelled or expired trades
+ALTER TABLE trades DROP CONSTRAINT IF EXISTS trades_status_check;
+ALTER TABLE trades ADD CONSTRAINT trades_status_check
+    CHECK (status IN ('open', 'closed', 'pending', 'cancelled', 'expired'));
+
+-- Create orders table
+CREATE TABLE IF NOT EXISTS orders (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    trade_id UUID NOT NULL UNIQUE REFERENCES trades(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+
+    -- Order
+    order_type VARCHAR(20) NOT NULL CHECK (order_type IN ('market', 'limit', 'stop', 'stop_limit')),
+    limit_price DECIMAL(20, 8) CHECK (limit_price > 0),
+    stop_price DECIMAL(20, 8) CHECK (stop_price > 0),
+    placed_at TIMESTAMPTZ NOT NULL,
+    expires_at TIMESTAMPTZ,
+    status VARCHAR(20) NOT NULL DEFAULT 'pending' CHECK (status IN ('pending', 'filled', 'cancelled', 'expired')),
+
+    -- Outcome
+    filled_at TIMESTAMPTZ,
+    fill_price DECIMAL(20, 8) CHECK (fill_price > 0),
+    cancelled_at TIMESTAMPTZ,
+    cancel_reason TEXT,
+
+    created_at TIMESTAMPTZ DEFAULT NOW(),
+    updated_at TIMESTAMPTZ DEFAULT NOW(),
+    CHECK ((order_type IN ('limit', 'stop_limit')) = (limit_price IS NOT NULL)),
+    CHECK ((order_type IN ('stop', 'stop_limit')) = (stop_price IS NOT NULL)),
+    CHECK (expires_at IS NULL OR expires_at > placed_at),
+    CHECK ((status = 'filled') = (filled_at IS NOT NULL AND fill_price IS NOT NULL)),
+    CHECK ((status = 'cancelled') = (cancelled_at IS NOT NULL))
+);
+
+-- Create indexes
+CREATE INDEX IF NOT EXISTS idx_orders_user_status ON orders(user_id, status, placed_at DESC);
+CREATE INDEX IF NOT EXISTS idx_orders_pending_expiry ON orders(user_id, expires_at) WHERE status = 'pending';
+
+-- Add comments
+COMMENT ON TABLE orders IS 'Planned entry orders; the trade stays pending until the order fills';
+COMMENT ON COLUMN orders.order_type IS 'market, limit, stop or stop_limit';
+COMMENT ON COLUMN orders.expires_at IS 'Time a pending order lapses; NULL is good until cancelled';
+COMMENT ON COLUMN trades.status IS 'open, closed, pending (order not filled), cancelled, expired';
@@ -0,0 +1,23 @@
+-- Create trade revisions table; revisions outlive their trade
+CREATE TABLE IF NOT EXISTS trade_revisions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    trade_id UUID NOT NULL,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    revision INTEGER NOT NULL CHECK (revision > 0),
+    action VARCHAR(10) NOT NULL CHECK (action IN ('create', 'update', 'delete', 'restore')),
+    changes JSONB NOT NULL,
+    request_id VARCHAR(100),
+    restored_from INTEGER,
+    created_at TIMESTAMPTZ DEFAULT NOW(),
+    UNIQUE (trade_id, revision),
+    CHECK ((action = 'restore') = (restored_from IS NOT NULL))
+);
+
+-- Create indexes
+CREATE INDEX IF NOT EXISTS idx_trade_revisions_user ON trade_revisions(user_id, created_at DESC);
+
+-- Add comments
+COMMENT ON TABLE trade_revisions IS 'Audit trail of changes made to trades';
+COMMENT ON COLUMN trade_revisions.changes IS 'Changed fields as {"field": {"from": old, "to": new}}; creates and deletes list every field that is set';
+COMMENT ON COLUMN trade_revisions.request_id IS 'ID of the API request that made the change';
+COMMENT ON COLUMN trade_revisions.restored_from IS 'Revision a restore brought the trade back to';
@@ -0,0 +1,25 @@
+//! Mock quote service for running the backend with `PRICE_SOURCE=http`.
+//!
+//! Listens on `MOCK_QUOTE_PORT` (default 8081) and, when `QUOTE_API_KEY` is
+//! set, requires it as a bearer token, matching the backend's settings.
+
+use std::net::SocketAddr;
+use trading_journal_backend::pricing::mock;
+
+#[tokio::main]
+async fn main() {
+    dotenv::dotenv().ok();
+    tracing_subscriber::fmt::init();
+
+    let port: u16 = std::env::var("MOCK_QUOTE_PORT")
+        .ok()
+        .and_then(|port| port.parse().ok())
+        .unwrap_or(8081);
+    let api_key = std::env::var("QUOTE_API_KEY").ok().filter(|key| !key.is_empty());
+
+    let addr = SocketAddr::from(([127, 0, 0, 1], port));
+    tracing::info!("Mock quote server on http://{}/quotes", addr);
+
+    let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
+    axum::serve(listener, mock::router(api_key)).await.unwrap();
+}
@@ -10,6 +10,21 @@ pub struct Config {
     pub server_port: u16,
     pub stripe_secret_key: String,
     pub stripe_webhook_secret: String,
+    /// Where attachments are stored: "local" or "s3"
+    pub storage_backend: String,
+    /// Root directory of the local attachment store
+    pub storage_path: String,
+    /// Base URL of an S3-compatible service, e.g. http://localhost:9000 for MinIO
+    pub s3_endpoint: Option<String>,
+    pub s3_bucket: Option<String>,
+    pub s3_region: String,
+    pub s3_access_key: Option<String>,
+    pub s3_secret_key: Option<String>,
+    /// Where open trades are priced from: "manual" or "http"
+    pub price_source: String,
+    /// Quote service of the http price source
+    pub quote_api_url: Option<String>,
+    pub quote_api_key: Option<String>,
 }
 
 impl Config {
@@ -42,6 +57,24 @@ impl Config {
         let stripe_webhook_secret = env::var("STRIPE_WEBHOOK_SECRET")
             .map_err(|_| "STRIPE_WEBHOOK_SECRET must be set".to_string())?;
 
+        let storage_backend = env::var("STORAGE_BACKEND")
+            .unwrap_or_else(|_| "local".to_string());
+
+        let storage_path = env::var("STORAGE_PATH")
+            .unwrap_or_else(|_| "./data/attachments".to_string());
+
+        let s3_endpoint = env::var("S3_ENDPOINT").ok();
+        let s3_bucket = env::var("S3_BUCKET").ok();
+        let s3_region = env::var("S3_REGION")
+            .unwrap_or_else(|_| "us-east-1".to_string());
+        let s3_access_key = env::var("S3_ACCESS_KEY").ok();
+        let s3_secret_key = env::var("S3_SECRET_KEY").ok();
+
+        let price_source = env::var("PRICE_SOURCE")
+            .unwrap_or_else(|_| "manual".to_string());
+        let quote_api_url = env::var("QUOTE_API_URL").ok();
+        let quote_api_key = env::var("QUOTE_API_KEY").ok();
+
         Ok(Config {
             database_url,
             jwt_secret,
@@ -50,6 +83,16 @@ impl Config {
             server_port,
             stripe_secret_key,
             stripe_webhook_secret,
+            storage_backend,
+            storage_path,
+            s3_endpoint,
+            s3_bucket,
+            s3_region,
+            s3_access_key,
+            s3_secret_key,
+            price_source,
+            quote_api_url,
+            quote_api_key,
         })
     }
 
@@ -63,6 +106,33 @@ impl Config {
             return Err("JWT_EXPIRATION_HOURS must be at least 1".to_string());
         }
 
+        match self.storage_backend.as_str() {
+            "local" => {}
+            "s3" => {
+                if self.s3_endpoint.is_none()
+                    || self.s3_bucket.is_none()
+                    || self.s3_access_key.is_none()
+                    || self.s3_secret_key.is_none()
+                {
+                    return Err(
+                        "S3_ENDPOINT, S3_BUCKET, S3_ACCESS_KEY and S3_SECRET_KEY must be set for the s3 storage backend"
+                            .to_string(),
+                    );
+                }
+            }
+            _ => return Err("STORAGE_BACKEND must be 'local' or 's3'".to_string()),
+        }
+
+        match self.price_source.as_str() {
+            "manual" => {}
+            "http" => {
+                if self.quote_api_url.is_none() {
+                    return Err("QUOTE_API_URL must be set for the http price source".to_string());
+                }
+            }
+            _ => return Err("PRICE_SOURCE must be 'manual' or 'http'".to_string()),
+        }
+
         Ok(())
     }
 }
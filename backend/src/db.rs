@@ -29,7 +29,7 @@ pub async fn run_migrations(pool: &PgPool) -> Result<(), sqlx::Error> {
     }
 
     let mut entries: Vec<_> = std::fs::read_dir(migrations_dir)
-        .map_err(|e| sqlx::Error::Io(e))?
+        .map_err(sqlx::Error::Io)?
         .filter_map(|e| e.ok())
         .filter(|e| {
             e.path()
@@ -50,7 +50,7 @@ pub async fn run_migrations(pool: &PgPool) -> Result<(), sqlx::Error> {
         tracing::info!("Running migration: {}", filename);
         
         let sql = std::fs::read_to_string(&path)
-            .map_err(|e| sqlx::Error::Io(e))?;
+            .map_err(sqlx::Error::Io)?;
         
         sqlx::raw_sql(&sql).execute(pool).await?;
         
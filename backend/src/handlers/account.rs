@@ -0,0 +1,172 @@
+use crate::{
+    error::{AppError, Result},
+    middleware::AuthUser,
+    models::{Account, CashMovement, CreateAccountRequest, CreateCashMovementRequest, UpdateAccountRequest},
+    repositories::AccountRepository,
+    AppState,
+};
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use rust_decimal::Decimal;
+use uuid::Uuid;
+
+/// Check an ISO 4217 style code and return it upper-cased
+pub(crate) fn validate_currency(code: &str) -> Result<String> {
+    if code.len() != 3 || !code.chars().all(|c| c.is_ascii_alphabetic()) {
+        return Err(AppError::ValidationError(
+            "Currency must be a 3-letter code".to_string(),
+        ));
+    }
+
+    Ok(code.to_uppercase())
+}
+
+fn validate_name(name: &str) -> Result<()> {
+    if name.trim().is_empty() || name.len() > 100 {
+        return Err(AppError::ValidationError(
+            "Account name must be 1 to 100 characters".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+fn validate_balance(balance: Option<Decimal>) -> Result<()> {
+    if balance.is_some_and(|b| b < Decimal::ZERO) {
+        return Err(AppError::ValidationError(
+            "Starting balance cannot be negative".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Create a new account
+pub async fn create_account(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+    Json(mut payload): Json<CreateAccountRequest>,
+) -> Result<Json<Account>> {
+    validate_name(&payload.name)?;
+    validate_balance(payload.starting_balance)?;
+    payload.base_currency = payload.base_currency.as_deref().map(validate_currency).transpose()?;
+
+    let account_repo = AccountRepository::new(state.db.clone());
+    let account = account_repo.create(user_id, payload).await?;
+
+    Ok(Json(account))
+}
+
+/// List accounts
+pub async fn list_accounts(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+) -> Result<Json<Vec<Account>>> {
+    let account_repo = AccountRepository::new(state.db.clone());
+    let accounts = account_repo.list(user_id).await?;
+
+    Ok(Json(accounts))
+}
+
+/// Get account by ID
+pub async fn get_account(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+    Path(account_id): Path<Uuid>,
+) -> Result<Json<Account>> {
+    let account_repo = AccountRepository::new(state.db.clone());
+
+    let account = account_repo
+        .get(account_id, user_id)
+        .await?
+        .ok_or(AppError::ValidationError("Account not found".to_string()))?;
+
+    Ok(Json(account))
+}
+
+/// Update account
+pub async fn update_account(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+    Path(account_id): Path<Uuid>,
+    Json(mut payload): Json<UpdateAccountRequest>,
+) -> Result<Json<Account>> {
+    if let Some(name) = &payload.name {
+        validate_name(name)?;
+    }
+    validate_balance(payload.starting_balance)?;
+    payload.base_currency = payload.base_currency.as_deref().map(validate_currency).transpose()?;
+
+    let account_repo = AccountRepository::new(state.db.clone());
+    let account = account_repo.update(account_id, user_id, payload).await?;
+
+    Ok(Json(account))
+}
+
+/// Delete account
+pub async fn delete_account(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+    Path(account_id): Path<Uuid>,
+) -> Result<StatusCode> {
+    let account_repo = AccountRepository::new(state.db.clone());
+    account_repo.delete(account_id, user_id).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// List cash movements of an account
+pub async fn list_cash_movements(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+    Path(account_id): Path<Uuid>,
+) -> Result<Json<Vec<CashMovement>>> {
+    let account_repo = AccountRepository::new(state.db.clone());
+    account_repo
+        .get(account_id, user_id)
+        .await?
+        .ok_or(AppError::ValidationError("Account not found".to_string()))?;
+
+    let movements = account_repo.list_movements(account_id, user_id).await?;
+
+    Ok(Json(movements))
+}
+
+/// Record a deposit, withdrawal, fee or interest payment
+pub async fn create_cash_movement(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+    Path(account_id): Path<Uuid>,
+    Json(payload): Json<CreateCashMovementRequest>,
+) -> Result<Json<CashMovement>> {
+    if !matches!(payload.kind.as_str(), "deposit" | "withdrawal" | "fee" | "interest") {
+        return Err(AppError::ValidationError(
+            "Kind must be 'deposit', 'withdrawal', 'fee' or 'interest'".to_string(),
+        ));
+    }
+    if payload.amount <= Decimal::ZERO {
+        return Err(AppError::ValidationError(
+            "Amount must be positive".to_string(),
+        ));
+    }
+
+    let account_repo = AccountRepository::new(state.db.clone());
+    let movement = account_repo.create_movement(account_id, user_id, payload).await?;
+
+    Ok(Json(movement))
+}
+
+/// Delete a cash movement
+pub async fn delete_cash_movement(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+    Path((account_id, movement_id)): Path<(Uuid, Uuid)>,
+) -> Result<StatusCode> {
+    let account_repo = AccountRepository::new(state.db.clone());
+    account_repo.delete_movement(movement_id, account_id, user_id).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -1,46 +1,209 @@
 use crate::{
-    error::Result,
+    error::{AppError, Result},
+    handlers::account::validate_currency,
     middleware::AuthUser,
-    models::TradeFilters,
-    repositories::TradeRepository,
-    services::{AnalyticsService, MistakeAnalysis, SetupPerformance, SymbolPerformance, TradeAnalytics},
+    models::{
+        AccountPerformanceQuery, CalendarQuery, EquityCurveQuery, JournalEntryQuery, RMultipleQuery, ReportingQuery, RiskMetricsQuery, TimeBreakdownQuery,
+        TradeFilters, UserSettings,
+    },
+    repositories::{AccountRepository, AnalyticsRepository, JournalRepository, TradeRepository, UserSettingsRepository},
+    services::{
+        AccountPerformance, AnalyticsService, CalendarMonth, EquityCurve, EquityInterval, ExcursionAnalytics, JournalAnalytics, MistakeAnalysis, OpenPositions, PlaybookPerformance, RMultipleAnalytics, RiskMetrics,
+        SetupPerformance, SymbolPerformance, TimeBreakdowns, TradeAnalytics, TradingSession,
+    },
     AppState,
 };
-use axum::{extract::State, Json};
+use axum::{
+    extract::{Query, State},
+    Json,
+};
+use chrono::{Months, NaiveDate, NaiveTime};
+use chrono_tz::Tz;
+use rust_decimal::Decimal;
+use uuid::Uuid;
+
+/// Restrict list filters to closed trades; pagination doesn't apply to
+/// aggregates
+fn closed_only(filters: TradeFilters) -> TradeFilters {
+    TradeFilters {
+        status: Some("closed".to_string()),
+        limit: None,
+        offset: None,
+        ..filters
+    }
+}
+
+/// Restrict list filters to open trades; pagination doesn't apply to
+/// aggregates
+fn open_only(filters: TradeFilters) -> TradeFilters {
+    TradeFilters {
+        status: Some("open".to_string()),
+        limit: None,
+        offset: None,
+        ..filters
+    }
+}
+
+/// Parse an IANA timezone name
+fn parse_timezone(name: &str) -> Result<Tz> {
+    name.parse()
+        .map_err(|_| AppError::ValidationError("Unknown timezone".to_string()))
+}
+
+/// Requested timezone, defaulting to the user's
+fn timezone(name: Option<&str>, settings: &UserSettings) -> Result<Tz> {
+    parse_timezone(name.unwrap_or(&settings.timezone))
+}
+
+/// Parse `name=HH:MM-HH:MM[@timezone]` sessions, reading hours without a
+/// timezone in `timezone`; defaults to the standard sessions
+fn parse_sessions(spec: Option<&str>, timezone: Tz) -> Result<Vec<TradingSession>> {
+    let Some(spec) = spec else {
+        return Ok(TradingSession::defaults());
+    };
+
+    spec.split(',')
+        .map(|session| {
+            let invalid = || {
+                AppError::ValidationError(format!(
+                    "Invalid session '{}'; expected name=HH:MM-HH:MM[@timezone]",
+                    session.trim()
+                ))
+            };
+            let (name, hours) = session.split_once('=').ok_or_else(invalid)?;
+            let (hours, zone) = match hours.split_once('@') {
+                Some((hours, zone)) => (hours, parse_timezone(zone.trim())?),
+                None => (hours, timezone),
+            };
+            let (start, end) = hours.split_once('-').ok_or_else(invalid)?;
+            let time = |t: &str| NaiveTime::parse_from_str(t.trim(), "%H:%M").map_err(|_| invalid());
+
+            let name = name.trim();
+            if name.is_empty() {
+                return Err(invalid());
+            }
+
+            Ok(TradingSession {
+                name: name.to_string(),
+                start: time(start)?,
+                end: time(end)?,
+                timezone: zone,
+            })
+        })
+        .collect()
+}
+
+/// Validated reporting currency, if one was requested or the user has one
+fn reporting_currency(query: &ReportingQuery, settings: &UserSettings) -> Result<Option<String>> {
+    query
+        .currency
+        .as_deref()
+        .or(settings.reporting_currency.as_deref())
+        .map(validate_currency)
+        .transpose()
+}
+
+/// Mark the open trades matching the filters to market through the
+/// configured price source, converting amounts at the latest rates
+async fn open_positions(
+    state: &AppState,
+    user_id: Uuid,
+    filters: TradeFilters,
+    currency: Option<&str>,
+) -> Result<OpenPositions> {
+    let trade_repo = TradeRepository::new(state.db.clone());
+    let trades = trade_repo.list(user_id, open_only(filters)).await?;
+
+    let mut symbols: Vec<String> = trades
+        .iter()
+        .filter(|trade| trade.underlying.is_none())
+        .map(|trade| trade.symbol.clone())
+        .collect();
+    symbols.sort();
+    symbols.dedup();
+    // An unreachable quote service leaves the positions unmarked rather
+    // than failing the request
+    let (quotes, quotes_unavailable) = match state.prices.quotes(user_id, &symbols).await {
+        Ok(quotes) => (quotes, false),
+        Err(e) => {
+            tracing::warn!("Failed to fetch quotes from the {} price source: {}", state.prices.name(), e);
+            (Vec::new(), true)
+        }
+    };
+
+    let rates = match currency {
+        Some(currency) => {
+            let currencies: Vec<String> = trades.iter().map(|trade| trade.currency.clone()).collect();
+            AnalyticsRepository::new(state.db.clone())
+                .latest_rates(user_id, &currencies, currency)
+                .await?
+        }
+        None => Vec::new(),
+    };
+
+    let mut positions = AnalyticsService::open_positions(&trades, &quotes, currency, rates)?;
+    positions.price_source = state.prices.name().to_string();
+    positions.quotes_unavailable = quotes_unavailable;
+    positions.currency = currency.map(str::to_string);
+
+    Ok(positions)
+}
 
 /// Get overall analytics
 pub async fn get_overview(
     State(state): State<AppState>,
     AuthUser(user_id): AuthUser,
+    Query(reporting): Query<ReportingQuery>,
+    Query(filters): Query<TradeFilters>,
 ) -> Result<Json<TradeAnalytics>> {
-    let trade_repo = TradeRepository::new(state.db.clone());
-    
-    // Get all closed trades
-    let filters = TradeFilters {
-        status: Some("closed".to_string()),
-        ..Default::default()
-    };
-    
-    let trades = trade_repo.list(user_id, filters).await?;
-    let analytics = AnalyticsService::calculate_overview(&trades)?;
+    let settings = UserSettingsRepository::new(state.db.clone()).get(user_id).await?;
+    let currency = reporting_currency(&reporting, &settings)?;
+
+    let analytics_repo = AnalyticsRepository::new(state.db.clone());
+    let mut analytics = analytics_repo
+        .overview(user_id, &closed_only(filters.clone()), currency.as_deref())
+        .await?;
+
+    let open = open_positions(&state, user_id, filters, currency.as_deref()).await?;
+    analytics.open_positions = open.positions.len() as i32;
+    analytics.unmarked_positions = open.unmarked;
+    analytics.unrealized_pnl = open.unrealized_pnl;
+    analytics.open_risk = open.open_risk;
+    analytics.fx_rates_used.extend(open.fx_rates_used);
 
     Ok(Json(analytics))
 }
 
+/// Get open trades marked to market, with exposure by symbol and
+/// direction and open risk
+pub async fn get_open_positions(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+    Query(reporting): Query<ReportingQuery>,
+    Query(filters): Query<TradeFilters>,
+) -> Result<Json<OpenPositions>> {
+    let settings = UserSettingsRepository::new(state.db.clone()).get(user_id).await?;
+    let currency = reporting_currency(&reporting, &settings)?;
+
+    let positions = open_positions(&state, user_id, filters, currency.as_deref()).await?;
+
+    Ok(Json(positions))
+}
+
 /// Get performance by symbol
 pub async fn get_by_symbol(
     State(state): State<AppState>,
     AuthUser(user_id): AuthUser,
+    Query(reporting): Query<ReportingQuery>,
+    Query(filters): Query<TradeFilters>,
 ) -> Result<Json<Vec<SymbolPerformance>>> {
-    let trade_repo = TradeRepository::new(state.db.clone());
-    
-    let filters = TradeFilters {
-        status: Some("closed".to_string()),
-        ..Default::default()
-    };
-    
-    let trades = trade_repo.list(user_id, filters).await?;
-    let performance = AnalyticsService::calculate_by_symbol(&trades)?;
+    let settings = UserSettingsRepository::new(state.db.clone()).get(user_id).await?;
+    let currency = reporting_currency(&reporting, &settings)?;
+
+    let analytics_repo = AnalyticsRepository::new(state.db.clone());
+    let performance = analytics_repo
+        .by_symbol(user_id, &closed_only(filters), currency.as_deref())
+        .await?;
 
     Ok(Json(performance))
 }
@@ -49,16 +212,34 @@ pub async fn get_by_symbol(
 pub async fn get_by_setup(
     State(state): State<AppState>,
     AuthUser(user_id): AuthUser,
+    Query(reporting): Query<ReportingQuery>,
+    Query(filters): Query<TradeFilters>,
 ) -> Result<Json<Vec<SetupPerformance>>> {
-    let trade_repo = TradeRepository::new(state.db.clone());
-    
-    let filters = TradeFilters {
-        status: Some("closed".to_string()),
-        ..Default::default()
-    };
-    
-    let trades = trade_repo.list(user_id, filters).await?;
-    let performance = AnalyticsService::calculate_by_setup(&trades)?;
+    let settings = UserSettingsRepository::new(state.db.clone()).get(user_id).await?;
+    let currency = reporting_currency(&reporting, &settings)?;
+
+    let analytics_repo = AnalyticsRepository::new(state.db.clone());
+    let performance = analytics_repo
+        .by_setup(user_id, &closed_only(filters), currency.as_deref())
+        .await?;
+
+    Ok(Json(performance))
+}
+
+/// Get performance by playbook, split by checklist compliance
+pub async fn get_by_playbook(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+    Query(reporting): Query<ReportingQuery>,
+    Query(filters): Query<TradeFilters>,
+) -> Result<Json<Vec<PlaybookPerformance>>> {
+    let settings = UserSettingsRepository::new(state.db.clone()).get(user_id).await?;
+    let currency = reporting_currency(&reporting, &settings)?;
+
+    let analytics_repo = AnalyticsRepository::new(state.db.clone());
+    let performance = analytics_repo
+        .by_playbook(user_id, &closed_only(filters), currency.as_deref())
+        .await?;
 
     Ok(Json(performance))
 }
@@ -67,17 +248,294 @@ pub async fn get_by_setup(
 pub async fn get_mistakes(
     State(state): State<AppState>,
     AuthUser(user_id): AuthUser,
+    Query(reporting): Query<ReportingQuery>,
+    Query(filters): Query<TradeFilters>,
 ) -> Result<Json<Vec<MistakeAnalysis>>> {
-    let trade_repo = TradeRepository::new(state.db.clone());
-    
-    let filters = TradeFilters {
-        status: Some("closed".to_string()),
-        ..Default::default()
-    };
-    
-    let trades = trade_repo.list(user_id, filters).await?;
-    let mistakes = AnalyticsService::analyze_mistakes(&trades)?;
+    let settings = UserSettingsRepository::new(state.db.clone()).get(user_id).await?;
+    let currency = reporting_currency(&reporting, &settings)?;
+
+    let analytics_repo = AnalyticsRepository::new(state.db.clone());
+    let mistakes = analytics_repo
+        .mistakes(user_id, &closed_only(filters), currency.as_deref())
+        .await?;
 
     Ok(Json(mistakes))
 }
 
+/// Get cumulative P&L with drawdowns, per trade or per calendar bucket
+pub async fn get_equity_curve(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+    Query(params): Query<EquityCurveQuery>,
+    Query(reporting): Query<ReportingQuery>,
+    Query(filters): Query<TradeFilters>,
+) -> Result<Json<EquityCurve>> {
+    let settings = UserSettingsRepository::new(state.db.clone()).get(user_id).await?;
+    let interval = EquityInterval::try_from(params.interval.as_deref().unwrap_or("trade"))?;
+    let timezone = timezone(params.timezone.as_deref(), &settings)?;
+    let currency = reporting_currency(&reporting, &settings)?;
+    let filters = closed_only(filters);
+
+    let analytics_repo = AnalyticsRepository::new(state.db.clone());
+    let fx_rates_used = analytics_repo
+        .rates_used(user_id, &filters, currency.as_deref())
+        .await?;
+    let buckets = analytics_repo
+        .equity_buckets(
+            user_id,
+            &filters,
+            interval,
+            timezone,
+            settings.day_rollover_hour,
+            currency.as_deref(),
+        )
+        .await?;
+
+    let mut curve = AnalyticsService::equity_curve(
+        buckets,
+        interval,
+        timezone,
+        params.starting_equity.unwrap_or_default(),
+    );
+    curve.currency = currency;
+    curve.fx_rates_used = fx_rates_used;
+
+    Ok(Json(curve))
+}
+
+/// Get risk-adjusted performance metrics
+pub async fn get_risk_metrics(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+    Query(params): Query<RiskMetricsQuery>,
+    Query(reporting): Query<ReportingQuery>,
+    Query(filters): Query<TradeFilters>,
+) -> Result<Json<RiskMetrics>> {
+    let settings = UserSettingsRepository::new(state.db.clone()).get(user_id).await?;
+    let timezone = timezone(params.timezone.as_deref(), &settings)?;
+    let currency = reporting_currency(&reporting, &settings)?;
+    let filters = closed_only(filters);
+
+    let analytics_repo = AnalyticsRepository::new(state.db.clone());
+    let fx_rates_used = analytics_repo
+        .rates_used(user_id, &filters, currency.as_deref())
+        .await?;
+    let trades = analytics_repo
+        .equity_buckets(
+            user_id,
+            &filters,
+            EquityInterval::Trade,
+            timezone,
+            settings.day_rollover_hour,
+            currency.as_deref(),
+        )
+        .await?;
+    let days = analytics_repo
+        .equity_buckets(
+            user_id,
+            &filters,
+            EquityInterval::Day,
+            timezone,
+            settings.day_rollover_hour,
+            currency.as_deref(),
+        )
+        .await?;
+
+    let mut metrics = AnalyticsService::risk_metrics(&trades, days, timezone, params.starting_equity);
+    metrics.currency = currency;
+    metrics.fx_rates_used = fx_rates_used;
+
+    Ok(Json(metrics))
+}
+
+/// Get average R, expectancy in R and the R distribution
+pub async fn get_r_multiples(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+    Query(params): Query<RMultipleQuery>,
+    Query(filters): Query<TradeFilters>,
+) -> Result<Json<RMultipleAnalytics>> {
+    let bucket_size = params.bucket_size.unwrap_or(Decimal::ONE);
+    if bucket_size <= Decimal::ZERO {
+        return Err(AppError::ValidationError(
+            "Bucket size must be positive".to_string(),
+        ));
+    }
+
+    let analytics_repo = AnalyticsRepository::new(state.db.clone());
+    let analytics = analytics_repo
+        .r_multiples(user_id, &closed_only(filters), bucket_size)
+        .await?;
+
+    Ok(Json(analytics))
+}
+
+/// Get MAE, MFE and entry/exit efficiency of closed trades for scatter plots
+pub async fn get_excursions(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+    Query(reporting): Query<ReportingQuery>,
+    Query(filters): Query<TradeFilters>,
+) -> Result<Json<ExcursionAnalytics>> {
+    let settings = UserSettingsRepository::new(state.db.clone()).get(user_id).await?;
+    let currency = reporting_currency(&reporting, &settings)?;
+
+    let analytics_repo = AnalyticsRepository::new(state.db.clone());
+    let analytics = analytics_repo
+        .excursions(user_id, &closed_only(filters), currency.as_deref())
+        .await?;
+
+    Ok(Json(analytics))
+}
+
+/// Get performance by entry hour, weekday, month and session, and by
+/// holding time
+pub async fn get_time_breakdowns(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+    Query(params): Query<TimeBreakdownQuery>,
+    Query(reporting): Query<ReportingQuery>,
+    Query(filters): Query<TradeFilters>,
+) -> Result<Json<TimeBreakdowns>> {
+    let settings = UserSettingsRepository::new(state.db.clone()).get(user_id).await?;
+    let timezone = timezone(params.timezone.as_deref(), &settings)?;
+    let sessions = parse_sessions(params.sessions.as_deref(), timezone)?;
+    let currency = reporting_currency(&reporting, &settings)?;
+    let filters = closed_only(filters);
+
+    let analytics_repo = AnalyticsRepository::new(state.db.clone());
+    let fx_rates_used = analytics_repo
+        .rates_used(user_id, &filters, currency.as_deref())
+        .await?;
+    let trades = analytics_repo
+        .timed_trades(user_id, &filters, currency.as_deref())
+        .await?;
+
+    let mut breakdowns = AnalyticsService::time_breakdowns(&trades, timezone, &sessions);
+    breakdowns.currency = currency;
+    breakdowns.fx_rates_used = fx_rates_used;
+
+    Ok(Json(breakdowns))
+}
+
+/// Get daily, weekly and monthly results of a month for a heatmap
+/// calendar. Trades count on the day of their exit (entry if none is
+/// recorded) in the user's timezone.
+pub async fn get_calendar(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+    Query(params): Query<CalendarQuery>,
+    Query(reporting): Query<ReportingQuery>,
+    Query(filters): Query<TradeFilters>,
+) -> Result<Json<CalendarMonth>> {
+    let settings = UserSettingsRepository::new(state.db.clone()).get(user_id).await?;
+    let first_day = NaiveDate::parse_from_str(&format!("{}-01", params.month), "%Y-%m-%d")
+        .map_err(|_| AppError::ValidationError("Month must be given as YYYY-MM".to_string()))?;
+    let timezone = timezone(params.timezone.as_deref(), &settings)?;
+    let currency = reporting_currency(&reporting, &settings)?;
+    let filters = closed_only(filters);
+
+    let analytics_repo = AnalyticsRepository::new(state.db.clone());
+    let fx_rates_used = analytics_repo
+        .rates_used(user_id, &filters, currency.as_deref())
+        .await?;
+    let buckets = analytics_repo
+        .calendar_buckets(
+            user_id,
+            &filters,
+            timezone,
+            settings.day_rollover_hour,
+            currency.as_deref(),
+            first_day,
+        )
+        .await?;
+
+    let journal_query = JournalEntryQuery {
+        from: Some(first_day),
+        to: first_day.checked_add_months(Months::new(1)).and_then(|next| next.pred_opt()),
+        limit: Some(31),
+        offset: None,
+    };
+    let journal_dates: Vec<NaiveDate> = JournalRepository::new(state.db.clone())
+        .list(user_id, &journal_query)
+        .await?
+        .into_iter()
+        .map(|entry| entry.entry_date)
+        .collect();
+
+    let mut calendar =
+        AnalyticsService::calendar(first_day, buckets, &journal_dates, timezone, settings.day_rollover_hour);
+    calendar.currency = currency;
+    calendar.fx_rates_used = fx_rates_used;
+
+    Ok(Json(calendar))
+}
+
+/// Relate journal days' plan adherence, mood and energy to the P&L of
+/// their linked closed trades
+pub async fn get_journal_analytics(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+    Query(params): Query<JournalEntryQuery>,
+    Query(reporting): Query<ReportingQuery>,
+    Query(filters): Query<TradeFilters>,
+) -> Result<Json<JournalAnalytics>> {
+    let settings = UserSettingsRepository::new(state.db.clone()).get(user_id).await?;
+    let currency = reporting_currency(&reporting, &settings)?;
+    let filters = closed_only(filters);
+
+    let analytics_repo = AnalyticsRepository::new(state.db.clone());
+    let fx_rates_used = analytics_repo
+        .rates_used(user_id, &filters, currency.as_deref())
+        .await?;
+    let days = analytics_repo
+        .journal_days(user_id, &filters, currency.as_deref(), params.from, params.to)
+        .await?;
+
+    let mut analytics = AnalyticsService::journal_analytics(days);
+    analytics.currency = currency;
+    analytics.fx_rates_used = fx_rates_used;
+
+    Ok(Json(analytics))
+}
+
+/// Get balances, returns and daily equity of every account; trades in
+/// other currencies are converted into the account's base currency
+pub async fn get_account_performance(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+    Query(params): Query<AccountPerformanceQuery>,
+) -> Result<Json<Vec<AccountPerformance>>> {
+    let settings = UserSettingsRepository::new(state.db.clone()).get(user_id).await?;
+    let timezone = timezone(params.timezone.as_deref(), &settings)?;
+
+    let account_repo = AccountRepository::new(state.db.clone());
+    let analytics_repo = AnalyticsRepository::new(state.db.clone());
+
+    let mut results = Vec::new();
+    for account in account_repo.list(user_id).await? {
+        let filters = closed_only(TradeFilters {
+            account_id: Some(account.id),
+            ..Default::default()
+        });
+        let currency = Some(account.base_currency.as_str());
+        let fx_rates_used = analytics_repo.rates_used(user_id, &filters, currency).await?;
+        let days = analytics_repo
+            .equity_buckets(
+                user_id,
+                &filters,
+                EquityInterval::Day,
+                timezone,
+                settings.day_rollover_hour,
+                currency,
+            )
+            .await?;
+        let movements = account_repo.list_movements(account.id, user_id).await?;
+
+        let mut performance = AnalyticsService::account_performance(&account, days, &movements, timezone);
+        performance.fx_rates_used = fx_rates_used;
+        results.push(performance);
+    }
+
+    Ok(Json(results))
+}
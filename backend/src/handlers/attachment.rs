@@ -0,0 +1,166 @@
+use crate::{
+    error::{AppError, Result},
+    middleware::AuthUser,
+    models::{Attachment, SubscriptionTier},
+    repositories::{AttachmentRepository, TradeRepository, UserRepository},
+    services::AttachmentService,
+    AppState,
+};
+use axum::{
+    body::Body,
+    extract::{Multipart, Path, State},
+    http::{header, StatusCode},
+    response::Response,
+    Json,
+};
+use uuid::Uuid;
+
+/// Attach files to a trade. Every multipart field with a file name is
+/// stored; other fields are ignored.
+pub async fn upload_attachments(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+    Path(trade_id): Path<Uuid>,
+    mut multipart: Multipart,
+) -> Result<Json<Vec<Attachment>>> {
+    let trade_repo = TradeRepository::new(state.db.clone());
+    trade_repo
+        .get(trade_id, user_id)
+        .await?
+        .ok_or(AppError::ValidationError("Trade not found".to_string()))?;
+
+    let user_repo = UserRepository::new(state.db.clone());
+    let user = user_repo
+        .find_by_id(user_id)
+        .await?
+        .ok_or(AppError::UserNotFound)?;
+    let tier = SubscriptionTier::from(user.subscription_tier);
+
+    let invalid = |e: axum::extract::multipart::MultipartError| {
+        AppError::ValidationError(format!("Invalid multipart body: {}", e.body_text()))
+    };
+    let mut files = Vec::new();
+    while let Some(field) = multipart.next_field().await.map_err(invalid)? {
+        let Some(file_name) = field.file_name().map(str::to_string) else {
+            continue;
+        };
+        files.push((file_name, field.bytes().await.map_err(invalid)?));
+    }
+
+    let attachment_repo = AttachmentRepository::new(state.db.clone());
+    let attachments =
+        AttachmentService::upload(&attachment_repo, state.blobs.as_ref(), user_id, &tier, trade_id, files).await?;
+
+    Ok(Json(attachments))
+}
+
+/// List attachments of a trade
+pub async fn list_attachments(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+    Path(trade_id): Path<Uuid>,
+) -> Result<Json<Vec<Attachment>>> {
+    let trade_repo = TradeRepository::new(state.db.clone());
+    trade_repo
+        .get(trade_id, user_id)
+        .await?
+        .ok_or(AppError::ValidationError("Trade not found".to_string()))?;
+
+    let attachment_repo = AttachmentRepository::new(state.db.clone());
+    let attachments = attachment_repo.list_for_trade(trade_id, user_id).await?;
+
+    Ok(Json(attachments))
+}
+
+/// Download an attachment's file
+pub async fn download_attachment(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+    Path((trade_id, attachment_id)): Path<(Uuid, Uuid)>,
+) -> Result<Response> {
+    let attachment_repo = AttachmentRepository::new(state.db.clone());
+    let attachment = attachment_repo
+        .get(attachment_id, trade_id, user_id)
+        .await?
+        .ok_or(AppError::ValidationError("Attachment not found".to_string()))?;
+
+    serve_blob(&state, &attachment.storage_key, &attachment.content_type, &attachment.file_name).await
+}
+
+/// Download the thumbnail of an image attachment
+pub async fn download_attachment_thumbnail(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+    Path((trade_id, attachment_id)): Path<(Uuid, Uuid)>,
+) -> Result<Response> {
+    let attachment_repo = AttachmentRepository::new(state.db.clone());
+    let attachment = attachment_repo
+        .get(attachment_id, trade_id, user_id)
+        .await?
+        .ok_or(AppError::ValidationError("Attachment not found".to_string()))?;
+    let thumbnail_key = attachment
+        .thumbnail_key
+        .as_deref()
+        .ok_or(AppError::ValidationError("Only image attachments have thumbnails".to_string()))?;
+
+    // Thumbnails of images with transparency are PNGs, the rest JPEGs
+    let content_type = if thumbnail_key.ends_with(".png") { "image/png" } else { "image/jpeg" };
+    let file_name = format!("thumbnail-{}", attachment.file_name);
+    serve_blob(&state, thumbnail_key, content_type, &file_name).await
+}
+
+/// Delete an attachment and its stored files
+pub async fn delete_attachment(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+    Path((trade_id, attachment_id)): Path<(Uuid, Uuid)>,
+) -> Result<StatusCode> {
+    let attachment_repo = AttachmentRepository::new(state.db.clone());
+    let attachment = attachment_repo.delete(attachment_id, trade_id, user_id).await?;
+
+    AttachmentService::delete_blobs(state.blobs.as_ref(), &AttachmentService::keys(&[attachment])).await;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn serve_blob(state: &AppState, key: &str, content_type: &str, file_name: &str) -> Result<Response> {
+    let data = state
+        .blobs
+        .get(key)
+        .await?
+        .ok_or_else(|| AppError::InternalServerError(format!("Attachment blob '{}' is missing", key)))?;
+
+    let content_type = if content_type.starts_with("text/") {
+        format!("{}; charset=utf-8", content_type)
+    } else {
+        content_type.to_string()
+    };
+
+    Response::builder()
+        .header(header::CONTENT_TYPE, content_type)
+        .header(header::CONTENT_DISPOSITION, content_disposition(file_name))
+        .header(header::X_CONTENT_TYPE_OPTIONS, "nosniff")
+        .header(header::CACHE_CONTROL, "private, max-age=86400")
+        .body(Body::from(data))
+        .map_err(|e| AppError::InternalServerError(format!("Failed to build attachment response: {}", e)))
+}
+
+/// Inline disposition with an ASCII file name and its UTF-8 original
+fn content_disposition(file_name: &str) -> String {
+    let ascii: String = file_name
+        .chars()
+        .map(|c| if (c.is_ascii_graphic() || c == ' ') && c != '"' && c != '\\' { c } else { '_' })
+        .collect();
+    let encoded: String = file_name
+        .bytes()
+        .map(|b| {
+            if b.is_ascii_alphanumeric() || b"-._~".contains(&b) {
+                (b as char).to_string()
+            } else {
+                format!("%{:02X}", b)
+            }
+        })
+        .collect();
+
+    format!("inline; filename=\"{}\"; filename*=UTF-8''{}", ascii, encoded)
+}
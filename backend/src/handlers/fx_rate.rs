@@ -0,0 +1,80 @@
+use crate::{
+    error::{AppError, Result},
+    handlers::account::validate_currency,
+    middleware::AuthUser,
+    models::{CreateFxRateRequest, FxImportReport, FxRate, FxRateQuery},
+    repositories::FxRateRepository,
+    services::FxService,
+    AppState,
+};
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use rust_decimal::Decimal;
+use uuid::Uuid;
+
+/// List FX rates, newest first
+pub async fn list_fx_rates(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+    Query(mut query): Query<FxRateQuery>,
+) -> Result<Json<Vec<FxRate>>> {
+    query.base_currency = query.base_currency.as_deref().map(validate_currency).transpose()?;
+    query.quote_currency = query.quote_currency.as_deref().map(validate_currency).transpose()?;
+
+    let fx_repo = FxRateRepository::new(state.db.clone());
+    let rates = fx_repo.list(user_id, &query).await?;
+
+    Ok(Json(rates))
+}
+
+/// Record the rate of a pair on a date, replacing an existing one
+pub async fn create_fx_rate(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+    Json(mut payload): Json<CreateFxRateRequest>,
+) -> Result<Json<FxRate>> {
+    payload.base_currency = validate_currency(&payload.base_currency)?;
+    payload.quote_currency = validate_currency(&payload.quote_currency)?;
+    if payload.base_currency == payload.quote_currency {
+        return Err(AppError::ValidationError(
+            "Base and quote currency must differ".to_string(),
+        ));
+    }
+    if payload.rate <= Decimal::ZERO {
+        return Err(AppError::ValidationError(
+            "Rate must be positive".to_string(),
+        ));
+    }
+
+    let fx_repo = FxRateRepository::new(state.db.clone());
+    let rate = fx_repo.upsert(user_id, &payload, "manual").await?;
+
+    Ok(Json(rate))
+}
+
+/// Import FX rates from a CSV file sent as the request body
+pub async fn import_fx_rates(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+    body: String,
+) -> Result<Json<FxImportReport>> {
+    let fx_repo = FxRateRepository::new(state.db.clone());
+    let report = FxService::import(&fx_repo, user_id, &body).await?;
+
+    Ok(Json(report))
+}
+
+/// Delete an FX rate
+pub async fn delete_fx_rate(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+    Path(rate_id): Path<Uuid>,
+) -> Result<StatusCode> {
+    let fx_repo = FxRateRepository::new(state.db.clone());
+    fx_repo.delete(rate_id, user_id).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
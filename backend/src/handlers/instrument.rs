@@ -0,0 +1,92 @@
+use crate::{
+    error::{AppError, Result},
+    handlers::account::validate_currency,
+    middleware::AuthUser,
+    models::{CreateInstrumentRequest, Instrument, UpdateInstrumentRequest},
+    repositories::InstrumentRepository,
+    AppState,
+};
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use rust_decimal::Decimal;
+use uuid::Uuid;
+
+const ASSET_CLASSES: &[&str] = &["stock", "etf", "future", "option", "forex", "crypto", "cfd"];
+
+fn validate_spec(asset_class: Option<&str>, multiplier: Option<Decimal>) -> Result<()> {
+    if asset_class.is_some_and(|c| !ASSET_CLASSES.contains(&c)) {
+        return Err(AppError::ValidationError(format!(
+            "Asset class must be one of: {}",
+            ASSET_CLASSES.join(", ")
+        )));
+    }
+    if multiplier.is_some_and(|m| m <= Decimal::ZERO) {
+        return Err(AppError::ValidationError(
+            "Multiplier must be positive".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+/// List instruments: the shared catalog merged with the user's own
+pub async fn list_instruments(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+) -> Result<Json<Vec<Instrument>>> {
+    let instrument_repo = InstrumentRepository::new(state.db.clone());
+    let instruments = instrument_repo.list(user_id).await?;
+
+    Ok(Json(instruments))
+}
+
+/// Create a per-user instrument, overriding the catalog entry if any
+pub async fn create_instrument(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+    Json(mut payload): Json<CreateInstrumentRequest>,
+) -> Result<Json<Instrument>> {
+    if payload.symbol.trim().is_empty() {
+        return Err(AppError::ValidationError(
+            "Symbol is required".to_string(),
+        ));
+    }
+    validate_spec(Some(&payload.asset_class), payload.multiplier)?;
+    payload.quote_currency = payload.quote_currency.as_deref().map(validate_currency).transpose()?;
+
+    let instrument_repo = InstrumentRepository::new(state.db.clone());
+    let instrument = instrument_repo.create(user_id, payload).await?;
+
+    Ok(Json(instrument))
+}
+
+/// Update a per-user instrument
+pub async fn update_instrument(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+    Path(instrument_id): Path<Uuid>,
+    Json(mut payload): Json<UpdateInstrumentRequest>,
+) -> Result<Json<Instrument>> {
+    validate_spec(payload.asset_class.as_deref(), payload.multiplier)?;
+    payload.quote_currency = payload.quote_currency.as_deref().map(validate_currency).transpose()?;
+
+    let instrument_repo = InstrumentRepository::new(state.db.clone());
+    let instrument = instrument_repo.update(instrument_id, user_id, payload).await?;
+
+    Ok(Json(instrument))
+}
+
+/// Delete a per-user instrument
+pub async fn delete_instrument(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+    Path(instrument_id): Path<Uuid>,
+) -> Result<StatusCode> {
+    let instrument_repo = InstrumentRepository::new(state.db.clone());
+    instrument_repo.delete(instrument_id, user_id).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,96 @@
+use crate::{
+    error::{AppError, Result},
+    middleware::AuthUser,
+    models::{CreateJournalEntryRequest, JournalEntry, JournalEntryQuery, UpdateJournalEntryRequest},
+    repositories::JournalRepository,
+    AppState,
+};
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use uuid::Uuid;
+
+fn validate_scores(mood: Option<i16>, energy: Option<i16>) -> Result<()> {
+    if mood.is_some_and(|m| !(1..=5).contains(&m)) {
+        return Err(AppError::ValidationError(
+            "Mood must be between 1 and 5".to_string(),
+        ));
+    }
+    if energy.is_some_and(|e| !(1..=5).contains(&e)) {
+        return Err(AppError::ValidationError(
+            "Energy must be between 1 and 5".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+/// List journal entries, newest day first
+pub async fn list_journal_entries(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+    Query(query): Query<JournalEntryQuery>,
+) -> Result<Json<Vec<JournalEntry>>> {
+    let journal_repo = JournalRepository::new(state.db.clone());
+    let entries = journal_repo.list(user_id, &query).await?;
+
+    Ok(Json(entries))
+}
+
+/// Create the journal entry of a day
+pub async fn create_journal_entry(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+    Json(payload): Json<CreateJournalEntryRequest>,
+) -> Result<Json<JournalEntry>> {
+    validate_scores(payload.mood, payload.energy)?;
+
+    let journal_repo = JournalRepository::new(state.db.clone());
+    let entry = journal_repo.create(user_id, payload).await?;
+
+    Ok(Json(entry))
+}
+
+/// Get a journal entry
+pub async fn get_journal_entry(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+    Path(entry_id): Path<Uuid>,
+) -> Result<Json<JournalEntry>> {
+    let journal_repo = JournalRepository::new(state.db.clone());
+    let entry = journal_repo
+        .get(entry_id, user_id)
+        .await?
+        .ok_or(AppError::ValidationError("Journal entry not found".to_string()))?;
+
+    Ok(Json(entry))
+}
+
+/// Update a journal entry
+pub async fn update_journal_entry(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+    Path(entry_id): Path<Uuid>,
+    Json(payload): Json<UpdateJournalEntryRequest>,
+) -> Result<Json<JournalEntry>> {
+    validate_scores(payload.mood, payload.energy)?;
+
+    let journal_repo = JournalRepository::new(state.db.clone());
+    let entry = journal_repo.update(entry_id, user_id, payload).await?;
+
+    Ok(Json(entry))
+}
+
+/// Delete a journal entry
+pub async fn delete_journal_entry(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+    Path(entry_id): Path<Uuid>,
+) -> Result<StatusCode> {
+    let journal_repo = JournalRepository::new(state.db.clone());
+    journal_repo.delete(entry_id, user_id).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -1,10 +1,50 @@
+pub mod account;
 pub mod analytics;
+pub mod attachment;
 pub mod auth;
+pub mod fx_rate;
+pub mod instrument;
+pub mod journal;
+pub mod order;
+pub mod playbook;
+pub mod price_bar;
+pub mod quote;
 pub mod subscription;
 pub mod trade;
+pub mod user_settings;
+pub mod vocabulary;
 
-pub use analytics::{get_by_setup, get_by_symbol, get_mistakes, get_overview};
+pub use account::{
+    create_account, create_cash_movement, delete_account, delete_cash_movement, get_account,
+    list_accounts, list_cash_movements, update_account,
+};
+pub use analytics::{
+    get_account_performance, get_by_playbook, get_by_setup, get_by_symbol, get_calendar, get_equity_curve,
+    get_excursions, get_journal_analytics, get_mistakes, get_open_positions, get_overview, get_r_multiples, get_risk_metrics,
+    get_time_breakdowns,
+};
+pub use attachment::{
+    delete_attachment, download_attachment, download_attachment_thumbnail, list_attachments,
+    upload_attachments,
+};
 pub use auth::{login, me, register};
+pub use fx_rate::{create_fx_rate, delete_fx_rate, import_fx_rates, list_fx_rates};
+pub use instrument::{create_instrument, delete_instrument, list_instruments, update_instrument};
+pub use journal::{
+    create_journal_entry, delete_journal_entry, get_journal_entry, list_journal_entries, update_journal_entry,
+};
+pub use order::{cancel_order, create_order, expire_orders, fill_order, get_order, list_orders};
+pub use playbook::{create_playbook, delete_playbook, get_playbook, list_playbooks, update_playbook};
+pub use price_bar::{delete_price_bars, import_price_bars, list_price_bars};
+pub use quote::{delete_quote, import_quotes, list_quotes, set_quote};
 pub use subscription::{create_checkout_session, handle_stripe_webhook};
-pub use trade::{create_trade, delete_trade, get_trade, list_trades, update_trade};
-
+pub use trade::{
+    close_option_leg, create_trade, delete_trade, expire_options, export_trades, get_trade, get_trade_history,
+    get_trade_replay, import_trades, list_trade_executions, list_trade_legs, list_trades, restore_trade_revision,
+    update_trade,
+};
+pub use user_settings::{get_settings, update_settings};
+pub use vocabulary::{
+    create_vocabulary_term, delete_vocabulary_term, get_vocabulary, list_vocabulary_values,
+    merge_vocabulary_values, update_vocabulary, update_vocabulary_term,
+};
@@ -0,0 +1,185 @@
+use crate::{
+    error::{AppError, Result},
+    handlers::{account::validate_currency, playbook::clean_list},
+    middleware::AuthUser,
+    models::{
+        CancelOrderRequest, CreateOrderRequest, ExpireOrdersRequest, FillOrderRequest, OrderQuery, OrderWithTrade,
+        ORDER_TYPES,
+    },
+    repositories::{OrderRepository, UserSettingsRepository},
+    AppState,
+};
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use chrono::Utc;
+use rust_decimal::Decimal;
+use uuid::Uuid;
+
+const ORDER_STATUSES: &[&str] = &["pending", "filled", "cancelled", "expired"];
+
+fn validate_price(name: &str, price: Option<Decimal>) -> Result<()> {
+    match price {
+        Some(price) if price <= Decimal::ZERO => Err(AppError::ValidationError(format!("{} must be positive", name))),
+        _ => Ok(()),
+    }
+}
+
+/// Check the order type has exactly the prices it needs
+fn validate_order(payload: &CreateOrderRequest) -> Result<()> {
+    if !ORDER_TYPES.contains(&payload.order_type.as_str()) {
+        return Err(AppError::ValidationError(format!(
+            "Order type must be one of: {}",
+            ORDER_TYPES.join(", ")
+        )));
+    }
+    if payload.direction != "long" && payload.direction != "short" {
+        return Err(AppError::ValidationError(
+            "Direction must be 'long' or 'short'".to_string(),
+        ));
+    }
+    if payload.quantity <= Decimal::ZERO {
+        return Err(AppError::ValidationError(
+            "Quantity must be positive".to_string(),
+        ));
+    }
+
+    let needs_limit = matches!(payload.order_type.as_str(), "limit" | "stop_limit");
+    let needs_stop = matches!(payload.order_type.as_str(), "stop" | "stop_limit");
+    if needs_limit != payload.limit_price.is_some() {
+        return Err(AppError::ValidationError(format!(
+            "Limit price is {} for {} orders",
+            if needs_limit { "required" } else { "not allowed" },
+            payload.order_type
+        )));
+    }
+    if needs_stop != payload.stop_price.is_some() {
+        return Err(AppError::ValidationError(format!(
+            "Stop price is {} for {} orders",
+            if needs_stop { "required" } else { "not allowed" },
+            payload.order_type
+        )));
+    }
+    validate_price("Limit price", payload.limit_price)?;
+    validate_price("Stop price", payload.stop_price)?;
+    validate_price("Entry price", payload.entry_price)?;
+
+    let placed_at = payload.placed_at.unwrap_or_else(Utc::now);
+    if payload.expires_at.is_some_and(|expires_at| expires_at <= placed_at) {
+        return Err(AppError::ValidationError(
+            "Expiry must be after the order is placed".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+/// List orders with their trades
+pub async fn list_orders(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+    Query(query): Query<OrderQuery>,
+) -> Result<Json<Vec<OrderWithTrade>>> {
+    if let Some(status) = query.status.as_deref().filter(|status| !ORDER_STATUSES.contains(status)) {
+        return Err(AppError::ValidationError(format!("Unknown order status '{}'", status)));
+    }
+
+    let order_repo = OrderRepository::new(state.db.clone());
+    let orders = order_repo.list(user_id, &query).await?;
+
+    Ok(Json(orders))
+}
+
+/// Place an order; its trade stays pending until the order fills
+pub async fn create_order(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+    Json(mut payload): Json<CreateOrderRequest>,
+) -> Result<Json<OrderWithTrade>> {
+    validate_order(&payload)?;
+
+    payload.currency = payload.currency.as_deref().map(validate_currency).transpose()?;
+    payload.checklist_met = payload.checklist_met.map(clean_list);
+
+    // Same defaults as trades; fees are recorded with the fill
+    let settings = UserSettingsRepository::new(state.db.clone()).get(user_id).await?;
+    payload.broker = payload.broker.or(settings.default_broker);
+    payload.account_id = payload.account_id.or(settings.default_account_id);
+    if payload.stop_loss.is_none() {
+        payload.planned_risk = payload.planned_risk.or(settings.risk_per_trade);
+    }
+
+    let order_repo = OrderRepository::new(state.db.clone());
+    let order = order_repo.create(user_id, payload).await?;
+
+    Ok(Json(order))
+}
+
+/// Get an order with its trade
+pub async fn get_order(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+    Path(order_id): Path<Uuid>,
+) -> Result<Json<OrderWithTrade>> {
+    let order_repo = OrderRepository::new(state.db.clone());
+    let order = order_repo
+        .get(order_id, user_id)
+        .await?
+        .ok_or(AppError::ValidationError("Order not found".to_string()))?;
+
+    Ok(Json(order))
+}
+
+/// Fill a pending order, opening its trade
+pub async fn fill_order(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+    Path(order_id): Path<Uuid>,
+    Json(mut payload): Json<FillOrderRequest>,
+) -> Result<Json<OrderWithTrade>> {
+    let settings = UserSettingsRepository::new(state.db.clone()).get(user_id).await?;
+    payload.fees = payload.fees.or(settings.default_fees);
+    if payload.fees.is_some_and(|fees| fees < Decimal::ZERO) {
+        return Err(AppError::ValidationError(
+            "Fees must not be negative".to_string(),
+        ));
+    }
+
+    let order_repo = OrderRepository::new(state.db.clone());
+    let order = order_repo.fill(order_id, user_id, payload).await?;
+
+    Ok(Json(order))
+}
+
+/// Cancel a pending order
+pub async fn cancel_order(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+    Path(order_id): Path<Uuid>,
+    Json(mut payload): Json<CancelOrderRequest>,
+) -> Result<Json<OrderWithTrade>> {
+    payload.reason = payload
+        .reason
+        .map(|reason| reason.trim().to_string())
+        .filter(|reason| !reason.is_empty());
+
+    let order_repo = OrderRepository::new(state.db.clone());
+    let order = order_repo.cancel(order_id, user_id, payload).await?;
+
+    Ok(Json(order))
+}
+
+/// Expire every pending order past its expiry
+pub async fn expire_orders(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+    Json(payload): Json<ExpireOrdersRequest>,
+) -> Result<Json<Vec<OrderWithTrade>>> {
+    let as_of = payload.as_of.unwrap_or_else(Utc::now);
+
+    let order_repo = OrderRepository::new(state.db.clone());
+    let orders = order_repo.expire(user_id, as_of).await?;
+
+    Ok(Json(orders))
+}
@@ -0,0 +1,106 @@
+use crate::{
+    error::{AppError, Result},
+    middleware::AuthUser,
+    models::{CreatePlaybookRequest, Playbook, UpdatePlaybookRequest},
+    repositories::PlaybookRepository,
+    AppState,
+};
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use uuid::Uuid;
+
+fn validate_name(name: &str) -> Result<String> {
+    let name = name.trim();
+    if name.is_empty() || name.chars().count() > 100 {
+        return Err(AppError::ValidationError(
+            "Name must be between 1 and 100 characters".to_string(),
+        ));
+    }
+
+    Ok(name.to_string())
+}
+
+/// Trim checklist items and drop empty and repeated ones, keeping order
+pub(crate) fn clean_list(items: Vec<String>) -> Vec<String> {
+    let mut cleaned: Vec<String> = Vec::with_capacity(items.len());
+    for item in items {
+        let item = item.trim();
+        if !item.is_empty() && !cleaned.iter().any(|c| c == item) {
+            cleaned.push(item.to_string());
+        }
+    }
+
+    cleaned
+}
+
+/// List playbooks
+pub async fn list_playbooks(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+) -> Result<Json<Vec<Playbook>>> {
+    let playbook_repo = PlaybookRepository::new(state.db.clone());
+    let playbooks = playbook_repo.list(user_id).await?;
+
+    Ok(Json(playbooks))
+}
+
+/// Create a playbook
+pub async fn create_playbook(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+    Json(mut payload): Json<CreatePlaybookRequest>,
+) -> Result<Json<Playbook>> {
+    payload.name = validate_name(&payload.name)?;
+    payload.checklist = payload.checklist.map(clean_list);
+
+    let playbook_repo = PlaybookRepository::new(state.db.clone());
+    let playbook = playbook_repo.create(user_id, payload).await?;
+
+    Ok(Json(playbook))
+}
+
+/// Get a playbook
+pub async fn get_playbook(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+    Path(playbook_id): Path<Uuid>,
+) -> Result<Json<Playbook>> {
+    let playbook_repo = PlaybookRepository::new(state.db.clone());
+    let playbook = playbook_repo
+        .get(playbook_id, user_id)
+        .await?
+        .ok_or(AppError::ValidationError("Playbook not found".to_string()))?;
+
+    Ok(Json(playbook))
+}
+
+/// Update a playbook; renaming also renames the setup type of its trades
+pub async fn update_playbook(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+    Path(playbook_id): Path<Uuid>,
+    Json(mut payload): Json<UpdatePlaybookRequest>,
+) -> Result<Json<Playbook>> {
+    payload.name = payload.name.as_deref().map(validate_name).transpose()?;
+    payload.checklist = payload.checklist.map(clean_list);
+
+    let playbook_repo = PlaybookRepository::new(state.db.clone());
+    let playbook = playbook_repo.update(playbook_id, user_id, payload).await?;
+
+    Ok(Json(playbook))
+}
+
+/// Delete a playbook
+pub async fn delete_playbook(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+    Path(playbook_id): Path<Uuid>,
+) -> Result<StatusCode> {
+    let playbook_repo = PlaybookRepository::new(state.db.clone());
+    playbook_repo.delete(playbook_id, user_id).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
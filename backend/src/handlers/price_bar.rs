@@ -0,0 +1,101 @@
+use crate::{
+    error::{AppError, Result},
+    middleware::AuthUser,
+    models::{PriceBar, PriceBarImportQuery, PriceBarImportReport, PriceBarQuery, TIMEFRAMES},
+    repositories::{PriceBarRepository, TradeRepository},
+    services::PriceBarService,
+    AppState,
+};
+use axum::{
+    body::Bytes,
+    extract::{Query, State},
+    http::StatusCode,
+    Json,
+};
+use chrono::Duration;
+
+fn validate_symbol(symbol: &str) -> Result<String> {
+    let symbol = symbol.trim();
+    if symbol.is_empty() || symbol.len() > 50 {
+        return Err(AppError::ValidationError(
+            "Symbol must be 1 to 50 characters".to_string(),
+        ));
+    }
+
+    Ok(symbol.to_string())
+}
+
+pub(crate) fn validate_timeframe(timeframe: &str) -> Result<()> {
+    if !TIMEFRAMES.contains(&timeframe) {
+        return Err(AppError::ValidationError(format!(
+            "Timeframe must be one of: {}",
+            TIMEFRAMES.join(", ")
+        )));
+    }
+
+    Ok(())
+}
+
+/// Import OHLC bars for a symbol and timeframe from a CSV or Parquet file
+/// sent as the request body
+pub async fn import_price_bars(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+    Query(params): Query<PriceBarImportQuery>,
+    body: Bytes,
+) -> Result<Json<PriceBarImportReport>> {
+    let symbol = validate_symbol(&params.symbol)?;
+    validate_timeframe(&params.timeframe)?;
+
+    let bar_repo = PriceBarRepository::new(state.db.clone());
+    let trade_repo = TradeRepository::new(state.db.clone());
+    let report = PriceBarService::import(&bar_repo, &trade_repo, user_id, &symbol, &params.timeframe, body).await?;
+
+    Ok(Json(report))
+}
+
+/// List stored bars of a symbol
+pub async fn list_price_bars(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+    Query(mut query): Query<PriceBarQuery>,
+) -> Result<Json<Vec<PriceBar>>> {
+    query.symbol = validate_symbol(&query.symbol)?;
+    if let Some(timeframe) = &query.timeframe {
+        validate_timeframe(timeframe)?;
+    }
+    if query.limit.is_some_and(|limit| !(1..=10_000).contains(&limit)) {
+        return Err(AppError::ValidationError(
+            "Limit must be between 1 and 10000".to_string(),
+        ));
+    }
+
+    let bar_repo = PriceBarRepository::new(state.db.clone());
+    let bars = bar_repo.list(user_id, &query).await?;
+
+    Ok(Json(bars))
+}
+
+/// Delete stored bars of a symbol and recalculate the excursions of the
+/// trades they covered
+pub async fn delete_price_bars(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+    Query(mut query): Query<PriceBarQuery>,
+) -> Result<StatusCode> {
+    query.symbol = validate_symbol(&query.symbol)?;
+    if let Some(timeframe) = &query.timeframe {
+        validate_timeframe(timeframe)?;
+    }
+
+    let bar_repo = PriceBarRepository::new(state.db.clone());
+    bar_repo.delete(user_id, &query).await?;
+
+    // A deleted bar can cover trades up to a day past its start
+    let trade_repo = TradeRepository::new(state.db.clone());
+    trade_repo
+        .refresh_excursions(user_id, &query.symbol, query.from, query.to.map(|to| to + Duration::days(1)))
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
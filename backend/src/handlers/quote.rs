@@ -0,0 +1,92 @@
+use crate::{
+    error::{AppError, Result},
+    middleware::AuthUser,
+    models::{Quote, QuoteImportReport, SetQuoteRequest},
+    repositories::QuoteRepository,
+    services::QuoteService,
+    AppState,
+};
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use chrono::Utc;
+use rust_decimal::Decimal;
+
+fn validate_symbol(symbol: &str) -> Result<String> {
+    let symbol = symbol.trim();
+    if symbol.is_empty() || symbol.chars().count() > 50 {
+        return Err(AppError::ValidationError(
+            "Symbol must be between 1 and 50 characters".to_string(),
+        ));
+    }
+
+    Ok(symbol.to_string())
+}
+
+/// List the prices entered or imported for open trades
+pub async fn list_quotes(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+) -> Result<Json<Vec<Quote>>> {
+    let quote_repo = QuoteRepository::new(state.db.clone());
+    let quotes = quote_repo.list(user_id).await?;
+
+    Ok(Json(quotes))
+}
+
+/// Set the price of a symbol, unless a newer one is already stored
+pub async fn set_quote(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+    Path(symbol): Path<String>,
+    Json(payload): Json<SetQuoteRequest>,
+) -> Result<Json<Quote>> {
+    let symbol = validate_symbol(&symbol)?;
+    if payload.price <= Decimal::ZERO {
+        return Err(AppError::ValidationError(
+            "Price must be positive".to_string(),
+        ));
+    }
+
+    let quote = Quote {
+        symbol,
+        price: payload.price,
+        as_of: payload.as_of.unwrap_or_else(Utc::now),
+    };
+
+    let quote_repo = QuoteRepository::new(state.db.clone());
+    if !quote_repo.upsert(user_id, &quote, "manual").await? {
+        return Err(AppError::ValidationError(format!(
+            "A newer price of {} is already stored",
+            quote.symbol
+        )));
+    }
+
+    Ok(Json(quote))
+}
+
+/// Import prices from a CSV file sent as the request body
+pub async fn import_quotes(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+    body: String,
+) -> Result<Json<QuoteImportReport>> {
+    let quote_repo = QuoteRepository::new(state.db.clone());
+    let report = QuoteService::import(&quote_repo, user_id, &body).await?;
+
+    Ok(Json(report))
+}
+
+/// Delete the price of a symbol
+pub async fn delete_quote(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+    Path(symbol): Path<String>,
+) -> Result<StatusCode> {
+    let quote_repo = QuoteRepository::new(state.db.clone());
+    quote_repo.delete(user_id, symbol.trim()).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
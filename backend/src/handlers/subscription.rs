@@ -45,7 +45,7 @@ pub async fn create_checkout_session(
         
         // Update user with customer ID
         user_repo
-            .update_stripe_customer(user_id, &customer.id.to_string())
+            .update_stripe_customer(user_id, customer.id.as_ref())
             .await?;
 
         customer.id.to_string()
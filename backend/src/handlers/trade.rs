@@ -1,28 +1,84 @@
 use crate::{
     error::{AppError, Result},
+    handlers::{account::validate_currency, playbook::clean_list, price_bar::validate_timeframe},
+    importers,
     middleware::AuthUser,
-    models::{CreateTradeRequest, Trade, TradeFilters, UpdateTradeRequest},
-    repositories::TradeRepository,
+    models::{
+        timeframe_duration, CloseOptionLegRequest, CreateTradeRequest, ExpireOptionsRequest, ExportQuery,
+        ImportQuery, ImportReport, OptionLeg, ReplayLevel, ReplayMarker, ReplayQuery, Trade, TradeExecution,
+        TradeFilters, TradeReplay, TradeRevision, UpdateTradeRequest,
+    },
+    repositories::{
+        AttachmentRepository, ExecutionRepository, InstrumentRepository, OptionLegRepository, PriceBarRepository,
+        TradeRepository, TradeRevisionRepository, UserSettingsRepository,
+    },
+    services::{AttachmentService, ExportFormat, ExportService, ImportService},
     AppState,
 };
 use axum::{
     extract::{Path, Query, State},
-    http::StatusCode,
+    http::{header, StatusCode},
+    response::Response,
     Json,
 };
+use chrono::Utc;
 use uuid::Uuid;
 
+/// Most bars a replay returns
+const MAX_REPLAY_BARS: i64 = 5000;
+
+/// Strategies a trade's option legs can be grouped as
+const STRATEGIES: &[&str] = &[
+    "single",
+    "vertical",
+    "iron_condor",
+    "iron_butterfly",
+    "butterfly",
+    "straddle",
+    "strangle",
+    "calendar",
+    "diagonal",
+    "covered_call",
+    "protective_put",
+    "collar",
+    "custom",
+];
+
+fn validate_strategy(strategy: Option<&str>) -> Result<()> {
+    match strategy {
+        Some(strategy) if !STRATEGIES.contains(&strategy) => Err(AppError::ValidationError(format!(
+            "Strategy must be one of: {}",
+            STRATEGIES.join(", ")
+        ))),
+        _ => Ok(()),
+    }
+}
+
 /// Create a new trade
 pub async fn create_trade(
     State(state): State<AppState>,
     AuthUser(user_id): AuthUser,
-    Json(payload): Json<CreateTradeRequest>,
+    Json(mut payload): Json<CreateTradeRequest>,
 ) -> Result<Json<Trade>> {
-    // Validate direction
-    if payload.direction != "long" && payload.direction != "short" {
-        return Err(AppError::ValidationError(
-            "Direction must be 'long' or 'short'".to_string(),
-        ));
+    payload.validate()?;
+    validate_strategy(payload.strategy.as_deref())?;
+
+    payload.currency = payload.currency.as_deref().map(validate_currency).transpose()?;
+    payload.fee_currency = payload.fee_currency.as_deref().map(validate_currency).transpose()?;
+    payload.checklist_met = payload.checklist_met.map(clean_list);
+
+    // Fill in the user's defaults; fills and legs carry their own fees, and
+    // a stop defines the risk
+    let settings = UserSettingsRepository::new(state.db.clone()).get(user_id).await?;
+    payload.broker = payload.broker.or(settings.default_broker);
+    payload.account_id = payload.account_id.or(settings.default_account_id);
+    let has_executions = payload.executions.as_ref().is_some_and(|e| !e.is_empty());
+    let has_legs = payload.legs.as_ref().is_some_and(|l| !l.is_empty());
+    if !has_executions && !has_legs {
+        payload.fees = payload.fees.or(settings.default_fees);
+    }
+    if payload.initial_stop.is_none() && payload.stop_loss.is_none() {
+        payload.planned_risk = payload.planned_risk.or(settings.risk_per_trade);
     }
 
     let trade_repo = TradeRepository::new(state.db.clone());
@@ -64,23 +120,254 @@ pub async fn update_trade(
     State(state): State<AppState>,
     AuthUser(user_id): AuthUser,
     Path(trade_id): Path<Uuid>,
-    Json(payload): Json<UpdateTradeRequest>,
+    Json(mut payload): Json<UpdateTradeRequest>,
 ) -> Result<Json<Trade>> {
+    payload.currency = payload.currency.as_deref().map(validate_currency).transpose()?;
+    payload.fee_currency = payload.fee_currency.as_deref().map(validate_currency).transpose()?;
+    validate_strategy(payload.strategy.as_deref())?;
+    payload.checklist_met = payload.checklist_met.map(clean_list);
+    // Pending, cancelled and expired follow the trade's order
+    if payload.status.as_deref().is_some_and(|status| status != "open" && status != "closed") {
+        return Err(AppError::ValidationError(
+            "Status must be 'open' or 'closed'; orders manage the others".to_string(),
+        ));
+    }
+
     let trade_repo = TradeRepository::new(state.db.clone());
     let trade = trade_repo.update(trade_id, user_id, payload).await?;
 
     Ok(Json(trade))
 }
 
+/// List the recorded changes of a trade, newest first; available after
+/// the trade is deleted
+pub async fn get_trade_history(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+    Path(trade_id): Path<Uuid>,
+) -> Result<Json<Vec<TradeRevision>>> {
+    let revision_repo = TradeRevisionRepository::new(state.db.clone());
+    let revisions = revision_repo.list(trade_id, user_id).await?;
+
+    Ok(Json(revisions))
+}
+
+/// Bring a trade back to how it was right after a revision
+pub async fn restore_trade_revision(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+    Path((trade_id, revision)): Path<(Uuid, i32)>,
+) -> Result<Json<Trade>> {
+    let trade_repo = TradeRepository::new(state.db.clone());
+    let trade = trade_repo.restore(trade_id, user_id, revision).await?;
+
+    Ok(Json(trade))
+}
+
 /// Delete trade
 pub async fn delete_trade(
     State(state): State<AppState>,
     AuthUser(user_id): AuthUser,
     Path(trade_id): Path<Uuid>,
 ) -> Result<StatusCode> {
+    // Attachment records go with the trade; their files are removed after
+    let attachment_repo = AttachmentRepository::new(state.db.clone());
+    let attachments = attachment_repo.list_for_trade(trade_id, user_id).await?;
+
     let trade_repo = TradeRepository::new(state.db.clone());
     trade_repo.delete(trade_id, user_id).await?;
 
+    AttachmentService::delete_blobs(state.blobs.as_ref(), &AttachmentService::keys(&attachments)).await;
+
     Ok(StatusCode::NO_CONTENT)
 }
 
+/// List executions of a trade
+pub async fn list_trade_executions(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+    Path(trade_id): Path<Uuid>,
+) -> Result<Json<Vec<TradeExecution>>> {
+    let trade_repo = TradeRepository::new(state.db.clone());
+    trade_repo
+        .get(trade_id, user_id)
+        .await?
+        .ok_or(AppError::ValidationError("Trade not found".to_string()))?;
+
+    let execution_repo = ExecutionRepository::new(state.db.clone());
+    let executions = execution_repo.list_for_trade(trade_id, user_id).await?;
+
+    Ok(Json(executions))
+}
+
+/// List option legs of a trade
+pub async fn list_trade_legs(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+    Path(trade_id): Path<Uuid>,
+) -> Result<Json<Vec<OptionLeg>>> {
+    let trade_repo = TradeRepository::new(state.db.clone());
+    trade_repo
+        .get(trade_id, user_id)
+        .await?
+        .ok_or(AppError::ValidationError("Trade not found".to_string()))?;
+
+    let leg_repo = OptionLegRepository::new(state.db.clone());
+    let legs = leg_repo.list_for_trade(trade_id, user_id).await?;
+
+    Ok(Json(legs))
+}
+
+/// Get the stored bars around a trade with its fills, stop and targets
+/// overlaid; open trades run up to now
+pub async fn get_trade_replay(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+    Path(trade_id): Path<Uuid>,
+    Query(params): Query<ReplayQuery>,
+) -> Result<Json<TradeReplay>> {
+    let padding = params.padding.unwrap_or(30);
+    if !(0..=1000).contains(&padding) {
+        return Err(AppError::ValidationError(
+            "Padding must be between 0 and 1000 bars".to_string(),
+        ));
+    }
+    if let Some(timeframe) = &params.timeframe {
+        validate_timeframe(timeframe)?;
+    }
+
+    let trade_repo = TradeRepository::new(state.db.clone());
+    let trade = trade_repo
+        .get(trade_id, user_id)
+        .await?
+        .ok_or(AppError::ValidationError("Trade not found".to_string()))?;
+
+    let executions = ExecutionRepository::new(state.db.clone())
+        .list_for_trade(trade_id, user_id)
+        .await?;
+    let legs = OptionLegRepository::new(state.db.clone())
+        .list_for_trade(trade_id, user_id)
+        .await?;
+
+    // Option leg prices are premiums, so their chart is the underlying's
+    let symbol = match &trade.underlying {
+        Some(underlying) if !legs.is_empty() => underlying.clone(),
+        _ => trade.symbol.clone(),
+    };
+    let end = trade.exit_time.unwrap_or_else(Utc::now);
+
+    let bar_repo = PriceBarRepository::new(state.db.clone());
+    let timeframe = match params.timeframe {
+        Some(timeframe) => timeframe,
+        None => bar_repo
+            .timeframe_for(user_id, &symbol, trade.entry_time, end)
+            .await?
+            .ok_or_else(|| AppError::ValidationError(format!("No price bars stored for {} during this trade", symbol)))?,
+    };
+
+    let bar_length = timeframe_duration(&timeframe).unwrap_or_default();
+    let from = trade.entry_time - bar_length * padding as i32;
+    let to = end + bar_length * padding as i32;
+
+    let bars = bar_repo
+        .bars(user_id, &symbol, &timeframe, from, to, MAX_REPLAY_BARS + 1)
+        .await?;
+    if bars.len() as i64 > MAX_REPLAY_BARS {
+        return Err(AppError::ValidationError(format!(
+            "Replay spans more than {} bars; use a longer timeframe",
+            MAX_REPLAY_BARS
+        )));
+    }
+
+    Ok(Json(TradeReplay {
+        trade_id,
+        symbol,
+        timeframe,
+        from,
+        to,
+        bars,
+        markers: ReplayMarker::for_trade(&trade, &executions, &legs),
+        levels: ReplayLevel::for_trade(&trade),
+    }))
+}
+
+/// Close, expire, assign or exercise an open leg
+pub async fn close_option_leg(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+    Path((trade_id, leg_id)): Path<(Uuid, Uuid)>,
+    Json(payload): Json<CloseOptionLegRequest>,
+) -> Result<Json<Trade>> {
+    let trade_repo = TradeRepository::new(state.db.clone());
+    let trade = trade_repo.close_leg(trade_id, leg_id, user_id, payload).await?;
+
+    Ok(Json(trade))
+}
+
+/// Expire every open option leg past its expiry as worthless
+pub async fn expire_options(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+    Json(payload): Json<ExpireOptionsRequest>,
+) -> Result<Json<Vec<Trade>>> {
+    let as_of = payload.as_of.unwrap_or_else(|| Utc::now().date_naive());
+
+    let trade_repo = TradeRepository::new(state.db.clone());
+    let trades = trade_repo.expire_legs(user_id, as_of).await?;
+
+    Ok(Json(trades))
+}
+
+/// Import trades from a broker export sent as the request body
+pub async fn import_trades(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+    Query(params): Query<ImportQuery>,
+    body: String,
+) -> Result<Json<ImportReport>> {
+    let importer = importers::importer_for(&params.broker).ok_or_else(|| {
+        AppError::ValidationError(format!("Unsupported broker '{}'", params.broker))
+    })?;
+
+    let trade_repo = TradeRepository::new(state.db.clone());
+    let instrument_repo = InstrumentRepository::new(state.db.clone());
+    let report = ImportService::import(
+        &trade_repo,
+        &instrument_repo,
+        user_id,
+        importer.as_ref(),
+        &body,
+        params.account_id,
+        params.dry_run.unwrap_or(false),
+    )
+    .await?;
+
+    Ok(Json(report))
+}
+
+/// Export trades matching the list filters as CSV, JSON or XLSX
+pub async fn export_trades(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+    Query(params): Query<ExportQuery>,
+    Query(filters): Query<TradeFilters>,
+) -> Result<Response> {
+    let format = ExportFormat::try_from(params.format.as_deref().unwrap_or("csv"))?;
+
+    let trade_repo = TradeRepository::new(state.db.clone());
+    let rows = trade_repo.stream(user_id, filters);
+
+    let body = match format {
+        ExportFormat::Xlsx => ExportService::xlsx(rows).await?,
+        _ => ExportService::stream(rows, format),
+    };
+
+    Response::builder()
+        .header(header::CONTENT_TYPE, format.content_type())
+        .header(
+            header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"trades.{}\"", format.extension()),
+        )
+        .body(body)
+        .map_err(|e| AppError::InternalServerError(format!("Failed to build export response: {}", e)))
+}
@@ -0,0 +1,60 @@
+use crate::{
+    error::{AppError, Result},
+    handlers::account::validate_currency,
+    middleware::AuthUser,
+    models::{UpdateUserSettingsRequest, UserSettings},
+    repositories::UserSettingsRepository,
+    AppState,
+};
+use axum::{extract::State, Json};
+use chrono_tz::Tz;
+use rust_decimal::Decimal;
+
+/// Get the user's settings
+pub async fn get_settings(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+) -> Result<Json<UserSettings>> {
+    let settings_repo = UserSettingsRepository::new(state.db.clone());
+    let settings = settings_repo.get(user_id).await?;
+
+    Ok(Json(settings))
+}
+
+/// Update the user's settings
+pub async fn update_settings(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+    Json(mut payload): Json<UpdateUserSettingsRequest>,
+) -> Result<Json<UserSettings>> {
+    if let Some(timezone) = payload.timezone.as_deref() {
+        timezone
+            .parse::<Tz>()
+            .map_err(|_| AppError::ValidationError("Unknown timezone".to_string()))?;
+    }
+
+    if payload.day_rollover_hour.is_some_and(|hour| !(0..24).contains(&hour)) {
+        return Err(AppError::ValidationError(
+            "Day rollover hour must be between 0 and 23".to_string(),
+        ));
+    }
+
+    if payload.default_fees.is_some_and(|fees| fees < Decimal::ZERO) {
+        return Err(AppError::ValidationError(
+            "Default fees cannot be negative".to_string(),
+        ));
+    }
+
+    if payload.risk_per_trade.is_some_and(|risk| risk <= Decimal::ZERO) {
+        return Err(AppError::ValidationError(
+            "Risk per trade must be positive".to_string(),
+        ));
+    }
+
+    payload.reporting_currency = payload.reporting_currency.as_deref().map(validate_currency).transpose()?;
+
+    let settings_repo = UserSettingsRepository::new(state.db.clone());
+    let settings = settings_repo.update(user_id, payload).await?;
+
+    Ok(Json(settings))
+}
@@ -0,0 +1,165 @@
+use crate::{
+    error::{AppError, Result},
+    middleware::AuthUser,
+    models::{
+        CreateVocabularyTermRequest, MergeReport, MergeVocabularyRequest, UpdateVocabularyRequest,
+        UpdateVocabularyTermRequest, Vocabulary, VocabularyKind, VocabularyTerm, VocabularyValue,
+    },
+    repositories::VocabularyRepository,
+    AppState,
+};
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use uuid::Uuid;
+
+fn validate_name(name: &str) -> Result<String> {
+    let name = name.trim();
+    if name.is_empty() || name.chars().count() > 100 {
+        return Err(AppError::ValidationError(
+            "Name must be between 1 and 100 characters".to_string(),
+        ));
+    }
+
+    Ok(name.to_string())
+}
+
+/// Accept `#rrggbb` colors, stored in lowercase
+fn validate_color(color: &str) -> Result<String> {
+    let hex = color.strip_prefix('#').unwrap_or_default();
+    if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(AppError::ValidationError(
+            "Color must be a hex code like #1e90ff".to_string(),
+        ));
+    }
+
+    Ok(color.to_lowercase())
+}
+
+fn validate_category(category: &str) -> Result<String> {
+    let category = category.trim();
+    if category.chars().count() > 50 {
+        return Err(AppError::ValidationError(
+            "Category must be at most 50 characters".to_string(),
+        ));
+    }
+
+    Ok(category.to_string())
+}
+
+/// Get a vocabulary with its terms
+pub async fn get_vocabulary(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+    Path(kind): Path<String>,
+) -> Result<Json<Vocabulary>> {
+    let kind = VocabularyKind::try_from(kind.as_str())?;
+
+    let vocabulary_repo = VocabularyRepository::new(state.db.clone());
+    let vocabulary = vocabulary_repo.get(user_id, kind).await?;
+
+    Ok(Json(vocabulary))
+}
+
+/// Turn strict mode of a vocabulary on or off
+pub async fn update_vocabulary(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+    Path(kind): Path<String>,
+    Json(payload): Json<UpdateVocabularyRequest>,
+) -> Result<Json<Vocabulary>> {
+    let kind = VocabularyKind::try_from(kind.as_str())?;
+
+    let vocabulary_repo = VocabularyRepository::new(state.db.clone());
+    vocabulary_repo.set_strict(user_id, kind, payload.strict).await?;
+    let vocabulary = vocabulary_repo.get(user_id, kind).await?;
+
+    Ok(Json(vocabulary))
+}
+
+/// List the values trades use, to spot typos and values to register
+pub async fn list_vocabulary_values(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+    Path(kind): Path<String>,
+) -> Result<Json<Vec<VocabularyValue>>> {
+    let kind = VocabularyKind::try_from(kind.as_str())?;
+
+    let vocabulary_repo = VocabularyRepository::new(state.db.clone());
+    let values = vocabulary_repo.values(user_id, kind).await?;
+
+    Ok(Json(values))
+}
+
+/// Add a term to a vocabulary
+pub async fn create_vocabulary_term(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+    Path(kind): Path<String>,
+    Json(mut payload): Json<CreateVocabularyTermRequest>,
+) -> Result<Json<VocabularyTerm>> {
+    let kind = VocabularyKind::try_from(kind.as_str())?;
+    payload.name = validate_name(&payload.name)?;
+    payload.color = payload.color.as_deref().map(validate_color).transpose()?;
+    payload.category = payload.category.as_deref().map(validate_category).transpose()?;
+
+    let vocabulary_repo = VocabularyRepository::new(state.db.clone());
+    let term = vocabulary_repo.create_term(user_id, kind, payload).await?;
+
+    Ok(Json(term))
+}
+
+/// Update a term; renaming rewrites it on all trades
+pub async fn update_vocabulary_term(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+    Path((kind, term_id)): Path<(String, Uuid)>,
+    Json(mut payload): Json<UpdateVocabularyTermRequest>,
+) -> Result<Json<VocabularyTerm>> {
+    let kind = VocabularyKind::try_from(kind.as_str())?;
+    payload.name = payload.name.as_deref().map(validate_name).transpose()?;
+    payload.color = payload.color.as_deref().map(validate_color).transpose()?;
+    payload.category = payload.category.as_deref().map(validate_category).transpose()?;
+
+    let vocabulary_repo = VocabularyRepository::new(state.db.clone());
+    let term = vocabulary_repo.update_term(user_id, kind, term_id, payload).await?;
+
+    Ok(Json(term))
+}
+
+/// Remove a term from a vocabulary
+pub async fn delete_vocabulary_term(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+    Path((kind, term_id)): Path<(String, Uuid)>,
+) -> Result<StatusCode> {
+    let kind = VocabularyKind::try_from(kind.as_str())?;
+
+    let vocabulary_repo = VocabularyRepository::new(state.db.clone());
+    vocabulary_repo.delete_term(user_id, kind, term_id).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Merge values into one term on all trades
+pub async fn merge_vocabulary_values(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+    Path(kind): Path<String>,
+    Json(mut payload): Json<MergeVocabularyRequest>,
+) -> Result<Json<MergeReport>> {
+    let kind = VocabularyKind::try_from(kind.as_str())?;
+    payload.into = validate_name(&payload.into)?;
+    if payload.from.is_empty() {
+        return Err(AppError::ValidationError(
+            "At least one value to merge is required".to_string(),
+        ));
+    }
+
+    let vocabulary_repo = VocabularyRepository::new(state.db.clone());
+    let report = vocabulary_repo.merge(user_id, kind, payload).await?;
+
+    Ok(Json(report))
+}
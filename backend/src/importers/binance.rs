@@ -0,0 +1,93 @@
+use super::{
+    fills::{parse_fills, Fill},
+    parse_decimal, parse_utc, CsvTable, ParsedImport, TradeImporter,
+};
+use csv::StringRecord;
+
+/// Binance spot "Trade History" export.
+///
+/// Expected columns: `Date(UTC)`, `Pair`, `Side`, `Price`, `Executed` and
+/// optionally `Fee`. The export has no trade ID, so the external ID is built
+/// from the fill's time, pair, side, price and size. Fees are taken as-is
+/// regardless of the asset they were charged in.
+pub struct BinanceImporter;
+
+const TIME_FORMATS: &[&str] = &["%Y-%m-%d %H:%M:%S", "%y-%m-%d %H:%M:%S"];
+
+fn parse_fill(table: &CsvTable, row: usize, record: &StringRecord) -> Result<Option<Fill>, String> {
+    let date = table.field(record, "Date(UTC)")?;
+    let pair = table.field(record, "Pair")?;
+    let side = match table.field(record, "Side")?.to_lowercase().as_str() {
+        "buy" => "buy",
+        "sell" => "sell",
+        other => return Err(format!("Unknown Side value '{}'", other)),
+    };
+    let price = table.field(record, "Price")?;
+    let executed = table.field(record, "Executed")?;
+
+    let fee = match table.optional(record, "Fee") {
+        Some(value) => parse_decimal(value, "Fee", table.delimiter)?,
+        None => Default::default(),
+    };
+
+    Ok(Some(Fill {
+        row,
+        external_id: format!("{}|{}|{}|{}|{}", date, pair, side, price, executed),
+        symbol: pair.to_string(),
+        side: side.to_string(),
+        price: parse_decimal(price, "Price", table.delimiter)?,
+        quantity: parse_decimal(executed, "Executed", table.delimiter)?,
+        executed_at: parse_utc(date, TIME_FORMATS, "Date(UTC)")?,
+        fee,
+    }))
+}
+
+impl TradeImporter for BinanceImporter {
+    fn broker(&self) -> &'static str {
+        "Binance"
+    }
+
+    fn parse(&self, content: &str) -> ParsedImport {
+        parse_fills(
+            self.broker(),
+            content,
+            b',',
+            &["Date(UTC)", "Pair", "Side", "Price", "Executed"],
+            parse_fill,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal::Decimal;
+
+    const EXPORT: &str = "\
+Date(UTC),Pair,Side,Price,Executed,Amount,Fee
+2025-01-02 10:00:00,BTCUSDT,BUY,95000.00,0.010BTC,950.00USDT,0.00001BTC
+2025-01-02 12:00:00,BTCUSDT,SELL,96000.00,0.010BTC,960.00USDT,0.96USDT
+2025-01-02 13:00:00,BTCUSDT,SELL,0,0.010BTC,0USDT,0USDT
+";
+
+    #[test]
+    fn trade_history_becomes_round_trips() {
+        let parsed = BinanceImporter.parse(EXPORT);
+
+        assert_eq!(parsed.total_rows, 3);
+        assert_eq!(parsed.errors.len(), 1);
+        assert_eq!(parsed.errors[0].row, 4);
+        assert_eq!(parsed.errors[0].message, "Fill price and quantity must be positive");
+        assert_eq!(parsed.trades.len(), 1);
+
+        let trade = &parsed.trades[0].trade;
+        assert_eq!(trade.symbol, "BTCUSDT");
+        assert_eq!(trade.direction, "long");
+        // Built from the fill, as the export has no trade ID
+        assert_eq!(trade.external_id.as_deref(), Some("2025-01-02 10:00:00|BTCUSDT|buy|95000.00|0.010BTC"));
+        assert_eq!(trade.broker.as_deref(), Some("Binance"));
+        let executions = trade.executions.as_ref().unwrap();
+        assert_eq!(executions[0].quantity, "0.010".parse::<Decimal>().unwrap());
+        assert_eq!(executions[1].fee, Some("0.96".parse::<Decimal>().unwrap()));
+    }
+}
@@ -0,0 +1,275 @@
+use super::{CsvTable, ImportRowError, ImportedTrade, ParsedImport};
+use crate::models::{CreateExecutionRequest, CreateTradeRequest};
+use chrono::{DateTime, Utc};
+use csv::StringRecord;
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+
+/// Single broker fill before it is grouped into a trade
+#[derive(Debug, Clone)]
+pub struct Fill {
+    pub row: usize,
+    pub external_id: String,
+    pub symbol: String,
+    pub side: String, // "buy" or "sell"
+    pub price: Decimal,
+    pub quantity: Decimal,
+    pub executed_at: DateTime<Utc>,
+    pub fee: Decimal,
+}
+
+/// Position being built up from fills
+struct OpenPosition {
+    row: usize,
+    external_id: String,
+    direction: &'static str,
+    size: Decimal,
+    executions: Vec<CreateExecutionRequest>,
+}
+
+/// Read a fill-per-row export and group the fills into trades.
+///
+/// `parse_fill` returns `Ok(None)` for rows that are valid but not fills
+/// (cancelled orders, transfers, ...).
+pub(crate) fn parse_fills<F>(
+    broker: &str,
+    content: &str,
+    delimiter: u8,
+    required: &[&str],
+    parse_fill: F,
+) -> ParsedImport
+where
+    F: Fn(&CsvTable, usize, &StringRecord) -> Result<Option<Fill>, String>,
+{
+    let mut parsed = ParsedImport::default();
+
+    let table = match CsvTable::read(content, delimiter).and_then(|t| t.require(required).map(|_| t)) {
+        Ok(table) => table,
+        Err(e) => {
+            parsed.errors.push(e);
+            return parsed;
+        }
+    };
+
+    parsed.total_rows = table.rows.len();
+    let mut fills = Vec::new();
+
+    for (row, record) in &table.rows {
+        match parse_fill(&table, *row, record) {
+            Ok(Some(fill)) if fill.quantity <= Decimal::ZERO || fill.price <= Decimal::ZERO => {
+                parsed.errors.push(ImportRowError {
+                    row: *row,
+                    message: "Fill price and quantity must be positive".to_string(),
+                });
+            }
+            Ok(Some(fill)) => fills.push(fill),
+            Ok(None) => {}
+            Err(message) => parsed.errors.push(ImportRowError { row: *row, message }),
+        }
+    }
+
+    group_round_trips(broker, fills, &mut parsed);
+
+    parsed
+}
+
+/// Group fills into round-trip trades.
+///
+/// A trade starts when a symbol goes from flat to a position and ends when it
+/// is flat again. A fill that reverses the position closes the current trade
+/// and opens a new one with the remainder. Positions still open at the end of
+/// the file are reported as errors rather than imported, so a later export
+/// containing the exits isn't skipped as a duplicate.
+pub fn group_round_trips(broker: &str, mut fills: Vec<Fill>, parsed: &mut ParsedImport) {
+    fills.sort_by_key(|f| f.executed_at);
+
+    let mut positions: HashMap<String, OpenPosition> = HashMap::new();
+
+    for fill in fills {
+        let mut remaining = fill.quantity;
+
+        if let Some(position) = positions.get_mut(&fill.symbol) {
+            if fill.side == position.direction_side() {
+                position.size += remaining;
+                position.executions.push(execution(&fill, remaining, fill.fee));
+                continue;
+            }
+
+            let closed = remaining.min(position.size);
+            let fee = fill.fee * closed / fill.quantity;
+            position.size -= closed;
+            position.executions.push(execution(&fill, closed, fee));
+            remaining -= closed;
+
+            if position.size.is_zero() {
+                let position = positions.remove(&fill.symbol).expect("position exists");
+                parsed.trades.push(finish(broker, &fill.symbol, position));
+            }
+        }
+
+        if remaining > Decimal::ZERO {
+            let fee = fill.fee * remaining / fill.quantity;
+            let external_id = if remaining == fill.quantity {
+                fill.external_id.clone()
+            } else {
+                format!("{}:reversal", fill.external_id)
+            };
+
+            positions.insert(
+                fill.symbol.clone(),
+                OpenPosition {
+                    row: fill.row,
+                    external_id,
+                    direction: if fill.side == "buy" { "long" } else { "short" },
+                    size: remaining,
+                    executions: vec![execution(&fill, remaining, fee)],
+                },
+            );
+        }
+    }
+
+    let mut still_open: Vec<(String, OpenPosition)> = positions.into_iter().collect();
+    still_open.sort_by_key(|(_, p)| p.row);
+    for (symbol, position) in still_open {
+        parsed.errors.push(ImportRowError {
+            row: position.row,
+            message: format!(
+                "{} position of {} still open at end of file, not imported",
+                symbol, position.size
+            ),
+        });
+    }
+}
+
+impl OpenPosition {
+    fn direction_side(&self) -> &'static str {
+        if self.direction == "long" { "buy" } else { "sell" }
+    }
+}
+
+fn execution(fill: &Fill, quantity: Decimal, fee: Decimal) -> CreateExecutionRequest {
+    CreateExecutionRequest {
+        side: fill.side.clone(),
+        price: fill.price,
+        quantity,
+        executed_at: fill.executed_at,
+        fee: Some(fee),
+    }
+}
+
+fn finish(broker: &str, symbol: &str, position: OpenPosition) -> ImportedTrade {
+    let entry_time = position.executions[0].executed_at;
+
+    ImportedTrade {
+        row: position.row,
+        trade: CreateTradeRequest {
+            symbol: symbol.to_string(),
+            direction: position.direction.to_string(),
+            entry_time,
+            broker: Some(broker.to_string()),
+            external_id: Some(position.external_id),
+            executions: Some(position.executions),
+            ..Default::default()
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decimal(value: &str) -> Decimal {
+        value.parse().unwrap()
+    }
+
+    fn fill(row: usize, id: &str, symbol: &str, side: &str, quantity: &str, price: &str, time: &str) -> Fill {
+        Fill {
+            row,
+            external_id: id.to_string(),
+            symbol: symbol.to_string(),
+            side: side.to_string(),
+            price: decimal(price),
+            quantity: decimal(quantity),
+            executed_at: time.parse().unwrap(),
+            fee: decimal("1"),
+        }
+    }
+
+    #[test]
+    fn fills_are_grouped_until_the_position_is_flat() {
+        let fills = vec![
+            // Out of order on purpose: fills are sorted by time
+            fill(4, "F4", "AAPL", "sell", "150", "155", "2025-01-02T16:00:00Z"),
+            fill(2, "F2", "AAPL", "buy", "100", "150", "2025-01-02T14:30:00Z"),
+            fill(3, "F3", "AAPL", "buy", "50", "152", "2025-01-02T15:00:00Z"),
+            fill(5, "F5", "MSFT", "sell", "10", "400", "2025-01-02T15:30:00Z"),
+            fill(6, "F6", "MSFT", "buy", "10", "390", "2025-01-02T17:00:00Z"),
+        ];
+        let mut parsed = ParsedImport::default();
+
+        group_round_trips("Test", fills, &mut parsed);
+
+        assert!(parsed.errors.is_empty());
+        assert_eq!(parsed.trades.len(), 2);
+
+        // Trades are listed in the order they were closed
+        let aapl = &parsed.trades[0];
+        assert_eq!(aapl.row, 2);
+        assert_eq!(aapl.trade.direction, "long");
+        assert_eq!(aapl.trade.external_id.as_deref(), Some("F2"));
+        assert_eq!(aapl.trade.broker.as_deref(), Some("Test"));
+        assert_eq!(aapl.trade.entry_time, "2025-01-02T14:30:00Z".parse::<DateTime<Utc>>().unwrap());
+        let executions = aapl.trade.executions.as_ref().unwrap();
+        let sides: Vec<&str> = executions.iter().map(|e| e.side.as_str()).collect();
+        assert_eq!(sides, ["buy", "buy", "sell"]);
+        assert_eq!(executions[2].quantity, decimal("150"));
+
+        let msft = &parsed.trades[1];
+        assert_eq!(msft.row, 5);
+        assert_eq!(msft.trade.symbol, "MSFT");
+        assert_eq!(msft.trade.direction, "short");
+        assert_eq!(msft.trade.external_id.as_deref(), Some("F5"));
+    }
+
+    #[test]
+    fn reversal_closes_the_trade_and_opens_the_remainder() {
+        let fills = vec![
+            fill(2, "F1", "ES", "buy", "2", "5900", "2025-01-02T14:30:00Z"),
+            fill(3, "F2", "ES", "sell", "3", "5910", "2025-01-02T15:00:00Z"),
+            fill(4, "F3", "ES", "buy", "1", "5905", "2025-01-02T15:30:00Z"),
+        ];
+        let mut parsed = ParsedImport::default();
+
+        group_round_trips("Test", fills, &mut parsed);
+
+        assert!(parsed.errors.is_empty());
+        assert_eq!(parsed.trades.len(), 2);
+        let long = parsed.trades[0].trade.executions.as_ref().unwrap();
+        assert_eq!(long[1].quantity, decimal("2"));
+        // The reversing fill's fee is split by the quantity on each side
+        assert_eq!(long[1].fee, Some(decimal("1") * decimal("2") / decimal("3")));
+
+        let short = &parsed.trades[1].trade;
+        assert_eq!(short.direction, "short");
+        assert_eq!(short.external_id.as_deref(), Some("F2:reversal"));
+        let executions = short.executions.as_ref().unwrap();
+        assert_eq!(executions[0].quantity, decimal("1"));
+        assert_eq!(executions[0].fee, Some(decimal("1") / decimal("3")));
+    }
+
+    #[test]
+    fn positions_open_at_the_end_are_reported_not_imported() {
+        let fills = vec![
+            fill(2, "F1", "AAPL", "buy", "100", "150", "2025-01-02T14:30:00Z"),
+            fill(3, "F2", "AAPL", "sell", "40", "155", "2025-01-02T15:00:00Z"),
+        ];
+        let mut parsed = ParsedImport::default();
+
+        group_round_trips("Test", fills, &mut parsed);
+
+        assert!(parsed.trades.is_empty());
+        assert_eq!(parsed.errors.len(), 1);
+        assert_eq!(parsed.errors[0].row, 2);
+        assert_eq!(parsed.errors[0].message, "AAPL position of 60 still open at end of file, not imported");
+    }
+}
@@ -0,0 +1,109 @@
+use super::{
+    fills::{parse_fills, Fill},
+    parse_decimal, parse_utc, CsvTable, ParsedImport, TradeImporter,
+};
+use csv::StringRecord;
+
+/// Interactive Brokers Flex Query "Trades" section exported as CSV.
+///
+/// Expected columns: `Symbol`, `Buy/Sell`, `Quantity`, `TradePrice`,
+/// `DateTime`, `IBCommission`, `TradeID`. Each row is a single fill. The
+/// Flex Query should be configured to output times in UTC.
+pub struct IbkrImporter;
+
+const TIME_FORMATS: &[&str] = &[
+    "%Y%m%d;%H%M%S",
+    "%Y-%m-%d;%H:%M:%S",
+    "%Y-%m-%d %H:%M:%S",
+    "%Y-%m-%d, %H:%M:%S",
+];
+
+fn parse_fill(table: &CsvTable, row: usize, record: &StringRecord) -> Result<Option<Fill>, String> {
+    let side = match table.field(record, "Buy/Sell")?.to_uppercase().as_str() {
+        "BUY" => "buy",
+        "SELL" => "sell",
+        other => return Err(format!("Unknown Buy/Sell value '{}'", other)),
+    };
+
+    let fee = match table.optional(record, "IBCommission") {
+        Some(value) => parse_decimal(value, "IBCommission", table.delimiter)?,
+        None => Default::default(),
+    };
+
+    Ok(Some(Fill {
+        row,
+        external_id: table.field(record, "TradeID")?.to_string(),
+        symbol: table.field(record, "Symbol")?.to_string(),
+        side: side.to_string(),
+        price: parse_decimal(table.field(record, "TradePrice")?, "TradePrice", table.delimiter)?,
+        // Sells are reported with a negative quantity
+        quantity: parse_decimal(table.field(record, "Quantity")?, "Quantity", table.delimiter)?.abs(),
+        executed_at: parse_utc(table.field(record, "DateTime")?, TIME_FORMATS, "DateTime")?,
+        // Commissions are reported as negative cash amounts
+        fee: fee.abs(),
+    }))
+}
+
+impl TradeImporter for IbkrImporter {
+    fn broker(&self) -> &'static str {
+        "Interactive Brokers"
+    }
+
+    fn parse(&self, content: &str) -> ParsedImport {
+        parse_fills(
+            self.broker(),
+            content,
+            b',',
+            &["Symbol", "Buy/Sell", "Quantity", "TradePrice", "DateTime", "TradeID"],
+            parse_fill,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{DateTime, Utc};
+    use rust_decimal::Decimal;
+
+    const EXPORT: &str = "\
+Symbol,Buy/Sell,Quantity,TradePrice,DateTime,IBCommission,TradeID
+AAPL,BUY,100,150.25,20250102;153000,-1.00,1001
+AAPL,SELL,-100,152.75,20250102;160000,-1.05,1002
+MSFT,SELL,-10,400,\"2025-01-03, 14:30:00\",-0.5,1003
+MSFT,BUY,10,395.5,\"2025-01-03, 15:00:00\",-0.5,1004
+TSLA,HOLD,1,250,20250103;150000,0,1005
+";
+
+    fn decimal(value: &str) -> Decimal {
+        value.parse().unwrap()
+    }
+
+    #[test]
+    fn flex_query_fills_become_round_trips() {
+        let parsed = IbkrImporter.parse(EXPORT);
+
+        assert_eq!(parsed.total_rows, 5);
+        assert_eq!(parsed.errors.len(), 1);
+        assert_eq!(parsed.errors[0].row, 6);
+        assert_eq!(parsed.errors[0].message, "Unknown Buy/Sell value 'HOLD'");
+
+        let trades: Vec<_> = parsed.trades.iter().map(|t| &t.trade).collect();
+        assert_eq!(trades.len(), 2);
+
+        assert_eq!(trades[0].symbol, "AAPL");
+        assert_eq!(trades[0].direction, "long");
+        assert_eq!(trades[0].external_id.as_deref(), Some("1001"));
+        assert_eq!(trades[0].broker.as_deref(), Some("Interactive Brokers"));
+        assert_eq!(trades[0].entry_time, "2025-01-02T15:30:00Z".parse::<DateTime<Utc>>().unwrap());
+        let executions = trades[0].executions.as_ref().unwrap();
+        assert_eq!(executions[1].price, decimal("152.75"));
+        // Negative quantities and commissions are stored as positive values
+        assert_eq!(executions[1].quantity, decimal("100"));
+        assert_eq!(executions[1].fee, Some(decimal("1.05")));
+
+        assert_eq!(trades[1].symbol, "MSFT");
+        assert_eq!(trades[1].direction, "short");
+        assert_eq!(trades[1].external_id.as_deref(), Some("1003"));
+    }
+}
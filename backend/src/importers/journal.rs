@@ -0,0 +1,217 @@
+use super::{parse_decimal, parse_utc, CsvTable, ImportRowError, ImportedTrade, ParsedImport, TradeImporter};
+use crate::models::CreateTradeRequest;
+use csv::StringRecord;
+use uuid::Uuid;
+
+/// Columns of the journal's own CSV export, in file order
+pub const EXPORT_COLUMNS: &[&str] = &[
+    "id",
+    "symbol",
+    "underlying",
+    "direction",
+    "entry_price",
+    "exit_price",
+    "quantity",
+    "open_quantity",
+    "multiplier",
+    "entry_time",
+    "exit_time",
+    "pnl",
+    "pnl_percentage",
+    "fees",
+    "currency",
+    "fee_currency",
+    "fee_fx_rate",
+    "fee_fx_date",
+    "stop_loss",
+    "initial_stop",
+    "take_profit",
+    "planned_risk",
+    "r_multiple",
+    "planned_r_multiple",
+    "mae",
+    "mfe",
+    "entry_efficiency",
+    "exit_efficiency",
+    "notes",
+    "tags",
+    "setup_type",
+    "checklist_met",
+    "strategy",
+    "mistakes",
+    "emotions",
+    "screenshots",
+    "broker",
+    "account_id",
+    "external_id",
+    "status",
+    "created_at",
+    "updated_at",
+];
+
+/// Separator between values of array columns (`tags`, `mistakes`, ...)
+const LIST_SEPARATOR: char = '|';
+const LIST_ESCAPE: char = '\\';
+
+/// Encode an array column as a single cell.
+///
+/// Values are joined with `|`; a literal `|` or `\` inside a value is
+/// preceded by `\`. An empty array is an empty cell. `decode_list` reverses
+/// this exactly.
+pub fn encode_list(values: &[String]) -> String {
+    values
+        .iter()
+        .map(|value| {
+            let mut escaped = String::with_capacity(value.len());
+            for c in value.chars() {
+                if c == LIST_SEPARATOR || c == LIST_ESCAPE {
+                    escaped.push(LIST_ESCAPE);
+                }
+                escaped.push(c);
+            }
+            escaped
+        })
+        .collect::<Vec<_>>()
+        .join(&LIST_SEPARATOR.to_string())
+}
+
+/// Decode a cell written by `encode_list`
+pub fn decode_list(cell: &str) -> Vec<String> {
+    if cell.is_empty() {
+        return Vec::new();
+    }
+
+    let mut values = Vec::new();
+    let mut current = String::new();
+    let mut chars = cell.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            LIST_ESCAPE => {
+                if let Some(next) = chars.next() {
+                    current.push(next);
+                }
+            }
+            LIST_SEPARATOR => values.push(std::mem::take(&mut current)),
+            _ => current.push(c),
+        }
+    }
+    values.push(current);
+
+    values
+}
+
+/// Re-import of the journal's own CSV export (`GET /api/trades/export`).
+///
+/// Every row keeps its broker, account and screenshot links, and links to
+/// the playbook named by its setup type. The external ID is the exported
+/// `external_id`, or the trade's `id` for trades entered by hand, so
+/// importing an export back into the same journal skips every row.
+/// Derived columns (P&L, R multiples, fee FX rates, underlying, excursions,
+/// status, timestamps) are recomputed on import. Option legs aren't part of the
+/// export, so multi-leg trades come back as single-row trades.
+pub struct JournalImporter;
+
+fn parse_row(table: &CsvTable, row: usize, record: &StringRecord) -> Result<ImportedTrade, String> {
+    let optional_decimal = |column: &str| {
+        table
+            .optional(record, column)
+            .map(|v| parse_decimal(v, column, table.delimiter))
+            .transpose()
+    };
+    let optional_time = |column: &str| {
+        table
+            .optional(record, column)
+            .map(|v| parse_utc(v, &[], column))
+            .transpose()
+    };
+    let list = |column: &str| table.optional(record, column).map(decode_list);
+    let decimal_list = |column: &str| {
+        list(column)
+            .map(|values| values.iter().map(|v| parse_decimal(v, column, table.delimiter)).collect::<Result<Vec<_>, _>>())
+            .transpose()
+    };
+    let text = |column: &str| table.optional(record, column).map(str::to_string);
+    let currency = |column: &str| {
+        table
+            .optional(record, column)
+            .map(|v| {
+                if v.len() == 3 && v.chars().all(|c| c.is_ascii_alphabetic()) {
+                    Ok(v.to_uppercase())
+                } else {
+                    Err(format!("Invalid currency '{}' in '{}'", v, column))
+                }
+            })
+            .transpose()
+    };
+
+    let external_id = text("external_id").or_else(|| text("id"));
+
+    Ok(ImportedTrade {
+        row,
+        trade: CreateTradeRequest {
+            symbol: table.field(record, "symbol")?.to_string(),
+            direction: table.field(record, "direction")?.to_string(),
+            entry_price: parse_decimal(table.field(record, "entry_price")?, "entry_price", table.delimiter)?,
+            exit_price: optional_decimal("exit_price")?,
+            quantity: parse_decimal(table.field(record, "quantity")?, "quantity", table.delimiter)?,
+            entry_time: parse_utc(table.field(record, "entry_time")?, &[], "entry_time")?,
+            exit_time: optional_time("exit_time")?,
+            fees: optional_decimal("fees")?,
+            currency: currency("currency")?,
+            fee_currency: currency("fee_currency")?,
+            stop_loss: optional_decimal("stop_loss")?,
+            initial_stop: optional_decimal("initial_stop")?,
+            take_profit: decimal_list("take_profit")?,
+            planned_risk: optional_decimal("planned_risk")?,
+            notes: text("notes"),
+            tags: list("tags"),
+            setup_type: text("setup_type"),
+            playbook_id: None,
+            checklist_met: list("checklist_met"),
+            strategy: text("strategy"),
+            mistakes: list("mistakes"),
+            emotions: list("emotions"),
+            screenshots: list("screenshots"),
+            broker: text("broker"),
+            account_id: table
+                .optional(record, "account_id")
+                .map(|v| Uuid::parse_str(v).map_err(|_| format!("Invalid account ID '{}'", v)))
+                .transpose()?,
+            external_id,
+            executions: None,
+            legs: None,
+        },
+    })
+}
+
+impl TradeImporter for JournalImporter {
+    fn broker(&self) -> &'static str {
+        "Trading Journal"
+    }
+
+    fn parse(&self, content: &str) -> ParsedImport {
+        let mut parsed = ParsedImport::default();
+
+        let table = match CsvTable::read(content, b',')
+            .and_then(|t| t.require(&["symbol", "direction", "entry_price", "quantity", "entry_time"]).map(|_| t))
+        {
+            Ok(table) => table,
+            Err(e) => {
+                parsed.errors.push(e);
+                return parsed;
+            }
+        };
+
+        parsed.total_rows = table.rows.len();
+
+        for (row, record) in &table.rows {
+            match parse_row(&table, *row, record) {
+                Ok(trade) => parsed.trades.push(trade),
+                Err(message) => parsed.errors.push(ImportRowError { row: *row, message }),
+            }
+        }
+
+        parsed
+    }
+}
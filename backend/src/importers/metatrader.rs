@@ -0,0 +1,169 @@
+use super::{
+    detect_delimiter, parse_decimal, parse_utc, CsvTable, ImportRowError, ImportedTrade, ParsedImport,
+    TradeImporter,
+};
+use crate::models::CreateTradeRequest;
+use csv::StringRecord;
+use rust_decimal::Decimal;
+
+/// MetaTrader 4/5 account history (closed positions) saved as CSV.
+///
+/// Expected columns: `Ticket` (MT4) or `Position` (MT5), `Open Time`, `Type`,
+/// `Size` or `Volume`, `Symbol` or `Item`, `Open Price`, `Close Time`,
+/// `Close Price`, and optionally `Commission`, `Swap`, `S / L` and `T / P`
+/// (a level of 0 means none was set). Each row is one
+/// closed position. Tab, semicolon and comma separated files are accepted;
+/// semicolon-separated files may use decimal commas (`1,10350`).
+/// Times are taken as UTC; MetaTrader writes broker server time, so terminals
+/// should be set to UTC before exporting.
+pub struct MetaTraderImporter;
+
+const TIME_FORMATS: &[&str] = &["%Y.%m.%d %H:%M:%S", "%Y.%m.%d %H:%M", "%Y-%m-%d %H:%M:%S"];
+
+impl MetaTraderImporter {
+    fn parse_row(&self, table: &CsvTable, row: usize, record: &StringRecord) -> Result<Option<ImportedTrade>, String> {
+        // Balance, credit and cancelled pending-order rows are not trades
+        let direction = match table.field(record, "Type")?.to_lowercase().as_str() {
+            "buy" => "long",
+            "sell" => "short",
+            _ => return Ok(None),
+        };
+
+        let commission = match table.optional(record, "Commission") {
+            Some(value) => parse_decimal(value, "Commission", table.delimiter)?,
+            None => Decimal::ZERO,
+        };
+        let swap = match table.optional(record, "Swap") {
+            Some(value) => parse_decimal(value, "Swap", table.delimiter)?,
+            None => Decimal::ZERO,
+        };
+
+        let level = |column: &str| -> Result<Option<Decimal>, String> {
+            match table.optional(record, column) {
+                Some(value) => Ok(Some(parse_decimal(value, column, table.delimiter)?).filter(|l| !l.is_zero())),
+                None => Ok(None),
+            }
+        };
+
+        let trade = CreateTradeRequest {
+            symbol: table.field_any(record, &["Symbol", "Item"])?.to_uppercase(),
+            direction: direction.to_string(),
+            entry_price: parse_decimal(table.field(record, "Open Price")?, "Open Price", table.delimiter)?,
+            exit_price: Some(parse_decimal(table.field(record, "Close Price")?, "Close Price", table.delimiter)?),
+            quantity: parse_decimal(table.field_any(record, &["Size", "Volume"])?, "Size", table.delimiter)?,
+            entry_time: parse_utc(table.field(record, "Open Time")?, TIME_FORMATS, "Open Time")?,
+            exit_time: Some(parse_utc(table.field(record, "Close Time")?, TIME_FORMATS, "Close Time")?),
+            // Commission and swap are signed cash flows; charges are negative
+            fees: Some(-(commission + swap)),
+            stop_loss: level("S / L")?,
+            take_profit: level("T / P")?.map(|target| vec![target]),
+            broker: Some(self.broker().to_string()),
+            external_id: Some(table.field_any(record, &["Ticket", "Position"])?.to_string()),
+            ..Default::default()
+        };
+
+        Ok(Some(ImportedTrade { row, trade }))
+    }
+}
+
+impl TradeImporter for MetaTraderImporter {
+    fn broker(&self) -> &'static str {
+        "MetaTrader"
+    }
+
+    fn parse(&self, content: &str) -> ParsedImport {
+        let mut parsed = ParsedImport::default();
+
+        let table = match CsvTable::read(content, detect_delimiter(content)) {
+            Ok(table) => table,
+            Err(e) => {
+                parsed.errors.push(e);
+                return parsed;
+            }
+        };
+
+        if let Err(e) = table.require(&["Open Time", "Type", "Open Price", "Close Time", "Close Price"]) {
+            parsed.errors.push(e);
+            return parsed;
+        }
+        for alternatives in [&["Ticket", "Position"][..], &["Size", "Volume"], &["Symbol", "Item"]] {
+            if !table.has_any(alternatives) {
+                parsed.errors.push(ImportRowError {
+                    row: 1,
+                    message: format!("Missing columns: {}", alternatives.join(" or ")),
+                });
+                return parsed;
+            }
+        }
+
+        parsed.total_rows = table.rows.len();
+
+        for (row, record) in &table.rows {
+            match self.parse_row(&table, *row, record) {
+                Ok(Some(trade)) => parsed.trades.push(trade),
+                Ok(None) => {}
+                Err(message) => parsed.errors.push(ImportRowError { row: *row, message }),
+            }
+        }
+
+        parsed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // European terminal settings: semicolons between fields, decimal commas
+    const EXPORT: &str = "\
+Ticket;Open Time;Type;Size;Item;Open Price;S / L;T / P;Close Time;Close Price;Commission;Swap;Profit
+50001;2025.01.02 10:00:00;buy;0,10;eurusd;1,10350;1,10000;0,00000;2025.01.02 12:30:00;1,10550;-0,70;0,00;20,00
+50002;2025.01.02 13:00;balance;;;;;;;;;;1.000,00
+50003;2025.01.03 09:00;sell;1,00;GBPUSD;1,25000;0;1,24500;2025.01.03 11:00;1,24800;-7,00;-1,50;200,00
+";
+
+    fn decimal(value: &str) -> Decimal {
+        value.parse().unwrap()
+    }
+
+    #[test]
+    fn closed_positions_become_trades() {
+        let parsed = MetaTraderImporter.parse(EXPORT);
+
+        assert!(parsed.errors.is_empty(), "{:?}", parsed.errors);
+        // The balance row counts as a row but is not a trade
+        assert_eq!(parsed.total_rows, 3);
+        assert_eq!(parsed.trades.len(), 2);
+
+        let long = &parsed.trades[0];
+        assert_eq!(long.row, 2);
+        assert_eq!(long.trade.symbol, "EURUSD");
+        assert_eq!(long.trade.direction, "long");
+        assert_eq!(long.trade.entry_price, decimal("1.10350"));
+        assert_eq!(long.trade.exit_price, Some(decimal("1.10550")));
+        assert_eq!(long.trade.quantity, decimal("0.10"));
+        assert_eq!(long.trade.stop_loss, Some(decimal("1.10000")));
+        assert_eq!(long.trade.take_profit, None);
+        assert_eq!(long.trade.fees, Some(decimal("0.70")));
+        assert_eq!(long.trade.external_id.as_deref(), Some("50001"));
+        assert_eq!(long.trade.broker.as_deref(), Some("MetaTrader"));
+
+        let short = &parsed.trades[1].trade;
+        assert_eq!(short.direction, "short");
+        assert_eq!(short.stop_loss, None);
+        assert_eq!(short.take_profit, Some(vec![decimal("1.24500")]));
+        // Commission and swap are both charges
+        assert_eq!(short.fees, Some(decimal("8.50")));
+        assert_eq!(short.exit_time, Some("2025-01-03T11:00:00Z".parse().unwrap()));
+        assert_eq!(short.external_id.as_deref(), Some("50003"));
+    }
+
+    #[test]
+    fn missing_columns_are_reported_once() {
+        let parsed = MetaTraderImporter.parse("Ticket\tOpen Time\tType\n1\t2025.01.02 10:00\tbuy\n");
+
+        assert!(parsed.trades.is_empty());
+        assert_eq!(parsed.errors.len(), 1);
+        assert_eq!(parsed.errors[0].message, "Missing columns: Open Price, Close Time, Close Price");
+    }
+}
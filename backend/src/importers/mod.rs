@@ -0,0 +1,312 @@
+pub mod binance;
+pub mod fills;
+pub mod ibkr;
+pub mod journal;
+pub mod metatrader;
+pub mod ninjatrader;
+pub mod tradingview;
+pub mod tradovate;
+
+pub use binance::BinanceImporter;
+pub use ibkr::IbkrImporter;
+pub use journal::JournalImporter;
+pub use metatrader::MetaTraderImporter;
+pub use ninjatrader::NinjaTraderImporter;
+pub use tradingview::TradingViewImporter;
+pub use tradovate::TradovateImporter;
+
+use crate::models::{CreateTradeRequest, ImportRowError};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use csv::{ReaderBuilder, StringRecord};
+use rust_decimal::Decimal;
+use std::{collections::HashMap, str::FromStr};
+
+/// Trade parsed from a broker export
+#[derive(Debug)]
+pub struct ImportedTrade {
+    /// Line in the source file the trade starts on
+    pub row: usize,
+    pub trade: CreateTradeRequest,
+}
+
+/// Result of parsing one export file
+#[derive(Debug, Default)]
+pub struct ParsedImport {
+    pub total_rows: usize,
+    pub trades: Vec<ImportedTrade>,
+    pub errors: Vec<ImportRowError>,
+}
+
+/// Parser for one broker's export format.
+///
+/// Implementations map rows into `CreateTradeRequest`s and must set
+/// `external_id` to a value that stays the same when the same export is
+/// imported twice, so duplicates can be skipped.
+pub trait TradeImporter: Send + Sync {
+    /// Broker name stored on imported trades
+    fn broker(&self) -> &'static str;
+
+    /// Parse the raw file content
+    fn parse(&self, content: &str) -> ParsedImport;
+}
+
+/// Look up the importer for a broker key
+pub fn importer_for(broker: &str) -> Option<Box<dyn TradeImporter>> {
+    let importer: Box<dyn TradeImporter> = match broker.to_lowercase().as_str() {
+        "ibkr" | "interactive_brokers" => Box::new(IbkrImporter),
+        "metatrader" | "mt4" | "mt5" => Box::new(MetaTraderImporter),
+        "tradingview" => Box::new(TradingViewImporter),
+        "ninjatrader" => Box::new(NinjaTraderImporter),
+        "tradovate" => Box::new(TradovateImporter),
+        "binance" => Box::new(BinanceImporter),
+        "journal" => Box::new(JournalImporter),
+        _ => return None,
+    };
+
+    Some(importer)
+}
+
+/// CSV file with case-insensitive header lookup
+pub(crate) struct CsvTable {
+    headers: HashMap<String, usize>,
+    pub rows: Vec<(usize, StringRecord)>,
+    /// Field delimiter of the file; decides what a comma in a number means
+    pub delimiter: u8,
+}
+
+impl CsvTable {
+    /// Read a delimited file, keeping the source line of every record
+    pub fn read(content: &str, delimiter: u8) -> Result<Self, ImportRowError> {
+        let mut reader = ReaderBuilder::new()
+            .delimiter(delimiter)
+            .flexible(true)
+            .trim(csv::Trim::All)
+            .from_reader(content.as_bytes());
+
+        let headers = reader
+            .headers()
+            .map_err(|e| ImportRowError {
+                row: 1,
+                message: format!("Invalid header row: {}", e),
+            })?
+            .iter()
+            .enumerate()
+            .map(|(i, h)| (normalize_header(h), i))
+            .collect();
+
+        let mut rows = Vec::new();
+        for record in reader.records() {
+            match record {
+                Ok(record) => {
+                    let line = record.position().map(|p| p.line() as usize).unwrap_or(0);
+                    if record.iter().all(|f| f.is_empty()) {
+                        continue;
+                    }
+                    rows.push((line, record));
+                }
+                Err(e) => {
+                    let line = e.position().map(|p| p.line() as usize).unwrap_or(0);
+                    return Err(ImportRowError {
+                        row: line,
+                        message: format!("Malformed CSV: {}", e),
+                    });
+                }
+            }
+        }
+
+        Ok(Self { headers, rows, delimiter })
+    }
+
+    /// Build a table from rows read out of another format
+    pub fn from_records(headers: &[String], rows: Vec<(usize, StringRecord)>) -> Self {
+        let headers = headers
+            .iter()
+            .enumerate()
+            .map(|(i, h)| (normalize_header(h), i))
+            .collect();
+
+        Self {
+            headers,
+            rows,
+            delimiter: b',',
+        }
+    }
+
+    /// Fail early when the file doesn't look like the expected export
+    pub fn require(&self, columns: &[&str]) -> Result<(), ImportRowError> {
+        let missing: Vec<&str> = columns
+            .iter()
+            .copied()
+            .filter(|c| !self.headers.contains_key(&normalize_header(c)))
+            .collect();
+
+        if missing.is_empty() {
+            Ok(())
+        } else {
+            Err(ImportRowError {
+                row: 1,
+                message: format!("Missing columns: {}", missing.join(", ")),
+            })
+        }
+    }
+
+    /// Get a required field by column name
+    pub fn field<'a>(&self, record: &'a StringRecord, column: &str) -> Result<&'a str, String> {
+        self.optional(record, column)
+            .ok_or_else(|| format!("Missing value for '{}'", column))
+    }
+
+    /// Get an optional field by column name, treating blanks as absent
+    pub fn optional<'a>(&self, record: &'a StringRecord, column: &str) -> Option<&'a str> {
+        self.headers
+            .get(&normalize_header(column))
+            .and_then(|&i| record.get(i))
+            .filter(|v| !v.is_empty())
+    }
+
+    /// Get the first present field among alternative column names
+    pub fn field_any<'a>(&self, record: &'a StringRecord, columns: &[&str]) -> Result<&'a str, String> {
+        columns
+            .iter()
+            .find_map(|c| self.optional(record, c))
+            .ok_or_else(|| format!("Missing value for '{}'", columns[0]))
+    }
+
+    /// Check that one of the alternative column names is present
+    pub fn has_any(&self, columns: &[&str]) -> bool {
+        columns
+            .iter()
+            .any(|c| self.headers.contains_key(&normalize_header(c)))
+    }
+}
+
+/// Guess the delimiter from the header line (tab, semicolon or comma)
+pub(crate) fn detect_delimiter(content: &str) -> u8 {
+    let header = content.lines().next().unwrap_or_default();
+
+    [b'\t', b';', b',']
+        .into_iter()
+        .max_by_key(|d| header.bytes().filter(|b| b == d).count())
+        .unwrap_or(b',')
+}
+
+fn normalize_header(header: &str) -> String {
+    header.trim().trim_start_matches('\u{feff}').to_lowercase()
+}
+
+/// Parse a number, tolerating currency symbols, thousands separators and
+/// trailing asset codes (e.g. `$1,234.50` or `0.015BTC`).
+///
+/// In semicolon-separated files a comma is the decimal separator, as in
+/// European exports (`1,10350` or `1.234,56`). Elsewhere a comma must
+/// separate thousands, so a number like `1,10350` is rejected instead of
+/// being read as `110350`.
+pub(crate) fn parse_decimal(value: &str, column: &str, delimiter: u8) -> Result<Decimal, String> {
+    let cleaned: String = value
+        .trim()
+        .trim_end_matches(|c: char| c.is_ascii_alphabetic())
+        .chars()
+        .filter(|c| !matches!(c, '$' | ' ' | '€' | '£'))
+        .collect();
+
+    let (cleaned, negative) = match cleaned.strip_prefix('(').and_then(|v| v.strip_suffix(')')) {
+        Some(inner) => (inner.to_string(), true),
+        None => (cleaned, false),
+    };
+
+    let (decimal_separator, group_separator) = if delimiter == b';' && cleaned.contains(',') {
+        (',', '.')
+    } else {
+        ('.', ',')
+    };
+    let (whole, fraction) = match cleaned.split_once(decimal_separator) {
+        Some((whole, fraction)) => (whole, Some(fraction)),
+        None => (cleaned.as_str(), None),
+    };
+    let ambiguous = || format!("Ambiguous number '{}' in '{}'", value, column);
+    if fraction.is_some_and(|f| f.contains([',', '.'])) {
+        return Err(ambiguous());
+    }
+    let whole = ungroup(whole, group_separator).ok_or_else(ambiguous)?;
+    let normalized = match fraction {
+        Some(fraction) => format!("{}.{}", whole, fraction),
+        None => whole,
+    };
+
+    let parsed = Decimal::from_str(&normalized)
+        .map_err(|_| format!("Invalid number '{}' in '{}'", value, column))?;
+
+    Ok(if negative { -parsed } else { parsed })
+}
+
+/// Remove thousands separators from the whole part of a number, failing
+/// unless they split it into groups of three digits
+fn ungroup(whole: &str, separator: char) -> Option<String> {
+    let mut groups = whole.split(separator);
+    let first = groups.next().unwrap_or_default();
+    let rest: Vec<&str> = groups.collect();
+
+    let grouped = rest.is_empty()
+        || (first.trim_start_matches(['-', '+']).len() <= 3 && rest.iter().all(|group| group.len() == 3));
+
+    grouped.then(|| std::iter::once(first).chain(rest).collect())
+}
+
+/// Parse a timestamp given in UTC using the first matching format
+pub(crate) fn parse_utc(value: &str, formats: &[&str], column: &str) -> Result<DateTime<Utc>, String> {
+    if let Ok(dt) = DateTime::parse_from_rfc3339(value) {
+        return Ok(dt.with_timezone(&Utc));
+    }
+
+    formats
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+        .map(|naive| naive.and_utc())
+        .ok_or_else(|| format!("Invalid timestamp '{}' in '{}'", value, column))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decimal(value: &str) -> Decimal {
+        Decimal::from_str(value).unwrap()
+    }
+
+    #[test]
+    fn delimiter_is_the_most_frequent_separator_in_the_header() {
+        assert_eq!(detect_delimiter("Ticket\tOpen Time\tType\tSize"), b'\t');
+        assert_eq!(detect_delimiter("Ticket;Open Time;Type;Size\n1;2;3;4,5"), b';');
+        assert_eq!(detect_delimiter("Symbol,Side,Qty"), b',');
+        assert_eq!(detect_delimiter(""), b',');
+    }
+
+    #[test]
+    fn numbers_keep_their_sign_and_drop_currency_decoration() {
+        assert_eq!(parse_decimal("$1,234.50", "pnl", b',').unwrap(), decimal("1234.50"));
+        assert_eq!(parse_decimal("(1,234.50)", "pnl", b',').unwrap(), decimal("-1234.50"));
+        assert_eq!(parse_decimal("-12.5", "pnl", b'\t').unwrap(), decimal("-12.5"));
+        assert_eq!(parse_decimal("0.015BTC", "fee", b',').unwrap(), decimal("0.015"));
+        assert_eq!(parse_decimal("€ 1,000,000", "pnl", b',').unwrap(), decimal("1000000"));
+        assert!(parse_decimal("n/a", "pnl", b',').is_err());
+    }
+
+    #[test]
+    fn semicolon_files_use_decimal_commas() {
+        assert_eq!(parse_decimal("1,10350", "Open Price", b';').unwrap(), decimal("1.10350"));
+        assert_eq!(parse_decimal("1.234,56", "Profit", b';').unwrap(), decimal("1234.56"));
+        assert_eq!(parse_decimal("-0,70", "Commission", b';').unwrap(), decimal("-0.70"));
+        // Files written with decimal points still parse
+        assert_eq!(parse_decimal("1.10350", "Open Price", b';').unwrap(), decimal("1.10350"));
+        assert!(parse_decimal("1,234,56", "Profit", b';').is_err());
+    }
+
+    #[test]
+    fn commas_that_are_not_thousands_separators_are_rejected() {
+        let error = parse_decimal("1,10350", "Open Price", b'\t').unwrap_err();
+        assert_eq!(error, "Ambiguous number '1,10350' in 'Open Price'");
+        assert!(parse_decimal("1,10350", "Open Price", b',').is_err());
+        assert!(parse_decimal("12345,678", "Profit", b',').is_err());
+        assert!(parse_decimal("1.5,0", "Profit", b',').is_err());
+    }
+}
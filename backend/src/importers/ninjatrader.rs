@@ -0,0 +1,96 @@
+use super::{
+    fills::{parse_fills, Fill},
+    parse_decimal, parse_utc, CsvTable, ParsedImport, TradeImporter,
+};
+use csv::StringRecord;
+
+/// NinjaTrader 8 "Executions" grid export.
+///
+/// Expected columns: `Instrument`, `Action`, `Quantity`, `Price`, `Time`,
+/// `ID` and optionally `Commission`. `Buy to cover` and `Sell short` actions
+/// are treated as plain buys and sells.
+pub struct NinjaTraderImporter;
+
+const TIME_FORMATS: &[&str] = &["%m/%d/%Y %I:%M:%S %p", "%m/%d/%Y %H:%M:%S", "%d.%m.%Y %H:%M:%S"];
+
+fn parse_fill(table: &CsvTable, row: usize, record: &StringRecord) -> Result<Option<Fill>, String> {
+    let action = table.field(record, "Action")?.to_lowercase();
+    let side = if action.starts_with("buy") {
+        "buy"
+    } else if action.starts_with("sell") {
+        "sell"
+    } else {
+        return Err(format!("Unknown Action value '{}'", action));
+    };
+
+    let fee = match table.optional(record, "Commission") {
+        Some(value) => parse_decimal(value, "Commission", table.delimiter)?,
+        None => Default::default(),
+    };
+
+    Ok(Some(Fill {
+        row,
+        external_id: table.field(record, "ID")?.to_string(),
+        symbol: table.field(record, "Instrument")?.to_string(),
+        side: side.to_string(),
+        price: parse_decimal(table.field(record, "Price")?, "Price", table.delimiter)?,
+        quantity: parse_decimal(table.field(record, "Quantity")?, "Quantity", table.delimiter)?,
+        executed_at: parse_utc(table.field(record, "Time")?, TIME_FORMATS, "Time")?,
+        fee,
+    }))
+}
+
+impl TradeImporter for NinjaTraderImporter {
+    fn broker(&self) -> &'static str {
+        "NinjaTrader"
+    }
+
+    fn parse(&self, content: &str) -> ParsedImport {
+        parse_fills(
+            self.broker(),
+            content,
+            b',',
+            &["Instrument", "Action", "Quantity", "Price", "Time", "ID"],
+            parse_fill,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{DateTime, Utc};
+    use rust_decimal::Decimal;
+
+    const EXPORT: &str = "\
+Instrument,Action,Quantity,Price,Time,ID,E/X,Commission
+ES 03-25,Sell short,2,5900.25,1/2/2025 9:30:00 AM,N1,Entry,4.30
+ES 03-25,Buy to cover,2,5890.00,1/2/2025 10:15:00 AM,N2,Exit,4.30
+NQ 03-25,Buy,1,21000,02.01.2025 16:00:00,N3,Entry,2.15
+NQ 03-25,Sell,1,21050,02.01.2025 16:30:00,N4,Exit,2.15
+";
+
+    #[test]
+    fn executions_become_round_trips() {
+        let parsed = NinjaTraderImporter.parse(EXPORT);
+
+        assert!(parsed.errors.is_empty(), "{:?}", parsed.errors);
+        assert_eq!(parsed.total_rows, 4);
+        assert_eq!(parsed.trades.len(), 2);
+
+        let es = &parsed.trades[0].trade;
+        assert_eq!(es.symbol, "ES 03-25");
+        assert_eq!(es.direction, "short");
+        assert_eq!(es.external_id.as_deref(), Some("N1"));
+        assert_eq!(es.broker.as_deref(), Some("NinjaTrader"));
+        assert_eq!(es.entry_time, "2025-01-02T09:30:00Z".parse::<DateTime<Utc>>().unwrap());
+        let executions = es.executions.as_ref().unwrap();
+        assert_eq!(executions[1].side, "buy");
+        assert_eq!(executions[1].fee, Some("4.30".parse::<Decimal>().unwrap()));
+
+        let nq = &parsed.trades[1].trade;
+        assert_eq!(nq.direction, "long");
+        assert_eq!(nq.external_id.as_deref(), Some("N3"));
+        assert_eq!(nq.entry_time, "2025-01-02T16:00:00Z".parse::<DateTime<Utc>>().unwrap());
+    }
+}
@@ -0,0 +1,96 @@
+use super::{
+    fills::{parse_fills, Fill},
+    parse_decimal, parse_utc, CsvTable, ParsedImport, TradeImporter,
+};
+use csv::StringRecord;
+
+/// TradingView paper trading "Order History" export.
+///
+/// Expected columns: `Symbol`, `Side`, `Qty`, `Fill Price`, `Status`,
+/// `Closing Time`, `Order ID` and optionally `Commission`. Only orders with
+/// status `Filled` are imported. Exchange prefixes such as `NASDAQ:` are
+/// stripped from the symbol.
+pub struct TradingViewImporter;
+
+const TIME_FORMATS: &[&str] = &["%Y-%m-%d %H:%M:%S", "%Y-%m-%d %H:%M"];
+
+fn parse_fill(table: &CsvTable, row: usize, record: &StringRecord) -> Result<Option<Fill>, String> {
+    if !table.field(record, "Status")?.eq_ignore_ascii_case("filled") {
+        return Ok(None);
+    }
+
+    let side = match table.field(record, "Side")?.to_lowercase().as_str() {
+        "buy" => "buy",
+        "sell" => "sell",
+        other => return Err(format!("Unknown Side value '{}'", other)),
+    };
+
+    let symbol = table.field(record, "Symbol")?;
+    let symbol = symbol.rsplit(':').next().unwrap_or(symbol);
+
+    let fee = match table.optional(record, "Commission") {
+        Some(value) => parse_decimal(value, "Commission", table.delimiter)?,
+        None => Default::default(),
+    };
+
+    Ok(Some(Fill {
+        row,
+        external_id: table.field(record, "Order ID")?.to_string(),
+        symbol: symbol.to_string(),
+        side: side.to_string(),
+        price: parse_decimal(table.field(record, "Fill Price")?, "Fill Price", table.delimiter)?,
+        quantity: parse_decimal(table.field(record, "Qty")?, "Qty", table.delimiter)?,
+        executed_at: parse_utc(table.field(record, "Closing Time")?, TIME_FORMATS, "Closing Time")?,
+        fee,
+    }))
+}
+
+impl TradeImporter for TradingViewImporter {
+    fn broker(&self) -> &'static str {
+        "TradingView"
+    }
+
+    fn parse(&self, content: &str) -> ParsedImport {
+        parse_fills(
+            self.broker(),
+            content,
+            b',',
+            &["Symbol", "Side", "Qty", "Fill Price", "Status", "Closing Time", "Order ID"],
+            parse_fill,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{DateTime, Utc};
+    use rust_decimal::Decimal;
+
+    const EXPORT: &str = "\
+Symbol,Side,Type,Qty,Fill Price,Status,Commission,Placing Time,Closing Time,Order ID
+NASDAQ:TSLA,Buy,Market,10,250.5,Filled,1,2025-01-02 15:00:00,2025-01-02 15:00:00,T1
+NASDAQ:TSLA,Sell,Limit,10,,Cancelled,,2025-01-02 15:05:00,2025-01-02 17:00:00,T2
+NASDAQ:TSLA,Sell,Limit,10,260,Filled,1,2025-01-02 15:05:00,2025-01-02 18:00,T3
+";
+
+    #[test]
+    fn filled_orders_become_round_trips() {
+        let parsed = TradingViewImporter.parse(EXPORT);
+
+        assert!(parsed.errors.is_empty(), "{:?}", parsed.errors);
+        assert_eq!(parsed.total_rows, 3);
+        assert_eq!(parsed.trades.len(), 1);
+
+        let trade = &parsed.trades[0].trade;
+        // The exchange prefix is dropped
+        assert_eq!(trade.symbol, "TSLA");
+        assert_eq!(trade.direction, "long");
+        assert_eq!(trade.external_id.as_deref(), Some("T1"));
+        assert_eq!(trade.broker.as_deref(), Some("TradingView"));
+        let executions = trade.executions.as_ref().unwrap();
+        assert_eq!(executions.len(), 2);
+        assert_eq!(executions[1].price, Decimal::from(260));
+        assert_eq!(executions[1].executed_at, "2025-01-02T18:00:00Z".parse::<DateTime<Utc>>().unwrap());
+    }
+}
@@ -0,0 +1,87 @@
+use super::{
+    fills::{parse_fills, Fill},
+    parse_decimal, parse_utc, CsvTable, ParsedImport, TradeImporter,
+};
+use csv::StringRecord;
+
+/// Tradovate "Orders" report export.
+///
+/// Expected columns: `orderId`, `B/S`, `Contract`, `avgPrice`, `filledQty`,
+/// `Fill Time` and `Status`. Only `Filled` orders are imported; the report
+/// carries no commissions, so fees are left at zero.
+pub struct TradovateImporter;
+
+const TIME_FORMATS: &[&str] = &["%m/%d/%Y %H:%M:%S", "%m/%d/%Y %H:%M", "%Y-%m-%d %H:%M:%S"];
+
+fn parse_fill(table: &CsvTable, row: usize, record: &StringRecord) -> Result<Option<Fill>, String> {
+    if !table.field(record, "Status")?.trim().eq_ignore_ascii_case("filled") {
+        return Ok(None);
+    }
+
+    let side = match table.field(record, "B/S")?.trim().to_lowercase().as_str() {
+        "buy" => "buy",
+        "sell" => "sell",
+        other => return Err(format!("Unknown B/S value '{}'", other)),
+    };
+
+    Ok(Some(Fill {
+        row,
+        external_id: table.field(record, "orderId")?.to_string(),
+        symbol: table.field(record, "Contract")?.to_string(),
+        side: side.to_string(),
+        price: parse_decimal(table.field(record, "avgPrice")?, "avgPrice", table.delimiter)?,
+        quantity: parse_decimal(table.field(record, "filledQty")?, "filledQty", table.delimiter)?,
+        executed_at: parse_utc(table.field(record, "Fill Time")?, TIME_FORMATS, "Fill Time")?,
+        fee: Default::default(),
+    }))
+}
+
+impl TradeImporter for TradovateImporter {
+    fn broker(&self) -> &'static str {
+        "Tradovate"
+    }
+
+    fn parse(&self, content: &str) -> ParsedImport {
+        parse_fills(
+            self.broker(),
+            content,
+            b',',
+            &["orderId", "B/S", "Contract", "avgPrice", "filledQty", "Fill Time", "Status"],
+            parse_fill,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{DateTime, Utc};
+    use rust_decimal::Decimal;
+
+    const EXPORT: &str = "\
+orderId,Account,B/S,Contract,Product,avgPrice,filledQty,Fill Time,Status
+T100,DEMO1, Buy,NQH5,NQ,21000.25,1,01/02/2025 14:30:00, Filled
+T101,DEMO1, Sell,NQH5,NQ,,0,, Canceled
+T102,DEMO1, Sell,NQH5,NQ,21010.75,1,01/02/2025 14:45,Filled
+";
+
+    #[test]
+    fn filled_orders_become_round_trips() {
+        let parsed = TradovateImporter.parse(EXPORT);
+
+        assert!(parsed.errors.is_empty(), "{:?}", parsed.errors);
+        assert_eq!(parsed.total_rows, 3);
+        assert_eq!(parsed.trades.len(), 1);
+
+        let trade = &parsed.trades[0].trade;
+        assert_eq!(trade.symbol, "NQH5");
+        assert_eq!(trade.direction, "long");
+        assert_eq!(trade.external_id.as_deref(), Some("T100"));
+        assert_eq!(trade.broker.as_deref(), Some("Tradovate"));
+        let executions = trade.executions.as_ref().unwrap();
+        assert_eq!(executions[0].price, "21000.25".parse::<Decimal>().unwrap());
+        assert_eq!(executions[1].executed_at, "2025-01-02T14:45:00Z".parse::<DateTime<Utc>>().unwrap());
+        // The report has no commissions
+        assert!(executions.iter().all(|e| e.fee == Some(Decimal::ZERO)));
+    }
+}
@@ -3,20 +3,32 @@ pub mod config;
 pub mod db;
 pub mod error;
 pub mod handlers;
+pub mod importers;
 pub mod middleware;
 pub mod models;
+pub mod pricing;
 pub mod repositories;
 pub mod services;
+pub mod storage;
+#[cfg(test)]
+pub(crate) mod test_support;
 
 pub use config::Config;
 pub use error::{AppError, Result};
 
+use pricing::PriceSource;
 use sqlx::PgPool;
+use std::sync::Arc;
+use storage::BlobStore;
 
 /// Application state shared across handlers
 #[derive(Clone)]
 pub struct AppState {
     pub db: PgPool,
     pub config: Config,
+    /// Where attachment files are kept
+    pub blobs: Arc<dyn BlobStore>,
+    /// Where open trades are marked to market from
+    pub prices: Arc<dyn PriceSource>,
 }
 
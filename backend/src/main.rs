@@ -1,4 +1,5 @@
 use axum::{
+    extract::DefaultBodyLimit,
     middleware,
     routing::{delete, get, post, put},
     Router,
@@ -10,8 +11,8 @@ use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 use trading_journal_backend::{
     db::{create_pool, run_migrations},
     handlers,
-    middleware::auth_middleware,
-    AppState, Config,
+    middleware::{auth_middleware, request_id_middleware, REQUEST_ID_HEADER},
+    pricing, storage, AppState, Config,
 };
 
 #[tokio::main]
@@ -39,17 +40,26 @@ async fn main() {
         .await
         .expect("Failed to run migrations");
 
+    // Open attachment storage
+    let blobs = storage::from_config(&config).expect("Failed to configure attachment storage");
+
+    // Choose where open trades are priced from
+    let prices = pricing::from_config(&config, &db).expect("Failed to configure price source");
+
     // Create application state
     let state = AppState {
         db: db.clone(),
         config: config.clone(),
+        blobs,
+        prices,
     };
 
     // CORS configuration
     let cors = CorsLayer::new()
         .allow_origin(Any)
         .allow_methods(Any)
-        .allow_headers(Any);
+        .allow_headers(Any)
+        .expose_headers([REQUEST_ID_HEADER.clone()]);
 
     // Public routes (no authentication required)
     let public_routes = Router::new()
@@ -62,13 +72,93 @@ async fn main() {
         .route("/subscriptions/checkout", post(handlers::create_checkout_session))
         .route("/trades", post(handlers::create_trade))
         .route("/trades", get(handlers::list_trades))
+        .route("/trades/import", post(handlers::import_trades))
+        .route("/trades/export", get(handlers::export_trades))
+        .route("/trades/expire-options", post(handlers::expire_options))
         .route("/trades/:id", get(handlers::get_trade))
         .route("/trades/:id", put(handlers::update_trade))
         .route("/trades/:id", delete(handlers::delete_trade))
+        .route("/trades/:id/executions", get(handlers::list_trade_executions))
+        .route("/trades/:id/legs", get(handlers::list_trade_legs))
+        .route("/trades/:id/replay", get(handlers::get_trade_replay))
+        .route("/trades/:id/history", get(handlers::get_trade_history))
+        .route("/trades/:id/history/:revision/restore", post(handlers::restore_trade_revision))
+        .route("/trades/:id/legs/:leg_id/close", post(handlers::close_option_leg))
+        .route("/trades/:id/attachments", get(handlers::list_attachments))
+        // Paid plans allow 25 MB per file and several files per request
+        .route(
+            "/trades/:id/attachments",
+            post(handlers::upload_attachments).layer(DefaultBodyLimit::max(64 * 1024 * 1024)),
+        )
+        .route("/trades/:id/attachments/:attachment_id", get(handlers::download_attachment))
+        .route("/trades/:id/attachments/:attachment_id", delete(handlers::delete_attachment))
+        .route("/trades/:id/attachments/:attachment_id/thumbnail", get(handlers::download_attachment_thumbnail))
+        .route("/orders", get(handlers::list_orders))
+        .route("/orders", post(handlers::create_order))
+        .route("/orders/expire", post(handlers::expire_orders))
+        .route("/orders/:id", get(handlers::get_order))
+        .route("/orders/:id/fill", post(handlers::fill_order))
+        .route("/orders/:id/cancel", post(handlers::cancel_order))
+        .route("/accounts", post(handlers::create_account))
+        .route("/accounts", get(handlers::list_accounts))
+        .route("/accounts/:id", get(handlers::get_account))
+        .route("/accounts/:id", put(handlers::update_account))
+        .route("/accounts/:id", delete(handlers::delete_account))
+        .route("/accounts/:id/cash-movements", get(handlers::list_cash_movements))
+        .route("/accounts/:id/cash-movements", post(handlers::create_cash_movement))
+        .route("/accounts/:id/cash-movements/:movement_id", delete(handlers::delete_cash_movement))
+        .route("/instruments", get(handlers::list_instruments))
+        .route("/instruments", post(handlers::create_instrument))
+        .route("/instruments/:id", put(handlers::update_instrument))
+        .route("/instruments/:id", delete(handlers::delete_instrument))
+        .route("/journal-entries", get(handlers::list_journal_entries))
+        .route("/journal-entries", post(handlers::create_journal_entry))
+        .route("/journal-entries/:id", get(handlers::get_journal_entry))
+        .route("/journal-entries/:id", put(handlers::update_journal_entry))
+        .route("/journal-entries/:id", delete(handlers::delete_journal_entry))
+        .route("/playbooks", get(handlers::list_playbooks))
+        .route("/playbooks", post(handlers::create_playbook))
+        .route("/playbooks/:id", get(handlers::get_playbook))
+        .route("/playbooks/:id", put(handlers::update_playbook))
+        .route("/playbooks/:id", delete(handlers::delete_playbook))
+        .route("/settings", get(handlers::get_settings))
+        .route("/settings", put(handlers::update_settings))
+        .route("/vocabularies/:kind", get(handlers::get_vocabulary))
+        .route("/vocabularies/:kind", put(handlers::update_vocabulary))
+        .route("/vocabularies/:kind/values", get(handlers::list_vocabulary_values))
+        .route("/vocabularies/:kind/merge", post(handlers::merge_vocabulary_values))
+        .route("/vocabularies/:kind/terms", post(handlers::create_vocabulary_term))
+        .route("/vocabularies/:kind/terms/:id", put(handlers::update_vocabulary_term))
+        .route("/vocabularies/:kind/terms/:id", delete(handlers::delete_vocabulary_term))
+        .route("/fx-rates", get(handlers::list_fx_rates))
+        .route("/fx-rates", post(handlers::create_fx_rate))
+        .route("/fx-rates/import", post(handlers::import_fx_rates))
+        .route("/fx-rates/:id", delete(handlers::delete_fx_rate))
+        .route("/price-bars", get(handlers::list_price_bars))
+        .route("/price-bars", delete(handlers::delete_price_bars))
+        // Intraday bar files are larger than the default 2 MB body limit
+        .route(
+            "/price-bars/import",
+            post(handlers::import_price_bars).layer(DefaultBodyLimit::max(64 * 1024 * 1024)),
+        )
+        .route("/quotes", get(handlers::list_quotes))
+        .route("/quotes/import", post(handlers::import_quotes))
+        .route("/quotes/:symbol", put(handlers::set_quote))
+        .route("/quotes/:symbol", delete(handlers::delete_quote))
         .route("/analytics/overview", get(handlers::get_overview))
+        .route("/analytics/positions", get(handlers::get_open_positions))
         .route("/analytics/symbols", get(handlers::get_by_symbol))
         .route("/analytics/setups", get(handlers::get_by_setup))
+        .route("/analytics/playbooks", get(handlers::get_by_playbook))
         .route("/analytics/mistakes", get(handlers::get_mistakes))
+        .route("/analytics/time", get(handlers::get_time_breakdowns))
+        .route("/analytics/equity-curve", get(handlers::get_equity_curve))
+        .route("/analytics/risk", get(handlers::get_risk_metrics))
+        .route("/analytics/r-multiples", get(handlers::get_r_multiples))
+        .route("/analytics/excursions", get(handlers::get_excursions))
+        .route("/analytics/accounts", get(handlers::get_account_performance))
+        .route("/analytics/journal", get(handlers::get_journal_analytics))
+        .route("/analytics/calendar", get(handlers::get_calendar))
         .layer(middleware::from_fn_with_state(state.clone(), auth_middleware));
 
     // Combine all API routes under /api prefix
@@ -81,6 +171,7 @@ async fn main() {
         .route("/health", get(health_check))
         .route("/webhooks/stripe", post(handlers::handle_stripe_webhook))
         .nest("/api", api_routes)
+        .layer(middleware::from_fn(request_id_middleware))
         .layer(cors)
         .with_state(state);
 
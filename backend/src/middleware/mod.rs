@@ -1,4 +1,5 @@
 pub mod auth;
+pub mod request_id;
 
 pub use auth::{auth_middleware, AuthUser};
-
+pub use request_id::{current_request_id, request_id_middleware, REQUEST_ID_HEADER};
@@ -0,0 +1,78 @@
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Brokerage account model from database
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct Account {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub name: String,
+    pub broker: Option<String>,
+    pub base_currency: String,
+    pub starting_balance: Decimal,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Create account request
+#[derive(Debug, Deserialize)]
+pub struct CreateAccountRequest {
+    pub name: String,
+    pub broker: Option<String>,
+    pub base_currency: Option<String>,
+    pub starting_balance: Option<Decimal>,
+}
+
+/// Update account request
+#[derive(Debug, Deserialize)]
+pub struct UpdateAccountRequest {
+    pub name: Option<String>,
+    pub broker: Option<String>,
+    pub base_currency: Option<String>,
+    pub starting_balance: Option<Decimal>,
+}
+
+/// Deposit, withdrawal, fee or interest booked to an account
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct CashMovement {
+    pub id: Uuid,
+    pub account_id: Uuid,
+    pub user_id: Uuid,
+    pub kind: String, // "deposit", "withdrawal", "fee", "interest"
+    pub amount: Decimal,
+    pub occurred_at: DateTime<Utc>,
+    pub notes: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Create cash movement request
+#[derive(Debug, Deserialize)]
+pub struct CreateCashMovementRequest {
+    pub kind: String,
+    pub amount: Decimal,
+    pub occurred_at: DateTime<Utc>,
+    pub notes: Option<String>,
+}
+
+impl CashMovement {
+    /// Money added to or taken out of the account by its owner
+    pub fn external_flow(&self) -> Decimal {
+        match self.kind.as_str() {
+            "deposit" => self.amount,
+            "withdrawal" => -self.amount,
+            _ => Decimal::ZERO,
+        }
+    }
+
+    /// Interest earned less fees charged; counts as return, not as a flow
+    pub fn income(&self) -> Decimal {
+        match self.kind.as_str() {
+            "interest" => self.amount,
+            "fee" => -self.amount,
+            _ => Decimal::ZERO,
+        }
+    }
+}
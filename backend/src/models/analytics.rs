@@ -0,0 +1,69 @@
+use rust_decimal::Decimal;
+use serde::Deserialize;
+
+/// Query parameters for the equity curve
+#[derive(Debug, Deserialize)]
+pub struct EquityCurveQuery {
+    /// "trade" (default), "day", "week" or "month"
+    pub interval: Option<String>,
+    /// IANA name used to bucket by local day, week and month; defaults to
+    /// the user's timezone
+    pub timezone: Option<String>,
+    /// Equity before the first trade, used for percent drawdown
+    pub starting_equity: Option<Decimal>,
+}
+
+/// Query parameters for risk metrics
+#[derive(Debug, Deserialize)]
+pub struct RiskMetricsQuery {
+    /// IANA name used to cut daily returns; defaults to the user's timezone
+    pub timezone: Option<String>,
+    /// Equity before the first trade; when set, daily returns are fractions
+    /// of equity instead of currency amounts
+    pub starting_equity: Option<Decimal>,
+}
+
+/// Query parameters for R-multiple analytics
+#[derive(Debug, Deserialize)]
+pub struct RMultipleQuery {
+    /// Width of the histogram buckets in R; defaults to 1
+    pub bucket_size: Option<Decimal>,
+}
+
+/// Query parameters for account performance
+#[derive(Debug, Deserialize)]
+pub struct AccountPerformanceQuery {
+    /// IANA name used to cut days; defaults to the user's timezone
+    pub timezone: Option<String>,
+}
+
+/// Query parameters for time breakdowns
+#[derive(Debug, Deserialize)]
+pub struct TimeBreakdownQuery {
+    /// IANA name entry hours, weekdays and months are read in; defaults to
+    /// the user's timezone
+    pub timezone: Option<String>,
+    /// Comma-separated sessions as `name=HH:MM-HH:MM`, optionally followed
+    /// by `@` and the IANA timezone of the hours (the `timezone` parameter
+    /// otherwise), e.g. `London=08:00-16:30@Europe/London`. Defaults to the
+    /// Asia (Tokyo), London and New York cash sessions.
+    pub sessions: Option<String>,
+}
+
+/// Query parameters for the P&L calendar
+#[derive(Debug, Deserialize)]
+pub struct CalendarQuery {
+    /// `YYYY-MM`
+    pub month: String,
+    /// IANA name used to cut days; defaults to the user's timezone
+    pub timezone: Option<String>,
+}
+
+/// Currency analytics are reported in
+#[derive(Debug, Deserialize)]
+pub struct ReportingQuery {
+    /// 3-letter code; P&L in other currencies is converted at the latest
+    /// rate on or before each trade's exit date. Defaults to the user's
+    /// reporting currency; without either, amounts are added up as recorded.
+    pub currency: Option<String>,
+}
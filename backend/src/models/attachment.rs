@@ -0,0 +1,57 @@
+use axum::body::Bytes;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// File attached to a trade; its bytes live in the blob store
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct Attachment {
+    pub id: Uuid,
+    pub trade_id: Uuid,
+    pub user_id: Uuid,
+    pub file_name: String,
+    /// Sniffed from the file contents
+    pub content_type: String,
+    pub size_bytes: i64,
+    pub width: Option<i32>,
+    pub height: Option<i32>,
+    #[serde(skip_serializing)]
+    pub storage_key: String,
+    #[serde(skip_serializing)]
+    pub thumbnail_key: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Attachment {
+    /// API path an attachment is downloaded from; image attachments are
+    /// listed under these paths in `Trade.screenshots`
+    pub fn download_path(trade_id: Uuid, attachment_id: Uuid) -> String {
+        format!("/api/trades/{}/attachments/{}", trade_id, attachment_id)
+    }
+}
+
+/// Attachment record written once its files are stored
+#[derive(Debug, Clone)]
+pub struct NewAttachment {
+    pub id: Uuid,
+    pub file_name: String,
+    pub content_type: String,
+    pub size_bytes: i64,
+    pub width: Option<i32>,
+    pub height: Option<i32>,
+    pub storage_key: String,
+    pub thumbnail_key: Option<String>,
+}
+
+/// Uploaded file after sniffing, metadata stripping and thumbnailing
+#[derive(Debug, Clone)]
+pub struct ProcessedUpload {
+    pub file_name: String,
+    pub content_type: &'static str,
+    pub data: Bytes,
+    pub width: Option<i32>,
+    pub height: Option<i32>,
+    /// Downscaled preview of an image and its content type
+    pub thumbnail: Option<(Bytes, &'static str)>,
+}
@@ -0,0 +1,167 @@
+use crate::error::{AppError, Result};
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Single fill belonging to a trade
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct TradeExecution {
+    pub id: Uuid,
+    pub trade_id: Uuid,
+    pub user_id: Uuid,
+    pub side: String, // "buy" or "sell"
+    pub price: Decimal,
+    pub quantity: Decimal,
+    pub executed_at: DateTime<Utc>,
+    pub fee: Decimal,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Create execution request
+#[derive(Debug, Clone, Deserialize)]
+pub struct CreateExecutionRequest {
+    pub side: String,
+    pub price: Decimal,
+    pub quantity: Decimal,
+    pub executed_at: DateTime<Utc>,
+    pub fee: Option<Decimal>,
+}
+
+impl From<&TradeExecution> for CreateExecutionRequest {
+    fn from(execution: &TradeExecution) -> Self {
+        CreateExecutionRequest {
+            side: execution.side.clone(),
+            price: execution.price,
+            quantity: execution.quantity,
+            executed_at: execution.executed_at,
+            fee: Some(execution.fee),
+        }
+    }
+}
+
+/// Trade-level values derived from a set of executions
+#[derive(Debug, Clone)]
+pub struct ExecutionSummary {
+    pub entry_price: Decimal,
+    pub exit_price: Option<Decimal>,
+    pub quantity: Decimal,
+    pub open_quantity: Decimal,
+    pub entry_time: DateTime<Utc>,
+    pub exit_time: Option<DateTime<Utc>>,
+    pub fees: Decimal,
+    pub pnl: Option<Decimal>,
+    pub pnl_percentage: Option<Decimal>,
+    pub status: String,
+}
+
+impl ExecutionSummary {
+    /// Derive trade values from its fills.
+    ///
+    /// Fills on the opening side (buy for long, sell for short) are averaged
+    /// into the entry price, fills on the closing side into the exit price.
+    /// Realized P&L is `(avg exit - avg entry) * closed quantity * multiplier`
+    /// less fees, sign-flipped for shorts; `fee_rate` converts the fees into
+    /// the trade currency. The trade is closed once the open quantity
+    /// reaches zero.
+    pub fn from_executions(
+        direction: &str,
+        multiplier: Decimal,
+        fee_rate: Decimal,
+        executions: &[CreateExecutionRequest],
+    ) -> Result<Self> {
+        let opening_side = match direction {
+            "long" => "buy",
+            "short" => "sell",
+            _ => {
+                return Err(AppError::ValidationError(
+                    "Direction must be 'long' or 'short'".to_string(),
+                ))
+            }
+        };
+
+        let mut fills: Vec<&CreateExecutionRequest> = executions.iter().collect();
+        fills.sort_by_key(|e| e.executed_at);
+
+        let mut entry_quantity = Decimal::ZERO;
+        let mut entry_value = Decimal::ZERO;
+        let mut exit_quantity = Decimal::ZERO;
+        let mut exit_value = Decimal::ZERO;
+        let mut fees = Decimal::ZERO;
+        let mut entry_time = None;
+        let mut last_exit_time = None;
+
+        for fill in fills {
+            if fill.side != "buy" && fill.side != "sell" {
+                return Err(AppError::ValidationError(
+                    "Execution side must be 'buy' or 'sell'".to_string(),
+                ));
+            }
+            if fill.quantity <= Decimal::ZERO || fill.price <= Decimal::ZERO {
+                return Err(AppError::ValidationError(
+                    "Execution price and quantity must be positive".to_string(),
+                ));
+            }
+
+            fees += fill.fee.unwrap_or(Decimal::ZERO);
+
+            if fill.side == opening_side {
+                entry_quantity += fill.quantity;
+                entry_value += fill.price * fill.quantity;
+                entry_time.get_or_insert(fill.executed_at);
+            } else {
+                exit_quantity += fill.quantity;
+                exit_value += fill.price * fill.quantity;
+                if exit_quantity > entry_quantity {
+                    return Err(AppError::ValidationError(
+                        "Executions close more than the open position".to_string(),
+                    ));
+                }
+                last_exit_time = Some(fill.executed_at);
+            }
+        }
+
+        let entry_time = entry_time.ok_or(AppError::ValidationError(
+            "At least one opening execution is required".to_string(),
+        ))?;
+
+        let entry_price = entry_value / entry_quantity;
+        let open_quantity = entry_quantity - exit_quantity;
+
+        let (exit_price, pnl, pnl_percentage) = if exit_quantity > Decimal::ZERO {
+            let exit_price = exit_value / exit_quantity;
+            let price_diff = if direction == "long" {
+                exit_price - entry_price
+            } else {
+                entry_price - exit_price
+            };
+
+            let pnl = price_diff * exit_quantity * multiplier - fees * fee_rate;
+            let pnl_percentage = (price_diff / entry_price) * Decimal::from(100);
+
+            (Some(exit_price), Some(pnl), Some(pnl_percentage))
+        } else {
+            (None, None, None)
+        };
+
+        let (status, exit_time) = if open_quantity.is_zero() {
+            ("closed", last_exit_time)
+        } else {
+            ("open", None)
+        };
+
+        Ok(ExecutionSummary {
+            entry_price,
+            exit_price,
+            quantity: entry_quantity,
+            open_quantity,
+            entry_time,
+            exit_time,
+            fees,
+            pnl,
+            pnl_percentage,
+            status: status.to_string(),
+        })
+    }
+}
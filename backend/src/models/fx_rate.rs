@@ -0,0 +1,58 @@
+use super::ImportRowError;
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// FX rate model from database: 1 `base_currency` = `rate` `quote_currency`
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct FxRate {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub base_currency: String,
+    pub quote_currency: String,
+    pub rate_date: NaiveDate,
+    pub rate: Decimal,
+    pub source: String, // "manual" or "import"
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Create or replace the rate of a pair on a date
+#[derive(Debug, Deserialize)]
+pub struct CreateFxRateRequest {
+    pub base_currency: String,
+    pub quote_currency: String,
+    pub rate_date: NaiveDate,
+    pub rate: Decimal,
+}
+
+/// FX rate list filters
+#[derive(Debug, Deserialize)]
+pub struct FxRateQuery {
+    pub base_currency: Option<String>,
+    pub quote_currency: Option<String>,
+    pub from_date: Option<NaiveDate>,
+    pub to_date: Option<NaiveDate>,
+}
+
+/// Result of an FX rate CSV import
+#[derive(Debug, Serialize)]
+pub struct FxImportReport {
+    pub total_rows: usize,
+    pub imported: usize,
+    pub failed: usize,
+    pub errors: Vec<ImportRowError>,
+}
+
+/// Rate used to convert a group of trades into the reporting currency
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct FxRateUsed {
+    pub from_currency: String,
+    pub to_currency: String,
+    pub rate_date: NaiveDate,
+    pub rate: Decimal,
+    /// Trades converted at this rate; zero for account capital
+    pub trades: i32,
+}
@@ -0,0 +1,47 @@
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Query parameters for a trade import
+#[derive(Debug, Deserialize)]
+pub struct ImportQuery {
+    pub broker: String,
+    pub dry_run: Option<bool>,
+    pub account_id: Option<Uuid>,
+}
+
+/// Per-trade line of an import report
+#[derive(Debug, Serialize)]
+pub struct ImportPreview {
+    pub row: usize,
+    pub external_id: Option<String>,
+    pub symbol: String,
+    pub direction: String,
+    pub entry_time: DateTime<Utc>,
+    pub entry_price: Decimal,
+    pub exit_price: Option<Decimal>,
+    pub quantity: Decimal,
+    pub pnl: Option<Decimal>,
+    pub status: String, // "imported", "new" (dry run), "duplicate"
+}
+
+/// Row-level error of an import
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportRowError {
+    pub row: usize,
+    pub message: String,
+}
+
+/// Import result
+#[derive(Debug, Serialize)]
+pub struct ImportReport {
+    pub broker: String,
+    pub dry_run: bool,
+    pub total_rows: usize,
+    pub imported: usize,
+    pub duplicates: usize,
+    pub failed: usize,
+    pub trades: Vec<ImportPreview>,
+    pub errors: Vec<ImportRowError>,
+}
@@ -0,0 +1,47 @@
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Contract specification model from database.
+///
+/// Rows without a `user_id` form the shared catalog; a user's own row for
+/// the same symbol takes precedence for that user.
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct Instrument {
+    pub id: Uuid,
+    pub user_id: Option<Uuid>,
+    pub symbol: String,
+    pub asset_class: String, // "stock", "etf", "future", "option", "forex", "crypto", "cfd"
+    pub multiplier: Decimal,
+    pub tick_size: Option<Decimal>,
+    pub tick_value: Option<Decimal>,
+    pub quote_currency: String,
+    pub exchange: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Create instrument request
+#[derive(Debug, Deserialize)]
+pub struct CreateInstrumentRequest {
+    pub symbol: String,
+    pub asset_class: String,
+    pub multiplier: Option<Decimal>,
+    pub tick_size: Option<Decimal>,
+    pub tick_value: Option<Decimal>,
+    pub quote_currency: Option<String>,
+    pub exchange: Option<String>,
+}
+
+/// Update instrument request
+#[derive(Debug, Deserialize)]
+pub struct UpdateInstrumentRequest {
+    pub asset_class: Option<String>,
+    pub multiplier: Option<Decimal>,
+    pub tick_size: Option<Decimal>,
+    pub tick_value: Option<Decimal>,
+    pub quote_currency: Option<String>,
+    pub exchange: Option<String>,
+}
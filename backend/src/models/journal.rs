@@ -0,0 +1,61 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Journal page of one trading day, from database
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct JournalEntry {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub entry_date: NaiveDate,
+    pub pre_market_plan: Option<String>,
+    pub post_session_review: Option<String>,
+    pub market_notes: Option<String>,
+    pub plan_followed: Option<bool>,
+    /// 1 (poor) to 5 (excellent)
+    pub mood: Option<i16>,
+    /// 1 (exhausted) to 5 (fully rested)
+    pub energy: Option<i16>,
+    /// Trades taken that day, oldest entry first
+    pub trade_ids: Vec<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Create journal entry request
+#[derive(Debug, Deserialize)]
+pub struct CreateJournalEntryRequest {
+    pub entry_date: NaiveDate,
+    pub pre_market_plan: Option<String>,
+    pub post_session_review: Option<String>,
+    pub market_notes: Option<String>,
+    pub plan_followed: Option<bool>,
+    pub mood: Option<i16>,
+    pub energy: Option<i16>,
+    pub trade_ids: Option<Vec<Uuid>>,
+}
+
+/// Update journal entry request; `trade_ids` replaces the linked trades
+#[derive(Debug, Deserialize)]
+pub struct UpdateJournalEntryRequest {
+    pub entry_date: Option<NaiveDate>,
+    pub pre_market_plan: Option<String>,
+    pub post_session_review: Option<String>,
+    pub market_notes: Option<String>,
+    pub plan_followed: Option<bool>,
+    pub mood: Option<i16>,
+    pub energy: Option<i16>,
+    pub trade_ids: Option<Vec<Uuid>>,
+}
+
+/// Journal entry list filters, also selecting the days of journal analytics
+#[derive(Debug, Default, Deserialize)]
+pub struct JournalEntryQuery {
+    /// First day, inclusive
+    pub from: Option<NaiveDate>,
+    /// Last day, inclusive
+    pub to: Option<NaiveDate>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
@@ -1,11 +1,64 @@
+pub mod account;
+pub mod analytics;
+pub mod attachment;
+pub mod execution;
+pub mod fx_rate;
+pub mod import;
+pub mod instrument;
+pub mod journal;
+pub mod option_leg;
+pub mod order;
+pub mod playbook;
+pub mod price_bar;
+pub mod quote;
+pub mod replay;
 pub mod subscription;
 pub mod trade;
+pub mod trade_revision;
 pub mod user;
+pub mod user_settings;
+pub mod vocabulary;
 
+pub use account::{
+    Account, CashMovement, CreateAccountRequest, CreateCashMovementRequest, UpdateAccountRequest,
+};
+pub use analytics::{
+    AccountPerformanceQuery, CalendarQuery, EquityCurveQuery, RMultipleQuery, ReportingQuery, RiskMetricsQuery, TimeBreakdownQuery,
+};
+pub use attachment::{Attachment, NewAttachment, ProcessedUpload};
+pub use execution::{CreateExecutionRequest, ExecutionSummary, TradeExecution};
+pub use fx_rate::{CreateFxRateRequest, FxImportReport, FxRate, FxRateQuery, FxRateUsed};
+pub use import::{ImportPreview, ImportQuery, ImportReport, ImportRowError};
+pub use instrument::{CreateInstrumentRequest, Instrument, UpdateInstrumentRequest};
+pub use journal::{
+    CreateJournalEntryRequest, JournalEntry, JournalEntryQuery, UpdateJournalEntryRequest,
+};
+pub use option_leg::{
+    expiration_time, CloseOptionLegRequest, CreateOptionLegRequest, ExpireOptionsRequest, LegSummary, OptionLeg,
+    OPTION_MULTIPLIER,
+};
+pub use order::{
+    CancelOrderRequest, CreateOrderRequest, ExpireOrdersRequest, FillOrderRequest, Order, OrderQuery, OrderWithTrade,
+    ORDER_TYPES,
+};
+pub use playbook::{CreatePlaybookRequest, Playbook, UpdatePlaybookRequest};
+pub use price_bar::{
+    timeframe_duration, CreatePriceBarRequest, Excursion, PriceBar, PriceBarImportQuery, PriceBarImportReport,
+    PriceBarQuery, TIMEFRAMES,
+};
+pub use quote::{Quote, QuoteImportReport, SetQuoteRequest};
+pub use replay::{ReplayBar, ReplayLevel, ReplayMarker, ReplayQuery, TradeReplay};
 pub use subscription::{
     CheckoutSessionResponse, CreateCheckoutRequest, SubscriptionInterval, SubscriptionStatus,
     SubscriptionTier, STRIPE_PRICE_IDS,
 };
-pub use trade::{CreateTradeRequest, Trade, TradeFilters, UpdateTradeRequest};
+pub use trade::{
+    round_trip_pnl, CreateTradeRequest, ExportQuery, Trade, TradeFilters, UpdateTradeRequest,
+};
+pub use trade_revision::TradeRevision;
 pub use user::{AuthResponse, CreateUserRequest, LoginRequest, User, UserResponse};
-
+pub use user_settings::{UpdateUserSettingsRequest, UserSettings};
+pub use vocabulary::{
+    CreateVocabularyTermRequest, MergeReport, MergeVocabularyRequest, UpdateVocabularyRequest,
+    UpdateVocabularyTermRequest, Vocabulary, VocabularyKind, VocabularyTerm, VocabularyValue,
+};
@@ -0,0 +1,328 @@
+use crate::error::{AppError, Result};
+use chrono::{DateTime, NaiveDate, NaiveTime, TimeZone, Utc};
+use chrono_tz::America::New_York;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Contract multiplier of listed equity options
+pub const OPTION_MULTIPLIER: i64 = 100;
+
+/// Option or stock leg belonging to a trade
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct OptionLeg {
+    pub id: Uuid,
+    pub trade_id: Uuid,
+    pub user_id: Uuid,
+    pub underlying: String,
+    pub option_type: String, // "call", "put" or "stock"
+    pub expiry: Option<NaiveDate>,
+    pub strike: Option<Decimal>,
+    pub multiplier: Decimal,
+    pub side: String, // "buy" or "sell"
+    pub quantity: Decimal,
+    /// Opening price per share (per unit for stock legs)
+    pub premium: Decimal,
+    pub opened_at: DateTime<Utc>,
+    pub fees: Decimal,
+    pub close_premium: Option<Decimal>,
+    pub closed_at: Option<DateTime<Utc>>,
+    pub close_reason: Option<String>, // "closed", "expired", "assigned", "exercised"
+    pub created_at: DateTime<Utc>,
+}
+
+/// Create option leg request
+///
+/// `expiry` and `strike` are required for calls and puts and absent for
+/// stock legs. `multiplier` defaults to 100 for options and 1 for stock,
+/// `opened_at` to the trade's entry time. A leg is closed by giving
+/// `close_premium` and `closed_at`; an `expired` leg closes at zero at the
+/// expiry date.
+#[derive(Debug, Clone, Deserialize)]
+pub struct CreateOptionLegRequest {
+    pub underlying: String,
+    pub option_type: String,
+    pub expiry: Option<NaiveDate>,
+    pub strike: Option<Decimal>,
+    pub multiplier: Option<Decimal>,
+    pub side: String,
+    pub quantity: Decimal,
+    pub premium: Decimal,
+    pub opened_at: Option<DateTime<Utc>>,
+    pub fees: Option<Decimal>,
+    pub close_premium: Option<Decimal>,
+    pub closed_at: Option<DateTime<Utc>>,
+    pub close_reason: Option<String>,
+}
+
+/// Close one open leg of a trade
+///
+/// `premium` is required for `closed` and ignored otherwise: expired legs
+/// close at zero, and assigned or exercised legs close at zero while the
+/// shares change hands at the strike. `closed_at` defaults to now, or to
+/// the expiry date for `expired`.
+#[derive(Debug, Deserialize)]
+pub struct CloseOptionLegRequest {
+    pub reason: String,
+    pub premium: Option<Decimal>,
+    pub closed_at: Option<DateTime<Utc>>,
+}
+
+/// Expire open option legs whose expiry has passed
+#[derive(Debug, Deserialize)]
+pub struct ExpireOptionsRequest {
+    /// Legs expiring before this date are closed; defaults to today (UTC)
+    pub as_of: Option<NaiveDate>,
+}
+
+impl From<&OptionLeg> for CreateOptionLegRequest {
+    fn from(leg: &OptionLeg) -> Self {
+        CreateOptionLegRequest {
+            underlying: leg.underlying.clone(),
+            option_type: leg.option_type.clone(),
+            expiry: leg.expiry,
+            strike: leg.strike,
+            multiplier: Some(leg.multiplier),
+            side: leg.side.clone(),
+            quantity: leg.quantity,
+            premium: leg.premium,
+            opened_at: Some(leg.opened_at),
+            fees: Some(leg.fees),
+            close_premium: leg.close_premium,
+            closed_at: leg.closed_at,
+            close_reason: leg.close_reason.clone(),
+        }
+    }
+}
+
+impl CreateOptionLegRequest {
+    /// Check the leg and fill in defaults; `entry_time` is the trade's
+    pub fn normalize(&mut self, entry_time: DateTime<Utc>) -> Result<()> {
+        let invalid = |message: &str| Err(AppError::ValidationError(message.to_string()));
+
+        self.underlying = self.underlying.trim().to_uppercase();
+        if self.underlying.is_empty() || self.underlying.len() > 20 {
+            return invalid("Underlying must be 1 to 20 characters");
+        }
+        if self.side != "buy" && self.side != "sell" {
+            return invalid("Leg side must be 'buy' or 'sell'");
+        }
+        if self.quantity <= Decimal::ZERO || self.premium < Decimal::ZERO {
+            return invalid("Leg quantity must be positive and premium not negative");
+        }
+        if self.multiplier.is_some_and(|m| m <= Decimal::ZERO) {
+            return invalid("Leg multiplier must be positive");
+        }
+
+        match self.option_type.as_str() {
+            "call" | "put" => {
+                if self.expiry.is_none() || self.strike.is_none_or(|s| s <= Decimal::ZERO) {
+                    return invalid("Option legs need an expiry and a positive strike");
+                }
+                self.multiplier.get_or_insert(Decimal::from(OPTION_MULTIPLIER));
+            }
+            "stock" => {
+                if self.expiry.is_some() || self.strike.is_some() {
+                    return invalid("Stock legs have no expiry or strike");
+                }
+                self.multiplier.get_or_insert(Decimal::ONE);
+            }
+            _ => return invalid("Option type must be 'call', 'put' or 'stock'"),
+        }
+
+        self.opened_at.get_or_insert(entry_time);
+
+        match self.close_reason.as_deref() {
+            None | Some("closed") => {}
+            Some("expired") => {
+                if self.option_type == "stock" {
+                    return invalid("Only option legs can expire");
+                }
+                self.close_premium = Some(Decimal::ZERO);
+                if self.closed_at.is_none() {
+                    self.closed_at = self.expiry.map(expiration_time);
+                }
+            }
+            // Stock legs closed by a delivery keep the strike as their price
+            Some("assigned") | Some("exercised") => {
+                if self.option_type != "stock" {
+                    self.close_premium = Some(Decimal::ZERO);
+                }
+            }
+            Some(_) => return invalid("Close reason must be 'closed', 'expired', 'assigned' or 'exercised'"),
+        }
+
+        if self.close_premium.is_some() != self.closed_at.is_some() {
+            return invalid("Closed legs need both a close premium and a close time");
+        }
+        if self.close_premium.is_some_and(|p| p < Decimal::ZERO) {
+            return invalid("Close premium cannot be negative");
+        }
+        if self.close_premium.is_some() && self.close_reason.is_none() {
+            self.close_reason = Some("closed".to_string());
+        }
+        if let (Some(opened_at), Some(closed_at)) = (self.opened_at, self.closed_at)
+            && closed_at < opened_at
+        {
+            return invalid("A leg can't close before it opens");
+        }
+
+        Ok(())
+    }
+
+    /// +1 for bought legs, -1 for sold legs
+    fn sign(&self) -> Decimal {
+        if self.side == "buy" {
+            Decimal::ONE
+        } else {
+            Decimal::NEGATIVE_ONE
+        }
+    }
+
+    fn units(&self) -> Decimal {
+        self.quantity * self.multiplier.unwrap_or(Decimal::ONE)
+    }
+}
+
+impl OptionLeg {
+    /// Stock leg delivered when this option is assigned or exercised.
+    ///
+    /// Calls deliver shares to the holder and puts take them, so a bought
+    /// call or sold put ends up buying stock at the strike, and a sold call
+    /// or bought put selling it.
+    pub fn delivery(&self, delivered_at: DateTime<Utc>) -> Option<CreateOptionLegRequest> {
+        let strike = self.strike?;
+        let buys_stock = (self.option_type == "call") == (self.side == "buy");
+
+        Some(CreateOptionLegRequest {
+            underlying: self.underlying.clone(),
+            option_type: "stock".to_string(),
+            expiry: None,
+            strike: None,
+            multiplier: Some(Decimal::ONE),
+            side: if buys_stock { "buy" } else { "sell" }.to_string(),
+            quantity: self.quantity * self.multiplier,
+            premium: strike,
+            opened_at: Some(delivered_at),
+            fees: Some(Decimal::ZERO),
+            close_premium: None,
+            closed_at: None,
+            close_reason: None,
+        })
+    }
+}
+
+/// Time an option stops trading on its expiry date: 4 pm New York
+pub fn expiration_time(expiry: NaiveDate) -> DateTime<Utc> {
+    let close = expiry.and_time(NaiveTime::from_hms_opt(16, 0, 0).unwrap_or_default());
+
+    // 4 pm is never skipped or repeated by a DST change
+    New_York
+        .from_local_datetime(&close)
+        .earliest()
+        .map_or_else(|| close.and_utc(), |time| time.with_timezone(&Utc))
+}
+
+/// Trade-level values derived from a set of legs
+#[derive(Debug, Clone)]
+pub struct LegSummary {
+    pub underlying: String,
+    pub direction: String,
+    pub entry_price: Decimal,
+    pub exit_price: Option<Decimal>,
+    pub quantity: Decimal,
+    pub open_quantity: Decimal,
+    pub multiplier: Decimal,
+    pub entry_time: DateTime<Utc>,
+    pub exit_time: Option<DateTime<Utc>>,
+    pub fees: Decimal,
+    pub pnl: Option<Decimal>,
+    pub pnl_percentage: Option<Decimal>,
+    pub status: String,
+}
+
+impl LegSummary {
+    /// Derive trade values from normalized legs.
+    ///
+    /// A strategy is one unit of its largest option leg (the stock leg when
+    /// there are no options). The net opening value is premium times
+    /// quantity times multiplier, summed with bought legs positive: a net
+    /// debit makes the trade long and a net credit short, and the entry
+    /// price is that value per unit. Realized P&L is
+    /// `(close - open premium) * quantity * multiplier` over closed legs,
+    /// sign-flipped for sold legs, less all fees converted at `fee_rate`.
+    /// The trade is closed once every leg is.
+    pub fn from_legs(legs: &[CreateOptionLegRequest], fee_rate: Decimal) -> Result<Self> {
+        let first = legs.first().ok_or(AppError::ValidationError(
+            "At least one leg is required".to_string(),
+        ))?;
+        if legs.iter().any(|leg| leg.underlying != first.underlying) {
+            return Err(AppError::ValidationError(
+                "All legs must share the same underlying".to_string(),
+            ));
+        }
+
+        let options: Vec<&CreateOptionLegRequest> = legs.iter().filter(|leg| leg.option_type != "stock").collect();
+        let sized = if options.is_empty() { legs.iter().collect() } else { options };
+        let quantity = sized.iter().map(|leg| leg.quantity).max().unwrap_or_default();
+        let multiplier = sized.first().and_then(|leg| leg.multiplier).unwrap_or(Decimal::ONE);
+        let open_quantity = sized
+            .iter()
+            .filter(|leg| leg.closed_at.is_none())
+            .map(|leg| leg.quantity)
+            .max()
+            .unwrap_or_default();
+
+        let mut open_value = Decimal::ZERO;
+        let mut close_value = Decimal::ZERO;
+        let mut realized = Decimal::ZERO;
+        let mut fees = Decimal::ZERO;
+        let mut any_closed = false;
+
+        for leg in legs {
+            let opened = leg.sign() * leg.premium * leg.units();
+            open_value += opened;
+            fees += leg.fees.unwrap_or_default();
+
+            if let Some(close_premium) = leg.close_premium {
+                let closed = leg.sign() * close_premium * leg.units();
+                close_value += closed;
+                realized += closed - opened;
+                any_closed = true;
+            }
+        }
+
+        let entry_time = legs.iter().filter_map(|leg| leg.opened_at).min().ok_or(AppError::ValidationError(
+            "Legs need an opening time".to_string(),
+        ))?;
+        let all_closed = legs.iter().all(|leg| leg.closed_at.is_some());
+        let exit_time = if all_closed {
+            legs.iter().filter_map(|leg| leg.closed_at).max()
+        } else {
+            None
+        };
+
+        let per_unit = |value: Decimal| (value / (quantity * multiplier)).abs();
+        let pnl = any_closed.then(|| realized - fees * fee_rate);
+        let pnl_percentage = pnl
+            .filter(|_| !open_value.is_zero())
+            .map(|pnl| pnl / open_value.abs() * Decimal::from(100));
+
+        Ok(LegSummary {
+            underlying: first.underlying.clone(),
+            direction: if open_value >= Decimal::ZERO { "long" } else { "short" }.to_string(),
+            entry_price: per_unit(open_value),
+            exit_price: all_closed.then(|| per_unit(close_value)),
+            quantity,
+            open_quantity,
+            multiplier,
+            entry_time,
+            exit_time,
+            fees,
+            pnl,
+            pnl_percentage,
+            status: if all_closed { "closed" } else { "open" }.to_string(),
+        })
+    }
+}
@@ -0,0 +1,113 @@
+use super::Trade;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Order types an entry can be planned with
+pub const ORDER_TYPES: &[&str] = &["market", "limit", "stop", "stop_limit"];
+
+/// Entry order of a trade. The trade is `pending` until the order fills,
+/// then `open`; unfilled orders leave it `cancelled` or `expired`.
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct Order {
+    pub id: Uuid,
+    pub trade_id: Uuid,
+    pub user_id: Uuid,
+    pub order_type: String,
+    /// Worst price the order fills at (limit and stop-limit orders)
+    pub limit_price: Option<Decimal>,
+    /// Price that triggers the order (stop and stop-limit orders)
+    pub stop_price: Option<Decimal>,
+    pub placed_at: DateTime<Utc>,
+    /// Good until cancelled when not set
+    pub expires_at: Option<DateTime<Utc>>,
+    pub status: String, // "pending", "filled", "cancelled", "expired"
+    pub filled_at: Option<DateTime<Utc>>,
+    pub fill_price: Option<Decimal>,
+    pub cancelled_at: Option<DateTime<Utc>>,
+    pub cancel_reason: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Order {
+    /// Price the order is planned to fill at: the limit, else the stop
+    pub fn planned_price(&self) -> Option<Decimal> {
+        self.limit_price.or(self.stop_price)
+    }
+}
+
+/// An order with its trade
+#[derive(Debug, Serialize)]
+pub struct OrderWithTrade {
+    #[serde(flatten)]
+    pub order: Order,
+    pub trade: Trade,
+}
+
+/// Place order request
+///
+/// The trade is created `pending` at the planned price: the limit price,
+/// else the stop price, or `entry_price` for market orders. Stops, targets
+/// and the other plan fields are recorded on it as for any trade.
+#[derive(Debug, Deserialize)]
+pub struct CreateOrderRequest {
+    pub order_type: String,
+    pub limit_price: Option<Decimal>,
+    pub stop_price: Option<Decimal>,
+    /// Expected fill of a market order
+    pub entry_price: Option<Decimal>,
+    /// Defaults to now
+    pub placed_at: Option<DateTime<Utc>>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub symbol: String,
+    pub direction: String,
+    pub quantity: Decimal,
+    pub currency: Option<String>,
+    pub stop_loss: Option<Decimal>,
+    pub take_profit: Option<Vec<Decimal>>,
+    pub planned_risk: Option<Decimal>,
+    pub notes: Option<String>,
+    pub tags: Option<Vec<String>>,
+    pub setup_type: Option<String>,
+    pub playbook_id: Option<Uuid>,
+    pub checklist_met: Option<Vec<String>>,
+    pub broker: Option<String>,
+    pub account_id: Option<Uuid>,
+}
+
+/// Order filters
+#[derive(Debug, Deserialize)]
+pub struct OrderQuery {
+    pub status: Option<String>,
+    pub symbol: Option<String>,
+}
+
+/// Fill order request; the trade opens at the fill
+#[derive(Debug, Deserialize)]
+pub struct FillOrderRequest {
+    /// Defaults to the planned price
+    pub price: Option<Decimal>,
+    /// Defaults to now
+    pub filled_at: Option<DateTime<Utc>>,
+    /// Filled size when less than ordered; the rest is dropped
+    pub quantity: Option<Decimal>,
+    pub fees: Option<Decimal>,
+}
+
+/// Cancel order request
+#[derive(Debug, Deserialize)]
+pub struct CancelOrderRequest {
+    pub reason: Option<String>,
+    /// Defaults to now
+    pub cancelled_at: Option<DateTime<Utc>>,
+}
+
+/// Expire pending orders whose expiry has passed
+#[derive(Debug, Deserialize)]
+pub struct ExpireOrdersRequest {
+    /// Orders expiring at or before this time lapse; defaults to now
+    pub as_of: Option<DateTime<Utc>>,
+}
@@ -0,0 +1,53 @@
+use crate::error::{AppError, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Setup definition model from database
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct Playbook {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    pub entry_criteria: Option<String>,
+    pub exit_criteria: Option<String>,
+    /// Conditions to confirm before taking a trade
+    pub checklist: Vec<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Create playbook request
+#[derive(Debug, Deserialize)]
+pub struct CreatePlaybookRequest {
+    pub name: String,
+    pub description: Option<String>,
+    pub entry_criteria: Option<String>,
+    pub exit_criteria: Option<String>,
+    pub checklist: Option<Vec<String>>,
+}
+
+/// Update playbook request; `checklist` replaces the whole list
+#[derive(Debug, Deserialize)]
+pub struct UpdatePlaybookRequest {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub entry_criteria: Option<String>,
+    pub exit_criteria: Option<String>,
+    pub checklist: Option<Vec<String>>,
+}
+
+impl Playbook {
+    /// Check that every item a trade claims to have met is on the checklist
+    pub fn validate_checklist_met(&self, met: &[String]) -> Result<()> {
+        match met.iter().find(|item| !self.checklist.contains(item)) {
+            Some(item) => Err(AppError::ValidationError(format!(
+                "'{}' is not on the checklist of playbook '{}'",
+                item, self.name
+            ))),
+            None => Ok(()),
+        }
+    }
+}
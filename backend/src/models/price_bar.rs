@@ -0,0 +1,125 @@
+use super::ImportRowError;
+use chrono::{DateTime, Duration, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Supported bar lengths, finest first
+pub const TIMEFRAMES: &[&str] = &["1m", "5m", "15m", "30m", "1h", "4h", "1d"];
+
+/// Length of a bar in one of `TIMEFRAMES`
+pub fn timeframe_duration(timeframe: &str) -> Option<Duration> {
+    let duration = match timeframe {
+        "1m" => Duration::minutes(1),
+        "5m" => Duration::minutes(5),
+        "15m" => Duration::minutes(15),
+        "30m" => Duration::minutes(30),
+        "1h" => Duration::hours(1),
+        "4h" => Duration::hours(4),
+        "1d" => Duration::days(1),
+        _ => return None,
+    };
+
+    Some(duration)
+}
+
+/// OHLC bar model from database
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct PriceBar {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub symbol: String,
+    pub timeframe: String,
+    /// Start of the bar
+    pub bar_time: DateTime<Utc>,
+    pub open: Decimal,
+    pub high: Decimal,
+    pub low: Decimal,
+    pub close: Decimal,
+    pub volume: Option<Decimal>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// One bar parsed from an import file
+#[derive(Debug, Clone)]
+pub struct CreatePriceBarRequest {
+    pub bar_time: DateTime<Utc>,
+    pub open: Decimal,
+    pub high: Decimal,
+    pub low: Decimal,
+    pub close: Decimal,
+    pub volume: Option<Decimal>,
+}
+
+/// Symbol and timeframe an imported file holds bars for
+#[derive(Debug, Deserialize)]
+pub struct PriceBarImportQuery {
+    pub symbol: String,
+    pub timeframe: String,
+}
+
+/// Price bar list and delete filters
+#[derive(Debug, Deserialize)]
+pub struct PriceBarQuery {
+    pub symbol: String,
+    /// All timeframes when absent
+    pub timeframe: Option<String>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    /// Bars returned by a list; defaults to 1000
+    pub limit: Option<i64>,
+}
+
+/// Result of a price bar import
+#[derive(Debug, Serialize)]
+pub struct PriceBarImportReport {
+    pub total_rows: usize,
+    pub imported: usize,
+    pub failed: usize,
+    pub errors: Vec<ImportRowError>,
+    /// Closed trades whose excursions were recalculated
+    pub trades_updated: usize,
+}
+
+/// How far a closed trade moved against and for it
+#[derive(Debug, Clone, Copy)]
+pub struct Excursion {
+    pub mae: Decimal,
+    pub mfe: Decimal,
+    pub entry_efficiency: Option<Decimal>,
+    pub exit_efficiency: Option<Decimal>,
+}
+
+impl Excursion {
+    /// Derive excursions from the highest and lowest price traded while the
+    /// trade was open.
+    ///
+    /// The range is widened to include the fill prices. MAE and MFE are
+    /// price distances from the entry per unit. Entry efficiency is the share
+    /// of the range beyond the entry in the trade's favor, exit efficiency
+    /// the share captured by the exit, both as percentages; they are absent
+    /// when the range is empty.
+    pub fn from_range(direction: &str, entry: Decimal, exit: Decimal, high: Decimal, low: Decimal) -> Self {
+        let high = high.max(entry).max(exit);
+        let low = low.min(entry).min(exit);
+        let range = high - low;
+        let percent = |part: Decimal| (!range.is_zero()).then(|| part / range * Decimal::from(100));
+
+        if direction == "long" {
+            Excursion {
+                mae: entry - low,
+                mfe: high - entry,
+                entry_efficiency: percent(high - entry),
+                exit_efficiency: percent(exit - low),
+            }
+        } else {
+            Excursion {
+                mae: high - entry,
+                mfe: entry - low,
+                entry_efficiency: percent(entry - low),
+                exit_efficiency: percent(high - exit),
+            }
+        }
+    }
+}
@@ -0,0 +1,33 @@
+use super::ImportRowError;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// Latest known price of a symbol
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct Quote {
+    pub symbol: String,
+    pub price: Decimal,
+    /// Time the price was observed
+    pub as_of: DateTime<Utc>,
+}
+
+/// Set the price of a symbol request
+#[derive(Debug, Deserialize)]
+pub struct SetQuoteRequest {
+    pub price: Decimal,
+    /// Defaults to now
+    pub as_of: Option<DateTime<Utc>>,
+}
+
+/// Result of a quote CSV import
+#[derive(Debug, Serialize)]
+pub struct QuoteImportReport {
+    pub total_rows: usize,
+    pub imported: usize,
+    /// Rows older than the stored quote of their symbol
+    pub skipped: usize,
+    pub failed: usize,
+    pub errors: Vec<ImportRowError>,
+}
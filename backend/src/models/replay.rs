@@ -0,0 +1,158 @@
+use super::{OptionLeg, Trade, TradeExecution};
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Query parameters for a trade replay
+#[derive(Debug, Deserialize)]
+pub struct ReplayQuery {
+    /// Bar length; defaults to the finest stored timeframe spanning the trade
+    pub timeframe: Option<String>,
+    /// Bars shown before entry and after exit; defaults to 30
+    pub padding: Option<i64>,
+}
+
+/// OHLC bar of a replay
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct ReplayBar {
+    /// Start of the bar
+    pub time: DateTime<Utc>,
+    pub open: Decimal,
+    pub high: Decimal,
+    pub low: Decimal,
+    pub close: Decimal,
+    pub volume: Option<Decimal>,
+}
+
+/// Fill or leg event drawn on the chart
+#[derive(Debug, Clone, Serialize)]
+pub struct ReplayMarker {
+    pub kind: String, // "entry" or "exit"
+    pub time: DateTime<Utc>,
+    /// Absent for option legs, whose premiums aren't on the chart's scale
+    pub price: Option<Decimal>,
+    pub side: String, // "buy" or "sell"
+    pub quantity: Decimal,
+    pub label: Option<String>,
+}
+
+/// Horizontal price level drawn on the chart
+#[derive(Debug, Clone, Serialize)]
+pub struct ReplayLevel {
+    pub kind: String, // "stop", "initial_stop" or "target"
+    pub price: Decimal,
+}
+
+/// Bars around a trade with its fills and risk levels overlaid
+#[derive(Debug, Serialize)]
+pub struct TradeReplay {
+    pub trade_id: Uuid,
+    /// Symbol the bars belong to; the underlying for option leg trades
+    pub symbol: String,
+    pub timeframe: String,
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub bars: Vec<ReplayBar>,
+    pub markers: Vec<ReplayMarker>,
+    pub levels: Vec<ReplayLevel>,
+}
+
+impl ReplayMarker {
+    /// Markers of a trade in time order: one per fill or leg event, or the
+    /// flat entry and exit when it has neither.
+    ///
+    /// Fills in the trade's direction are entries and the others exits.
+    pub fn for_trade(trade: &Trade, executions: &[TradeExecution], legs: &[OptionLeg]) -> Vec<Self> {
+        let mut markers = Vec::new();
+        let opening_side = if trade.direction == "long" { "buy" } else { "sell" };
+        let closing_side = if trade.direction == "long" { "sell" } else { "buy" };
+
+        if !legs.is_empty() {
+            for leg in legs {
+                let is_stock = leg.option_type == "stock";
+                let contract = match (leg.strike, leg.expiry) {
+                    (Some(strike), Some(expiry)) => format!("{} {} {}", strike.normalize(), leg.option_type, expiry),
+                    _ => "stock".to_string(),
+                };
+
+                markers.push(ReplayMarker {
+                    kind: "entry".to_string(),
+                    time: leg.opened_at,
+                    price: is_stock.then_some(leg.premium),
+                    side: leg.side.clone(),
+                    quantity: leg.quantity,
+                    label: Some(format!("{} {} {}", leg.side, leg.quantity.normalize(), contract)),
+                });
+
+                if let Some(closed_at) = leg.closed_at {
+                    let reason = leg.close_reason.as_deref().unwrap_or("closed");
+                    markers.push(ReplayMarker {
+                        kind: "exit".to_string(),
+                        time: closed_at,
+                        price: leg.close_premium.filter(|_| is_stock),
+                        side: if leg.side == "buy" { "sell" } else { "buy" }.to_string(),
+                        quantity: leg.quantity,
+                        label: Some(format!("{} {}", contract, reason)),
+                    });
+                }
+            }
+        } else if !executions.is_empty() {
+            for execution in executions {
+                markers.push(ReplayMarker {
+                    kind: if execution.side == opening_side { "entry" } else { "exit" }.to_string(),
+                    time: execution.executed_at,
+                    price: Some(execution.price),
+                    side: execution.side.clone(),
+                    quantity: execution.quantity,
+                    label: None,
+                });
+            }
+        } else {
+            markers.push(ReplayMarker {
+                kind: "entry".to_string(),
+                time: trade.entry_time,
+                price: Some(trade.entry_price),
+                side: opening_side.to_string(),
+                quantity: trade.quantity,
+                label: None,
+            });
+
+            if let (Some(exit_time), Some(exit_price)) = (trade.exit_time, trade.exit_price) {
+                markers.push(ReplayMarker {
+                    kind: "exit".to_string(),
+                    time: exit_time,
+                    price: Some(exit_price),
+                    side: closing_side.to_string(),
+                    quantity: trade.quantity,
+                    label: None,
+                });
+            }
+        }
+
+        markers.sort_by_key(|marker| marker.time);
+        markers
+    }
+}
+
+impl ReplayLevel {
+    /// Current stop, the initial stop when it differs, and every target
+    pub fn for_trade(trade: &Trade) -> Vec<Self> {
+        let level = |kind: &str, price: Decimal| ReplayLevel {
+            kind: kind.to_string(),
+            price,
+        };
+
+        let mut levels = Vec::new();
+        if let Some(stop) = trade.stop_loss {
+            levels.push(level("stop", stop));
+        }
+        if let Some(initial_stop) = trade.initial_stop.filter(|s| Some(*s) != trade.stop_loss) {
+            levels.push(level("initial_stop", initial_stop));
+        }
+        levels.extend(trade.take_profit.iter().map(|target| level("target", *target)));
+
+        levels
+    }
+}
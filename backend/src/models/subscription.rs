@@ -15,6 +15,22 @@ impl SubscriptionTier {
             SubscriptionTier::Paid => "paid",
         }
     }
+
+    /// Largest file a user on this tier may attach to a trade
+    pub fn max_attachment_bytes(&self) -> i64 {
+        match self {
+            SubscriptionTier::None => 5 * 1024 * 1024,   // 5 MB
+            SubscriptionTier::Paid => 25 * 1024 * 1024,  // 25 MB
+        }
+    }
+
+    /// Total size of all attachments a user on this tier may keep
+    pub fn attachment_quota_bytes(&self) -> i64 {
+        match self {
+            SubscriptionTier::None => 100 * 1024 * 1024,        // 100 MB
+            SubscriptionTier::Paid => 5 * 1024 * 1024 * 1024,   // 5 GB
+        }
+    }
 }
 
 impl From<String> for SubscriptionTier {
@@ -1,21 +1,28 @@
-use chrono::{DateTime, Utc};
+use super::{CreateExecutionRequest, CreateOptionLegRequest};
+use crate::error::AppError;
+use chrono::{DateTime, NaiveDate, Utc};
 use rust_decimal::Decimal;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 use sqlx::FromRow;
 use uuid::Uuid;
 
 /// Trade model from database
-#[derive(Debug, Clone, FromRow, Serialize)]
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
 pub struct Trade {
     pub id: Uuid,
     pub user_id: Uuid,
     
     // Trade Details
     pub symbol: String,
+    /// Underlying of option trades; symbol analytics roll up by it
+    pub underlying: Option<String>,
     pub direction: String, // "long" or "short"
     pub entry_price: Decimal,
     pub exit_price: Option<Decimal>,
     pub quantity: Decimal,
+    pub open_quantity: Decimal,
+    /// Currency value of a 1.0 price move per unit, from the instrument
+    pub multiplier: Decimal,
     
     // Timestamps
     pub entry_time: DateTime<Utc>,
@@ -26,44 +33,103 @@ pub struct Trade {
     pub pnl_percentage: Option<Decimal>,
     pub fees: Decimal,
     
+    // Currency
+    /// Currency of prices and P&L
+    pub currency: String,
+    pub fee_currency: String,
+    /// Converts fees into `currency`; 1 when both currencies match
+    pub fee_fx_rate: Decimal,
+    /// Date of the rate in `fee_fx_rate`
+    pub fee_fx_date: Option<NaiveDate>,
+    
+    // Risk
+    pub stop_loss: Option<Decimal>,
+    pub initial_stop: Option<Decimal>,
+    pub take_profit: Vec<Decimal>,
+    pub planned_risk: Option<Decimal>,
+    pub r_multiple: Option<Decimal>,
+    pub planned_r_multiple: Option<Decimal>,
+    
+    // Excursions (from price bars, closed trades only)
+    /// Maximum adverse excursion: furthest move against the entry per unit
+    pub mae: Option<Decimal>,
+    /// Maximum favorable excursion: furthest move in favor per unit
+    pub mfe: Option<Decimal>,
+    pub entry_efficiency: Option<Decimal>,
+    pub exit_efficiency: Option<Decimal>,
+    
     // Metadata
     pub notes: Option<String>,
     pub tags: Vec<String>,
     pub setup_type: Option<String>,
+    /// Playbook the trade was taken from; `setup_type` mirrors its name
+    pub playbook_id: Option<Uuid>,
+    /// Checklist items of the playbook that were met
+    pub checklist_met: Vec<String>,
+    pub strategy: Option<String>,
     pub mistakes: Vec<String>,
     pub emotions: Vec<String>,
     pub screenshots: Vec<String>,
     
     // Additional Fields
     pub broker: Option<String>,
-    pub account_id: Option<String>,
-    pub status: String, // "open", "closed", "pending"
+    pub account_id: Option<Uuid>,
+    pub external_id: Option<String>,
+    pub status: String, // "open", "closed", "pending", "cancelled", "expired"
     
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
 /// Create trade request
-#[derive(Debug, Deserialize)]
+///
+/// When `executions` or option `legs` are provided, prices, quantity,
+/// times and fees are derived from them and the flat fields may be omitted.
+/// `initial_stop` defaults to `stop_loss`. `currency` defaults to the
+/// instrument's quote currency, then the account's base currency, and
+/// `fee_currency` to `currency`. Without a `playbook_id`, a `setup_type`
+/// naming one of the user's playbooks (ignoring case) links the trade to it.
+/// Tags, mistakes and emotions take the spelling of matching vocabulary
+/// terms; a strict vocabulary rejects any other value.
+#[derive(Debug, Deserialize, Default)]
 pub struct CreateTradeRequest {
     pub symbol: String,
     pub direction: String,
+    #[serde(default)]
     pub entry_price: Decimal,
     pub exit_price: Option<Decimal>,
+    #[serde(default)]
     pub quantity: Decimal,
     pub entry_time: DateTime<Utc>,
     pub exit_time: Option<DateTime<Utc>>,
     pub fees: Option<Decimal>,
+    pub currency: Option<String>,
+    pub fee_currency: Option<String>,
+    pub stop_loss: Option<Decimal>,
+    pub initial_stop: Option<Decimal>,
+    pub take_profit: Option<Vec<Decimal>>,
+    pub planned_risk: Option<Decimal>,
     pub notes: Option<String>,
     pub tags: Option<Vec<String>>,
     pub setup_type: Option<String>,
+    pub playbook_id: Option<Uuid>,
+    pub checklist_met: Option<Vec<String>>,
+    pub strategy: Option<String>,
     pub mistakes: Option<Vec<String>>,
     pub emotions: Option<Vec<String>>,
+    pub screenshots: Option<Vec<String>>,
     pub broker: Option<String>,
-    pub account_id: Option<String>,
+    pub account_id: Option<Uuid>,
+    pub external_id: Option<String>,
+    pub executions: Option<Vec<CreateExecutionRequest>>,
+    pub legs: Option<Vec<CreateOptionLegRequest>>,
 }
 
 /// Update trade request
+///
+/// `executions` or `legs`, when present, replaces all fills or legs of the
+/// trade. A new `setup_type` without a `playbook_id` relinks the trade to
+/// the playbook of that name, or unlinks it when there is none.
 #[derive(Debug, Deserialize)]
 pub struct UpdateTradeRequest {
     pub symbol: Option<String>,
@@ -74,47 +140,209 @@ pub struct UpdateTradeRequest {
     pub entry_time: Option<DateTime<Utc>>,
     pub exit_time: Option<DateTime<Utc>>,
     pub fees: Option<Decimal>,
+    pub currency: Option<String>,
+    pub fee_currency: Option<String>,
+    pub stop_loss: Option<Decimal>,
+    pub initial_stop: Option<Decimal>,
+    pub take_profit: Option<Vec<Decimal>>,
+    pub planned_risk: Option<Decimal>,
     pub notes: Option<String>,
     pub tags: Option<Vec<String>>,
     pub setup_type: Option<String>,
+    pub playbook_id: Option<Uuid>,
+    pub checklist_met: Option<Vec<String>>,
+    pub strategy: Option<String>,
     pub mistakes: Option<Vec<String>>,
     pub emotions: Option<Vec<String>>,
     pub broker: Option<String>,
-    pub account_id: Option<String>,
+    pub account_id: Option<Uuid>,
     pub status: Option<String>,
+    pub executions: Option<Vec<CreateExecutionRequest>>,
+    pub legs: Option<Vec<CreateOptionLegRequest>>,
+}
+
+impl CreateTradeRequest {
+    /// Reject trades that can't be stored: flat trades need a positive size
+    /// and entry price, trades with fills or legs derive them
+    pub fn validate(&self) -> crate::error::Result<()> {
+        validate_direction(&self.direction)?;
+
+        let has_executions = self.executions.as_ref().is_some_and(|e| !e.is_empty());
+        let has_legs = self.legs.as_ref().is_some_and(|l| !l.is_empty());
+        if !has_executions && !has_legs {
+            if self.quantity <= Decimal::ZERO {
+                return Err(AppError::ValidationError(
+                    "Quantity must be positive or executions or legs must be provided".to_string(),
+                ));
+            }
+            if self.entry_price <= Decimal::ZERO {
+                return Err(AppError::ValidationError(
+                    "Entry price must be positive or executions or legs must be provided".to_string(),
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl UpdateTradeRequest {
+    /// Reject values a partial update can't merge into a trade
+    pub fn validate(&self) -> crate::error::Result<()> {
+        if let Some(direction) = &self.direction {
+            validate_direction(direction)?;
+        }
+        if self.entry_price.is_some_and(|price| price <= Decimal::ZERO) {
+            return Err(AppError::ValidationError(
+                "Entry price must be positive".to_string(),
+            ));
+        }
+        if self.quantity.is_some_and(|quantity| quantity <= Decimal::ZERO) {
+            return Err(AppError::ValidationError(
+                "Quantity must be positive".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+/// Check that a direction is `long` or `short`
+fn validate_direction(direction: &str) -> crate::error::Result<()> {
+    if direction != "long" && direction != "short" {
+        return Err(AppError::ValidationError(
+            "Direction must be 'long' or 'short'".to_string(),
+        ));
+    }
+
+    Ok(())
 }
 
 /// Trade list filters
-#[derive(Debug, Deserialize, Default)]
+#[derive(Debug, Deserialize, Default, Clone)]
 pub struct TradeFilters {
     pub symbol: Option<String>,
     pub direction: Option<String>,
     pub status: Option<String>,
     pub from_date: Option<DateTime<Utc>>,
     pub to_date: Option<DateTime<Utc>>,
+    /// Comma-separated in query strings (`tags=A+,breakout`); a trade
+    /// matches when it carries all of them
+    #[serde(default, deserialize_with = "deserialize_comma_list")]
     pub tags: Option<Vec<String>>,
     pub setup_type: Option<String>,
+    pub playbook_id: Option<Uuid>,
+    pub broker: Option<String>,
+    pub account_id: Option<Uuid>,
     pub limit: Option<i64>,
     pub offset: Option<i64>,
 }
 
+/// Deserialize `a,b,c` into a list, treating an empty value as absent
+fn deserialize_comma_list<'de, D>(deserializer: D) -> Result<Option<Vec<String>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value: Option<String> = Option::deserialize(deserializer)?;
+
+    Ok(value
+        .map(|v| {
+            v.split(',')
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(str::to_string)
+                .collect::<Vec<_>>()
+        })
+        .filter(|tags| !tags.is_empty()))
+}
+
+/// Trade export options; filters are read from the same query string
+#[derive(Debug, Deserialize)]
+pub struct ExportQuery {
+    pub format: Option<String>,
+}
+
 impl Trade {
+    /// Whether the trade is an order that never filled: pending, cancelled
+    /// or expired. Such trades hold no position.
+    pub fn is_unfilled(&self) -> bool {
+        matches!(self.status.as_str(), "pending" | "cancelled" | "expired")
+    }
+
     /// Calculate P&L for a trade
-    pub fn calculate_pnl(&self) -> Option<(Decimal, Decimal)> {
-        if let Some(exit_price) = self.exit_price {
-            let price_diff = if self.direction == "long" {
-                exit_price - self.entry_price
+    pub fn calculate_pnl(&self) -> Option<(Decimal, Option<Decimal>)> {
+        self.exit_price.map(|exit_price| {
+            round_trip_pnl(
+                &self.direction,
+                self.entry_price,
+                exit_price,
+                self.quantity,
+                self.multiplier,
+                self.fees_in_currency(),
+            )
+        })
+    }
+
+    /// Fees converted into the trade currency
+    pub fn fees_in_currency(&self) -> Decimal {
+        self.fees * self.fee_fx_rate
+    }
+
+    /// Amount risked (1R): `planned_risk` when set, otherwise the distance
+    /// from entry to the initial stop (or current stop) times quantity
+    pub fn risk_amount(&self) -> Option<Decimal> {
+        self.planned_risk.filter(|r| *r > Decimal::ZERO).or_else(|| {
+            let stop = self.initial_stop.or(self.stop_loss)?;
+            let risk = (self.entry_price - stop).abs() * self.quantity * self.multiplier;
+            (risk > Decimal::ZERO).then_some(risk)
+        })
+    }
+
+    /// Realized and planned R multiples.
+    ///
+    /// Realized R is `pnl / 1R` and only exists once the trade is closed.
+    /// Planned R is the reward at the final target divided by 1R.
+    pub fn calculate_r_multiples(&self) -> (Option<Decimal>, Option<Decimal>) {
+        let Some(risk) = self.risk_amount() else {
+            return (None, None);
+        };
+
+        let realized = self
+            .pnl
+            .filter(|_| self.status == "closed")
+            .map(|pnl| pnl / risk);
+
+        let planned = self.take_profit.last().map(|target| {
+            let reward = if self.direction == "long" {
+                target - self.entry_price
             } else {
-                self.entry_price - exit_price
+                self.entry_price - target
             };
-            
-            let pnl = price_diff * self.quantity - self.fees;
-            let pnl_percentage = (price_diff / self.entry_price) * Decimal::from(100);
-            
-            Some((pnl, pnl_percentage))
-        } else {
-            None
-        }
+            reward * self.quantity * self.multiplier / risk
+        });
+
+        (realized, planned)
     }
 }
 
+/// P&L and percentage move of a single entry/exit round trip; there is no
+/// percentage without an entry price
+pub fn round_trip_pnl(
+    direction: &str,
+    entry_price: Decimal,
+    exit_price: Decimal,
+    quantity: Decimal,
+    multiplier: Decimal,
+    fees: Decimal,
+) -> (Decimal, Option<Decimal>) {
+    let price_diff = if direction == "long" {
+        exit_price - entry_price
+    } else {
+        entry_price - exit_price
+    };
+
+    let pnl = price_diff * quantity * multiplier - fees;
+    let pnl_percentage = (!entry_price.is_zero()).then(|| (price_diff / entry_price) * Decimal::from(100));
+
+    (pnl, pnl_percentage)
+}
@@ -0,0 +1,23 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::Value;
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// One recorded change of a trade
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct TradeRevision {
+    pub id: Uuid,
+    pub trade_id: Uuid,
+    pub user_id: Uuid,
+    /// Sequence number of the change within the trade, from 1
+    pub revision: i32,
+    pub action: String, // "create", "update", "delete", "restore"
+    /// Changed fields as `{"field": {"from": old, "to": new}}`
+    pub changes: Value,
+    /// API request that made the change
+    pub request_id: Option<String>,
+    /// Revision a restore brought the trade back to
+    pub restored_from: Option<i32>,
+    pub created_at: DateTime<Utc>,
+}
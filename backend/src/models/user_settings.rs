@@ -0,0 +1,58 @@
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// User settings model from database
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct UserSettings {
+    pub user_id: Uuid,
+    /// IANA name analytics cut days, weeks and months in
+    pub timezone: String,
+    /// Local hour a trading day ends, 0 for midnight; trades closed at or
+    /// after it count toward the next day
+    pub day_rollover_hour: i16,
+    /// Currency analytics are converted into unless another is requested
+    pub reporting_currency: Option<String>,
+    pub default_broker: Option<String>,
+    pub default_account_id: Option<Uuid>,
+    /// Fees of new trades entered without fees or fills
+    pub default_fees: Option<Decimal>,
+    /// Planned risk (1R) of new trades entered without one or a stop
+    pub risk_per_trade: Option<Decimal>,
+    /// None until the settings are first saved
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+impl UserSettings {
+    /// Settings of a user who hasn't saved any
+    pub fn defaults(user_id: Uuid) -> Self {
+        UserSettings {
+            user_id,
+            timezone: "UTC".to_string(),
+            day_rollover_hour: 0,
+            reporting_currency: None,
+            default_broker: None,
+            default_account_id: None,
+            default_fees: None,
+            risk_per_trade: None,
+            created_at: None,
+            updated_at: None,
+        }
+    }
+}
+
+/// Update user settings request; fields missing from the request keep
+/// their value
+#[derive(Debug, Deserialize)]
+pub struct UpdateUserSettingsRequest {
+    pub timezone: Option<String>,
+    pub day_rollover_hour: Option<i16>,
+    pub reporting_currency: Option<String>,
+    pub default_broker: Option<String>,
+    pub default_account_id: Option<Uuid>,
+    pub default_fees: Option<Decimal>,
+    pub risk_per_trade: Option<Decimal>,
+}
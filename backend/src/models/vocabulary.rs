@@ -0,0 +1,122 @@
+use crate::error::{AppError, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Free-text array of trades that a vocabulary manages
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum VocabularyKind {
+    Tags,
+    Mistakes,
+    Emotions,
+}
+
+impl VocabularyKind {
+    /// Name stored in `vocabulary_terms.kind`, which is also the trades column
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            VocabularyKind::Tags => "tags",
+            VocabularyKind::Mistakes => "mistakes",
+            VocabularyKind::Emotions => "emotions",
+        }
+    }
+
+    /// One value of the kind, for messages
+    pub fn singular(&self) -> &'static str {
+        match self {
+            VocabularyKind::Tags => "tag",
+            VocabularyKind::Mistakes => "mistake",
+            VocabularyKind::Emotions => "emotion",
+        }
+    }
+}
+
+impl TryFrom<&str> for VocabularyKind {
+    type Error = AppError;
+
+    fn try_from(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "tags" => Ok(VocabularyKind::Tags),
+            "mistakes" => Ok(VocabularyKind::Mistakes),
+            "emotions" => Ok(VocabularyKind::Emotions),
+            _ => Err(AppError::ValidationError(
+                "Vocabulary must be 'tags', 'mistakes' or 'emotions'".to_string(),
+            )),
+        }
+    }
+}
+
+/// Vocabulary term model from database
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct VocabularyTerm {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub kind: String,
+    pub name: String,
+    /// Display color as `#rrggbb`
+    pub color: Option<String>,
+    pub category: Option<String>,
+    /// Trades carrying the term
+    pub trade_count: i64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A vocabulary with its options and terms
+#[derive(Debug, Serialize)]
+pub struct Vocabulary {
+    pub kind: VocabularyKind,
+    /// Trades may only use terms of the vocabulary
+    pub strict: bool,
+    pub terms: Vec<VocabularyTerm>,
+}
+
+/// A value found on trades, registered or not
+#[derive(Debug, FromRow, Serialize)]
+pub struct VocabularyValue {
+    pub value: String,
+    pub trade_count: i64,
+    /// Term the value is registered as, if any
+    pub term_id: Option<Uuid>,
+}
+
+/// Update vocabulary options request
+#[derive(Debug, Deserialize)]
+pub struct UpdateVocabularyRequest {
+    pub strict: bool,
+}
+
+/// Create vocabulary term request
+#[derive(Debug, Deserialize)]
+pub struct CreateVocabularyTermRequest {
+    pub name: String,
+    pub color: Option<String>,
+    pub category: Option<String>,
+}
+
+/// Update vocabulary term request; a new name is applied to all trades
+#[derive(Debug, Deserialize)]
+pub struct UpdateVocabularyTermRequest {
+    pub name: Option<String>,
+    pub color: Option<String>,
+    pub category: Option<String>,
+}
+
+/// Merge values into one term request
+#[derive(Debug, Deserialize)]
+pub struct MergeVocabularyRequest {
+    /// Values to replace, exactly as stored on trades
+    pub from: Vec<String>,
+    /// Term they become; created when missing
+    pub into: String,
+}
+
+/// Outcome of a merge
+#[derive(Debug, Serialize)]
+pub struct MergeReport {
+    pub term: VocabularyTerm,
+    pub trades_updated: u64,
+    pub terms_removed: u64,
+}
@@ -0,0 +1,144 @@
+use super::PriceSource;
+use crate::{
+    error::{AppError, Result},
+    models::Quote,
+};
+use async_trait::async_trait;
+use reqwest::{header, Client, Url};
+use std::time::Duration;
+use uuid::Uuid;
+
+/// Price source asking a quote service over HTTP, such as the
+/// `mock_quote_server` binary during development.
+///
+/// Requests `GET {url}?symbols=A,B` and expects a JSON array of
+/// `{"symbol", "price", "as_of"}` objects. Quotes are the same for every
+/// user.
+pub struct HttpPriceSource {
+    client: Client,
+    url: Url,
+    /// Sent as a bearer token when set
+    api_key: Option<String>,
+}
+
+impl HttpPriceSource {
+    pub fn new(url: &str, api_key: Option<String>) -> std::result::Result<Self, String> {
+        let url = Url::parse(url).map_err(|e| format!("QUOTE_API_URL is not a valid URL: {}", e))?;
+        let client = Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .map_err(|e| format!("Failed to build quote client: {}", e))?;
+
+        Ok(Self { client, url, api_key })
+    }
+}
+
+#[async_trait]
+impl PriceSource for HttpPriceSource {
+    fn name(&self) -> &'static str {
+        "http"
+    }
+
+    async fn quotes(&self, _user_id: Uuid, symbols: &[String]) -> Result<Vec<Quote>> {
+        if symbols.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut url = self.url.clone();
+        url.query_pairs_mut().append_pair("symbols", &symbols.join(","));
+
+        let mut request = self.client.get(url);
+        if let Some(api_key) = &self.api_key {
+            request = request.header(header::AUTHORIZATION, format!("Bearer {}", api_key));
+        }
+
+        let failed = |e: String| AppError::InternalServerError(format!("Failed to fetch quotes: {}", e));
+        let response = request.send().await.map_err(|e| failed(e.to_string()))?;
+        let status = response.status();
+        let body = response.bytes().await.map_err(|e| failed(e.to_string()))?;
+        if !status.is_success() {
+            return Err(failed(format!(
+                "quote service returned {}: {}",
+                status,
+                String::from_utf8_lossy(&body)
+            )));
+        }
+
+        let quotes: Vec<Quote> = serde_json::from_slice(&body).map_err(|e| failed(e.to_string()))?;
+
+        // Keep only what was asked for
+        Ok(quotes.into_iter().filter(|quote| symbols.contains(&quote.symbol)).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pricing::mock;
+    use rust_decimal::Decimal;
+
+    /// Serve the mock quote service on a free port and return its URL
+    async fn serve_mock(api_key: Option<&str>) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let router = mock::router(api_key.map(str::to_string));
+        tokio::spawn(async move { axum::serve(listener, router).await.unwrap() });
+        format!("http://{}/quotes", addr)
+    }
+
+    fn symbols(symbols: &[&str]) -> Vec<String> {
+        symbols.iter().map(|symbol| symbol.to_string()).collect()
+    }
+
+    #[tokio::test]
+    async fn fetches_quotes_from_the_mock_service() {
+        let url = serve_mock(Some("secret")).await;
+        let source = HttpPriceSource::new(&url, Some("secret".to_string())).unwrap();
+
+        let quotes = source.quotes(Uuid::new_v4(), &symbols(&["AAPL", "ES"])).await.unwrap();
+
+        assert_eq!(quotes.len(), 2);
+        for quote in &quotes {
+            assert_eq!(quote.price, mock::mock_price(&quote.symbol));
+        }
+        assert_eq!(quotes[0].symbol, "AAPL");
+        assert_eq!(quotes[1].symbol, "ES");
+    }
+
+    #[tokio::test]
+    async fn rejected_api_key_is_an_error() {
+        let url = serve_mock(Some("secret")).await;
+        let source = HttpPriceSource::new(&url, Some("wrong".to_string())).unwrap();
+
+        let result = source.quotes(Uuid::new_v4(), &symbols(&["AAPL"])).await;
+        assert!(matches!(result, Err(AppError::InternalServerError(message)) if message.contains("401")));
+    }
+
+    #[tokio::test]
+    async fn unreachable_service_is_an_error() {
+        // Bind and drop a listener so nothing accepts on its port
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/quotes", listener.local_addr().unwrap());
+        drop(listener);
+        let source = HttpPriceSource::new(&url, None).unwrap();
+
+        let result = source.quotes(Uuid::new_v4(), &symbols(&["AAPL"])).await;
+        assert!(matches!(result, Err(AppError::InternalServerError(_))));
+    }
+
+    #[tokio::test]
+    async fn no_symbols_skip_the_request() {
+        let source = HttpPriceSource::new("http://127.0.0.1:1/quotes", None).unwrap();
+
+        assert!(source.quotes(Uuid::new_v4(), &[]).await.unwrap().is_empty());
+    }
+
+    #[test]
+    fn mock_prices_are_stable_and_in_range() {
+        for symbol in ["AAPL", "ES", "EURUSD", "BTCUSDT"] {
+            let price = mock::mock_price(symbol);
+            assert_eq!(price, mock::mock_price(symbol));
+            assert!(price >= Decimal::from(10) && price < Decimal::from(1000));
+        }
+    }
+}
@@ -0,0 +1,27 @@
+use super::PriceSource;
+use crate::{error::Result, models::Quote, repositories::QuoteRepository};
+use async_trait::async_trait;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// Price source reading the quotes users enter or import themselves
+pub struct ManualPriceSource {
+    pool: PgPool,
+}
+
+impl ManualPriceSource {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl PriceSource for ManualPriceSource {
+    fn name(&self) -> &'static str {
+        "manual"
+    }
+
+    async fn quotes(&self, user_id: Uuid, symbols: &[String]) -> Result<Vec<Quote>> {
+        QuoteRepository::new(self.pool.clone()).latest(user_id, symbols).await
+    }
+}
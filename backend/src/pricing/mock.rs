@@ -0,0 +1,69 @@
+use crate::models::Quote;
+use axum::{
+    extract::{Query, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use chrono::Utc;
+use rust_decimal::Decimal;
+use serde::Deserialize;
+
+/// Query of a quote request: comma-separated symbols
+#[derive(Debug, Deserialize)]
+pub struct MockQuoteQuery {
+    #[serde(default)]
+    pub symbols: String,
+}
+
+/// Quote service standing in for a real one during development and tests.
+///
+/// Serves `GET /quotes?symbols=A,B` in the format `HttpPriceSource` reads.
+/// Every symbol gets a made-up price that stays the same between requests.
+/// With an API key, requests must send it as a bearer token.
+pub fn router(api_key: Option<String>) -> Router {
+    Router::new().route("/quotes", get(quotes)).with_state(api_key)
+}
+
+/// Price the mock quotes for a symbol: between 10 and 1000, derived from
+/// the symbol's bytes
+pub fn mock_price(symbol: &str) -> Decimal {
+    // FNV-1a, so the price doesn't depend on the standard hasher
+    let hash = symbol
+        .bytes()
+        .fold(0xcbf2_9ce4_8422_2325u64, |hash, byte| (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3));
+    Decimal::new((1_000 + hash % 99_000) as i64, 2)
+}
+
+async fn quotes(
+    State(api_key): State<Option<String>>,
+    headers: HeaderMap,
+    Query(query): Query<MockQuoteQuery>,
+) -> Response {
+    if let Some(api_key) = api_key {
+        let authorized = headers
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .is_some_and(|token| token == api_key);
+        if !authorized {
+            return (StatusCode::UNAUTHORIZED, "Missing or invalid API key").into_response();
+        }
+    }
+
+    let as_of = Utc::now();
+    let quotes: Vec<Quote> = query
+        .symbols
+        .split(',')
+        .map(str::trim)
+        .filter(|symbol| !symbol.is_empty())
+        .map(|symbol| Quote {
+            symbol: symbol.to_string(),
+            price: mock_price(symbol),
+            as_of,
+        })
+        .collect();
+
+    Json(quotes).into_response()
+}
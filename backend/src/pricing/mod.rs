@@ -0,0 +1,38 @@
+pub mod http;
+pub mod manual;
+pub mod mock;
+
+pub use http::HttpPriceSource;
+pub use manual::ManualPriceSource;
+
+use crate::{error::Result, models::Quote, Config};
+use async_trait::async_trait;
+use sqlx::PgPool;
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Where open trades get the prices they are marked to market at
+#[async_trait]
+pub trait PriceSource: Send + Sync {
+    /// Name reported next to marks, e.g. "manual"
+    fn name(&self) -> &'static str;
+
+    /// Latest quotes of the symbols for a user; symbols without a quote
+    /// are left out
+    async fn quotes(&self, user_id: Uuid, symbols: &[String]) -> Result<Vec<Quote>>;
+}
+
+/// Build the price source selected by the configuration
+pub fn from_config(config: &Config, db: &PgPool) -> std::result::Result<Arc<dyn PriceSource>, String> {
+    match config.price_source.as_str() {
+        "manual" => Ok(Arc::new(ManualPriceSource::new(db.clone()))),
+        "http" => {
+            let url = config
+                .quote_api_url
+                .as_deref()
+                .ok_or_else(|| "QUOTE_API_URL must be set".to_string())?;
+            Ok(Arc::new(HttpPriceSource::new(url, config.quote_api_key.clone())?))
+        }
+        other => Err(format!("Unknown price source '{}'", other)),
+    }
+}
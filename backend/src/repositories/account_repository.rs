@@ -0,0 +1,261 @@
+use crate::{
+    error::{AppError, Result},
+    models::{Account, CashMovement, CreateAccountRequest, CreateCashMovementRequest, Trade, UpdateAccountRequest},
+    repositories::TradeRevisionRepository,
+};
+use rust_decimal::Decimal;
+use sqlx::{PgConnection, PgPool};
+use uuid::Uuid;
+
+pub struct AccountRepository {
+    pool: PgPool,
+}
+
+impl AccountRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Create a new account
+    pub async fn create(&self, user_id: Uuid, req: CreateAccountRequest) -> Result<Account> {
+        let account = sqlx::query_as::<_, Account>(
+            r#"
+            INSERT INTO accounts (user_id, name, broker, base_currency, starting_balance)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING *
+            "#,
+        )
+        .bind(user_id)
+        .bind(&req.name)
+        .bind(&req.broker)
+        .bind(req.base_currency.as_deref().unwrap_or("USD"))
+        .bind(req.starting_balance.unwrap_or(Decimal::ZERO))
+        .fetch_one(&self.pool)
+        .await
+        .map_err(duplicate_name)?;
+
+        Ok(account)
+    }
+
+    /// Get account by ID
+    pub async fn get(&self, account_id: Uuid, user_id: Uuid) -> Result<Option<Account>> {
+        let account = sqlx::query_as::<_, Account>(
+            r#"
+            SELECT * FROM accounts WHERE id = $1 AND user_id = $2
+            "#,
+        )
+        .bind(account_id)
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(account)
+    }
+
+    /// List accounts by name
+    pub async fn list(&self, user_id: Uuid) -> Result<Vec<Account>> {
+        let accounts = sqlx::query_as::<_, Account>(
+            r#"
+            SELECT * FROM accounts WHERE user_id = $1 ORDER BY name
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(accounts)
+    }
+
+    /// Update account; fields missing from the request keep their value
+    pub async fn update(&self, account_id: Uuid, user_id: Uuid, req: UpdateAccountRequest) -> Result<Account> {
+        let account = sqlx::query_as::<_, Account>(
+            r#"
+            UPDATE accounts SET
+                name = COALESCE($3, name),
+                broker = COALESCE($4, broker),
+                base_currency = COALESCE($5, base_currency),
+                starting_balance = COALESCE($6, starting_balance),
+                updated_at = NOW()
+            WHERE id = $1 AND user_id = $2
+            RETURNING *
+            "#,
+        )
+        .bind(account_id)
+        .bind(user_id)
+        .bind(&req.name)
+        .bind(&req.broker)
+        .bind(&req.base_currency)
+        .bind(req.starting_balance)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(duplicate_name)?
+        .ok_or(AppError::ValidationError("Account not found".to_string()))?;
+
+        Ok(account)
+    }
+
+    /// Delete account; its trades are kept without an account, and the
+    /// unlinking is recorded in their history
+    pub async fn delete(&self, account_id: Uuid, user_id: Uuid) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+
+        let before = sqlx::query_as::<_, Trade>(
+            r#"
+            SELECT * FROM trades WHERE account_id = $1 AND user_id = $2 FOR UPDATE
+            "#,
+        )
+        .bind(account_id)
+        .bind(user_id)
+        .fetch_all(&mut *tx)
+        .await?;
+        let ids: Vec<Uuid> = before.iter().map(|trade| trade.id).collect();
+
+        let after = sqlx::query_as::<_, Trade>(
+            r#"
+            UPDATE trades SET account_id = NULL
+            WHERE id = ANY($1)
+            RETURNING *
+            "#,
+        )
+        .bind(&ids)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        TradeRevisionRepository::record_updates_with(&mut tx, &before, &after).await?;
+
+        let result = sqlx::query(
+            r#"
+            DELETE FROM accounts WHERE id = $1 AND user_id = $2
+            "#,
+        )
+        .bind(account_id)
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::ValidationError("Account not found".to_string()));
+        }
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// Get an account, failing unless it exists and belongs to the user
+    pub(crate) async fn ensure_owned(conn: &mut PgConnection, account_id: Uuid, user_id: Uuid) -> Result<Account> {
+        let account = sqlx::query_as::<_, Account>(
+            r#"
+            SELECT * FROM accounts WHERE id = $1 AND user_id = $2
+            "#,
+        )
+        .bind(account_id)
+        .bind(user_id)
+        .fetch_optional(conn)
+        .await?
+        .ok_or(AppError::ValidationError("Account not found".to_string()))?;
+
+        Ok(account)
+    }
+
+    /// Capital put into the user's accounts, or into one account, per base
+    /// currency: starting balances plus deposits less withdrawals
+    pub async fn capital(&self, user_id: Uuid, account_id: Option<Uuid>) -> Result<Vec<(String, Decimal)>> {
+        let capital: Vec<(String, Decimal)> = sqlx::query_as(
+            r#"
+            SELECT
+                a.base_currency::TEXT,
+                SUM(a.starting_balance + COALESCE((
+                    SELECT SUM(CASE kind WHEN 'deposit' THEN amount WHEN 'withdrawal' THEN -amount ELSE 0 END)
+                    FROM cash_movements m
+                    WHERE m.account_id = a.id
+                ), 0))
+            FROM accounts a
+            WHERE a.user_id = $1 AND ($2::UUID IS NULL OR a.id = $2)
+            GROUP BY a.base_currency
+            ORDER BY a.base_currency
+            "#,
+        )
+        .bind(user_id)
+        .bind(account_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(capital)
+    }
+
+    /// List cash movements of an account in date order
+    pub async fn list_movements(&self, account_id: Uuid, user_id: Uuid) -> Result<Vec<CashMovement>> {
+        let movements = sqlx::query_as::<_, CashMovement>(
+            r#"
+            SELECT * FROM cash_movements
+            WHERE account_id = $1 AND user_id = $2
+            ORDER BY occurred_at ASC
+            "#,
+        )
+        .bind(account_id)
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(movements)
+    }
+
+    /// Record a cash movement
+    pub async fn create_movement(
+        &self,
+        account_id: Uuid,
+        user_id: Uuid,
+        req: CreateCashMovementRequest,
+    ) -> Result<CashMovement> {
+        let mut conn = self.pool.acquire().await?;
+        Self::ensure_owned(&mut conn, account_id, user_id).await?;
+
+        let movement = sqlx::query_as::<_, CashMovement>(
+            r#"
+            INSERT INTO cash_movements (account_id, user_id, kind, amount, occurred_at, notes)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING *
+            "#,
+        )
+        .bind(account_id)
+        .bind(user_id)
+        .bind(&req.kind)
+        .bind(req.amount)
+        .bind(req.occurred_at)
+        .bind(&req.notes)
+        .fetch_one(&mut *conn)
+        .await?;
+
+        Ok(movement)
+    }
+
+    /// Delete a cash movement
+    pub async fn delete_movement(&self, movement_id: Uuid, account_id: Uuid, user_id: Uuid) -> Result<()> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM cash_movements WHERE id = $1 AND account_id = $2 AND user_id = $3
+            "#,
+        )
+        .bind(movement_id)
+        .bind(account_id)
+        .bind(user_id)
+        .execute(&self.pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::ValidationError("Cash movement not found".to_string()));
+        }
+
+        Ok(())
+    }
+}
+
+fn duplicate_name(e: sqlx::Error) -> AppError {
+    match e {
+        sqlx::Error::Database(db_err) if db_err.is_unique_violation() => {
+            AppError::ValidationError("An account with this name already exists".to_string())
+        }
+        _ => AppError::DatabaseError(e),
+    }
+}
@@ -0,0 +1,938 @@
+use crate::{
+    error::{AppError, Result},
+    models::{FxRateUsed, TradeFilters},
+    repositories::{AccountRepository, FxRateRepository, TradeRepository},
+    services::{
+        AnalyticsService, CalendarBucket, EquityBucket, EquityInterval, ExcursionAnalytics, ExcursionPoint, GroupTotals, JournalDay, MistakeAnalysis, MistakeTotals,
+        OverviewTotals, PlaybookPerformance, PlaybookTrade, RBucket, RMultipleAnalytics, RMultipleTotals, SetupPerformance, SymbolPerformance, TimedTrade, TradeAnalytics,
+    },
+};
+use chrono_tz::Tz;
+use chrono::{NaiveDate, Utc};
+use rust_decimal::Decimal;
+use sqlx::{postgres::PgArguments, query::QueryAs, FromRow, PgPool, Postgres};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// Rate used for the trades of one analytics group
+#[derive(Debug, FromRow)]
+struct FxUsage {
+    key: Option<String>,
+    #[sqlx(flatten)]
+    rate: FxRateUsed,
+}
+
+/// Analytics computed inside PostgreSQL.
+///
+/// Each query returns the same raw totals the in-memory reference in
+/// `AnalyticsService` accumulates, and the derived figures are built by the
+/// same conversions, so both yield identical results for the same trades.
+///
+/// With a reporting currency, queries read trades through `source`, which
+/// converts P&L at the latest rate on or before each trade's exit date;
+/// the rates applied are reported next to the figures.
+pub struct AnalyticsRepository {
+    pool: PgPool,
+}
+
+impl AnalyticsRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Overall analytics; streaks use a gaps-and-islands pass over
+    /// non-zero results in entry-time order. The P&L percentage is relative
+    /// to the capital of the filtered account, or of all accounts.
+    pub async fn overview(&self, user_id: Uuid, filters: &TradeFilters, currency: Option<&str>) -> Result<TradeAnalytics> {
+        let fx_rates_used = self.rates_used(user_id, filters, currency).await?;
+
+        let (conditions, param_count) = TradeRepository::filter_conditions(filters);
+        let source = Self::source(currency.map(|_| param_count + 1));
+        let query = format!(
+            r#"
+            WITH filtered AS (
+                SELECT id, entry_time, pnl FROM {source} WHERE {conditions}
+            ),
+            decided AS (
+                SELECT
+                    SIGN(pnl) AS sgn,
+                    ROW_NUMBER() OVER (ORDER BY entry_time, id) AS rn,
+                    ROW_NUMBER() OVER (ORDER BY entry_time, id)
+                        - ROW_NUMBER() OVER (PARTITION BY SIGN(pnl) ORDER BY entry_time, id) AS island
+                FROM filtered
+                WHERE pnl IS NOT NULL AND pnl <> 0
+            ),
+            streaks AS (
+                SELECT sgn, COUNT(*)::INT4 AS len, MAX(rn) AS last_rn
+                FROM decided
+                GROUP BY sgn, island
+            )
+            SELECT
+                COUNT(*)::INT4 AS total_trades,
+                (COUNT(*) FILTER (WHERE pnl > 0))::INT4 AS winning_trades,
+                (COUNT(*) FILTER (WHERE pnl < 0))::INT4 AS losing_trades,
+                COALESCE(SUM(pnl), 0) AS total_pnl,
+                COALESCE(SUM(pnl) FILTER (WHERE pnl > 0), 0) AS total_wins,
+                COALESCE(-SUM(pnl) FILTER (WHERE pnl < 0), 0) AS total_losses,
+                GREATEST(COALESCE(MAX(pnl), 0), 0) AS largest_win,
+                LEAST(COALESCE(MIN(pnl), 0), 0) AS largest_loss,
+                COALESCE((SELECT (sgn * len)::INT4 FROM streaks ORDER BY last_rn DESC LIMIT 1), 0) AS current_streak,
+                COALESCE((SELECT MAX(len) FROM streaks WHERE sgn > 0), 0) AS longest_win_streak,
+                COALESCE((SELECT MAX(len) FROM streaks WHERE sgn < 0), 0) AS longest_loss_streak
+            FROM filtered
+            "#,
+        );
+
+        let q = TradeRepository::bind_filters(sqlx::query_as::<_, OverviewTotals>(&query), user_id, filters);
+        let mut totals = Self::bind_currency(q, currency).fetch_one(&self.pool).await?;
+
+        let (capital, capital_rates) = self.capital(user_id, filters.account_id, currency).await?;
+        totals.capital = capital;
+
+        let mut analytics: TradeAnalytics = totals.into();
+        analytics.currency = currency.map(str::to_string);
+        analytics.fx_rates_used = fx_rates_used.into_iter().chain(capital_rates).collect();
+
+        Ok(analytics)
+    }
+
+    /// Performance by symbol; option trades count towards their underlying
+    pub async fn by_symbol(
+        &self,
+        user_id: Uuid,
+        filters: &TradeFilters,
+        currency: Option<&str>,
+    ) -> Result<Vec<SymbolPerformance>> {
+        let mut rates = self.rates_used_by(user_id, filters, currency, SYMBOL_KEY, "").await?;
+        let groups = self.group_totals(user_id, filters, currency, SYMBOL_KEY).await?;
+
+        let mut results: Vec<SymbolPerformance> = groups
+            .into_iter()
+            .map(|group| SymbolPerformance {
+                fx_rates_used: rates.remove(&group.key).unwrap_or_default(),
+                ..group.into()
+            })
+            .collect();
+        AnalyticsService::sort_symbols(&mut results);
+
+        Ok(results)
+    }
+
+    /// Performance by setup type; trades without a setup are left out
+    pub async fn by_setup(
+        &self,
+        user_id: Uuid,
+        filters: &TradeFilters,
+        currency: Option<&str>,
+    ) -> Result<Vec<SetupPerformance>> {
+        let mut rates = self.rates_used_by(user_id, filters, currency, "setup_type", "").await?;
+        let groups = self.group_totals(user_id, filters, currency, "setup_type").await?;
+
+        let mut results: Vec<SetupPerformance> = groups
+            .into_iter()
+            .map(|group| SetupPerformance {
+                fx_rates_used: rates.remove(&group.key).unwrap_or_default(),
+                ..group.into()
+            })
+            .collect();
+        AnalyticsService::sort_setups(&mut results);
+
+        Ok(results)
+    }
+
+    /// Performance by playbook and checklist compliance; trades without a
+    /// playbook are left out
+    pub async fn by_playbook(
+        &self,
+        user_id: Uuid,
+        filters: &TradeFilters,
+        currency: Option<&str>,
+    ) -> Result<Vec<PlaybookPerformance>> {
+        let mut rates = self
+            .rates_used_by(user_id, filters, currency, "playbook_id::TEXT", "")
+            .await?;
+
+        let (conditions, param_count) = TradeRepository::filter_conditions(filters);
+        let source = Self::source(currency.map(|_| param_count + 1));
+        let query = format!(
+            r#"
+            SELECT
+                p.id AS playbook_id,
+                p.name,
+                p.checklist,
+                t.checklist_met,
+                t.pnl
+            FROM (
+                SELECT playbook_id, checklist_met, pnl FROM {source}
+                WHERE {conditions} AND pnl IS NOT NULL
+            ) t
+            JOIN playbooks p ON p.id = t.playbook_id
+            "#,
+        );
+
+        let q = TradeRepository::bind_filters(sqlx::query_as::<_, PlaybookTrade>(&query), user_id, filters);
+        let trades = Self::bind_currency(q, currency).fetch_all(&self.pool).await?;
+
+        let results = AnalyticsService::playbook_performance(trades)
+            .into_iter()
+            .map(|performance| PlaybookPerformance {
+                fx_rates_used: rates.remove(&performance.playbook_id.to_string()).unwrap_or_default(),
+                ..performance
+            })
+            .collect();
+
+        Ok(results)
+    }
+
+    /// Mistake frequency and impact
+    pub async fn mistakes(
+        &self,
+        user_id: Uuid,
+        filters: &TradeFilters,
+        currency: Option<&str>,
+    ) -> Result<Vec<MistakeAnalysis>> {
+        let mut rates = self
+            .rates_used_by(user_id, filters, currency, "m.mistake", MISTAKES_JOIN)
+            .await?;
+
+        let (conditions, param_count) = TradeRepository::filter_conditions(filters);
+        let source = Self::source(currency.map(|_| param_count + 1));
+        let query = format!(
+            r#"
+            SELECT
+                m.mistake,
+                COUNT(*)::INT4 AS count,
+                SUM(pnl) AS total_pnl
+            FROM {source}
+            {MISTAKES_JOIN}
+            WHERE {conditions} AND pnl IS NOT NULL
+            GROUP BY m.mistake
+            "#,
+        );
+
+        let q = TradeRepository::bind_filters(sqlx::query_as::<_, MistakeTotals>(&query), user_id, filters);
+        let totals = Self::bind_currency(q, currency).fetch_all(&self.pool).await?;
+
+        let mut results: Vec<MistakeAnalysis> = totals
+            .into_iter()
+            .map(|totals| MistakeAnalysis {
+                fx_rates_used: rates.remove(&totals.mistake).unwrap_or_default(),
+                ..totals.into()
+            })
+            .collect();
+        AnalyticsService::sort_mistakes(&mut results);
+
+        Ok(results)
+    }
+
+    /// Realized P&L per trade or per calendar bucket, in time order.
+    ///
+    /// Trades count at their exit time (entry time if none is recorded).
+    /// Buckets are cut at `rollover_hour` local time in `timezone`, with
+    /// later times counting toward the next day, and start at that day's
+    /// local midnight; weeks start on Monday.
+    /// With a reporting currency, fails when a trade lacks a rate.
+    pub async fn equity_buckets(
+        &self,
+        user_id: Uuid,
+        filters: &TradeFilters,
+        interval: EquityInterval,
+        timezone: Tz,
+        rollover_hour: i16,
+        currency: Option<&str>,
+    ) -> Result<Vec<EquityBucket>> {
+        self.ensure_rates(user_id, filters, currency).await?;
+
+        let (conditions, mut param_count) = TradeRepository::filter_conditions(filters);
+        if currency.is_some() {
+            param_count += 1;
+        }
+        let source = Self::source(currency.map(|_| param_count));
+
+        let query = match interval.date_trunc_unit() {
+            None => format!(
+                r#"
+                SELECT
+                    COALESCE(exit_time, entry_time) AS time,
+                    id AS trade_id,
+                    symbol,
+                    1::INT4 AS trades,
+                    pnl
+                FROM {source}
+                WHERE {conditions} AND pnl IS NOT NULL
+                ORDER BY time, id
+                "#,
+            ),
+            Some(unit) => format!(
+                r#"
+                SELECT
+                    date_trunc(
+                        '{unit}',
+                        COALESCE(exit_time, entry_time) + make_interval(hours => ${shift_param}),
+                        ${tz_param}
+                    ) AS time,
+                    NULL::UUID AS trade_id,
+                    NULL::TEXT AS symbol,
+                    COUNT(*)::INT4 AS trades,
+                    SUM(pnl) AS pnl
+                FROM {source}
+                WHERE {conditions} AND pnl IS NOT NULL
+                GROUP BY 1
+                ORDER BY 1
+                "#,
+                tz_param = param_count + 1,
+                shift_param = param_count + 2,
+            ),
+        };
+
+        let q = TradeRepository::bind_filters(sqlx::query_as::<_, EquityBucket>(&query), user_id, filters);
+        let mut q = Self::bind_currency(q, currency);
+        if interval.date_trunc_unit().is_some() {
+            q = q.bind(timezone.name()).bind(i32::from((24 - rollover_hour) % 24));
+        }
+
+        Ok(q.fetch_all(&self.pool).await?)
+    }
+
+    /// Closed trades summed per trading day of the month starting on
+    /// `first_day`. Days are cut like the daily equity buckets; fees are
+    /// converted into the trade currency, then like the P&L. With a
+    /// reporting currency, fails when a trade lacks a rate.
+    pub async fn calendar_buckets(
+        &self,
+        user_id: Uuid,
+        filters: &TradeFilters,
+        timezone: Tz,
+        rollover_hour: i16,
+        currency: Option<&str>,
+        first_day: NaiveDate,
+    ) -> Result<Vec<CalendarBucket>> {
+        self.ensure_rates(user_id, filters, currency).await?;
+
+        let (conditions, mut param_count) = TradeRepository::filter_conditions(filters);
+        if currency.is_some() {
+            param_count += 1;
+        }
+        let source = Self::source(currency.map(|_| param_count));
+        let rate = match currency {
+            Some(_) => format!("CASE WHEN currency = ${param_count} THEN 1 ELSE fx_rate END"),
+            None => "1".to_string(),
+        };
+
+        let query = format!(
+            r#"
+            SELECT
+                date,
+                COUNT(*)::INT4 AS total_trades,
+                (COUNT(*) FILTER (WHERE pnl > 0))::INT4 AS winning_trades,
+                SUM(pnl) AS net_pnl,
+                SUM(fees) AS fees
+            FROM (
+                SELECT
+                    ((COALESCE(exit_time, entry_time) + make_interval(hours => ${shift_param}))
+                        AT TIME ZONE ${tz_param})::DATE AS date,
+                    pnl,
+                    ROUND(fees * fee_fx_rate * {rate}, 8) AS fees
+                FROM {source}
+                WHERE {conditions} AND pnl IS NOT NULL
+            ) t
+            WHERE date >= ${month_param} AND date < (${month_param} + INTERVAL '1 month')::DATE
+            GROUP BY date
+            ORDER BY date
+            "#,
+            tz_param = param_count + 1,
+            shift_param = param_count + 2,
+            month_param = param_count + 3,
+        );
+
+        let q = TradeRepository::bind_filters(sqlx::query_as::<_, CalendarBucket>(&query), user_id, filters);
+        let buckets = Self::bind_currency(q, currency)
+            .bind(timezone.name())
+            .bind(i32::from((24 - rollover_hour) % 24))
+            .bind(first_day)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(buckets)
+    }
+
+    /// R-multiple averages and a histogram with buckets `bucket_size` R wide
+    pub async fn r_multiples(
+        &self,
+        user_id: Uuid,
+        filters: &TradeFilters,
+        bucket_size: Decimal,
+    ) -> Result<RMultipleAnalytics> {
+        let (conditions, param_count) = TradeRepository::filter_conditions(filters);
+
+        let totals_query = format!(
+            r#"
+            SELECT
+                (COUNT(*) FILTER (WHERE r_multiple IS NOT NULL))::INT4 AS total_trades,
+                (COUNT(*) FILTER (WHERE r_multiple > 0))::INT4 AS winning_trades,
+                (COUNT(*) FILTER (WHERE r_multiple < 0))::INT4 AS losing_trades,
+                COALESCE(SUM(r_multiple), 0) AS total_r,
+                COALESCE(SUM(r_multiple) FILTER (WHERE r_multiple > 0), 0) AS total_win_r,
+                COALESCE(SUM(r_multiple) FILTER (WHERE r_multiple < 0), 0) AS total_loss_r,
+                (COUNT(*) FILTER (WHERE planned_r_multiple IS NOT NULL))::INT4 AS planned_trades,
+                COALESCE(SUM(planned_r_multiple), 0) AS total_planned_r
+            FROM trades
+            WHERE {conditions}
+            "#,
+        );
+
+        let totals =
+            TradeRepository::bind_filters(sqlx::query_as::<_, RMultipleTotals>(&totals_query), user_id, filters)
+                .fetch_one(&self.pool)
+                .await?;
+
+        let size_param = param_count + 1;
+        let buckets_query = format!(
+            r#"
+            SELECT
+                FLOOR(r_multiple / ${size_param}) * ${size_param} AS "from",
+                (FLOOR(r_multiple / ${size_param}) + 1) * ${size_param} AS "to",
+                COUNT(*)::INT4 AS count
+            FROM trades
+            WHERE {conditions} AND r_multiple IS NOT NULL
+            GROUP BY 1, 2
+            ORDER BY 1
+            "#,
+        );
+
+        let distribution =
+            TradeRepository::bind_filters(sqlx::query_as::<_, RBucket>(&buckets_query), user_id, filters)
+                .bind(bucket_size)
+                .fetch_all(&self.pool)
+                .await?;
+
+        Ok(RMultipleAnalytics::new(totals, distribution))
+    }
+
+    /// MAE, MFE and efficiencies of every closed trade with price bars, for
+    /// scatter plots against the result. Excursion amounts are the per-unit
+    /// distances times quantity and multiplier, converted like the P&L.
+    pub async fn excursions(
+        &self,
+        user_id: Uuid,
+        filters: &TradeFilters,
+        currency: Option<&str>,
+    ) -> Result<ExcursionAnalytics> {
+        let fx_rates_used = self.rates_used(user_id, filters, currency).await?;
+
+        let (conditions, param_count) = TradeRepository::filter_conditions(filters);
+        let currency_param = param_count + 1;
+        let source = Self::source(currency.map(|_| currency_param));
+        let rate = match currency {
+            Some(_) => format!("CASE WHEN currency = ${currency_param} THEN 1 ELSE fx_rate END"),
+            None => "1".to_string(),
+        };
+
+        let query = format!(
+            r#"
+            SELECT
+                id AS trade_id,
+                symbol,
+                direction,
+                exit_time,
+                pnl,
+                r_multiple,
+                mae,
+                mfe,
+                ROUND(mae * quantity * multiplier * {rate}, 8) AS mae_amount,
+                ROUND(mfe * quantity * multiplier * {rate}, 8) AS mfe_amount,
+                entry_efficiency,
+                exit_efficiency
+            FROM {source}
+            WHERE {conditions} AND pnl IS NOT NULL AND mae IS NOT NULL
+            ORDER BY exit_time, id
+            "#,
+        );
+
+        let q = TradeRepository::bind_filters(sqlx::query_as::<_, ExcursionPoint>(&query), user_id, filters);
+        let points = Self::bind_currency(q, currency).fetch_all(&self.pool).await?;
+
+        let mut analytics = ExcursionAnalytics::new(points);
+        analytics.currency = currency.map(str::to_string);
+        analytics.fx_rates_used = fx_rates_used;
+
+        Ok(analytics)
+    }
+
+    /// Entry time, exit time and P&L of closed trades, for the time
+    /// breakdowns
+    pub async fn timed_trades(
+        &self,
+        user_id: Uuid,
+        filters: &TradeFilters,
+        currency: Option<&str>,
+    ) -> Result<Vec<TimedTrade>> {
+        self.ensure_rates(user_id, filters, currency).await?;
+
+        let (conditions, param_count) = TradeRepository::filter_conditions(filters);
+        let source = Self::source(currency.map(|_| param_count + 1));
+        let query = format!(
+            r#"
+            SELECT entry_time, exit_time, pnl
+            FROM {source}
+            WHERE {conditions} AND pnl IS NOT NULL
+            ORDER BY entry_time, id
+            "#,
+        );
+
+        let q = TradeRepository::bind_filters(sqlx::query_as::<_, TimedTrade>(&query), user_id, filters);
+        let trades = Self::bind_currency(q, currency).fetch_all(&self.pool).await?;
+
+        Ok(trades)
+    }
+
+    /// Count, wins and P&L grouped by a trade column
+    async fn group_totals(
+        &self,
+        user_id: Uuid,
+        filters: &TradeFilters,
+        currency: Option<&str>,
+        column: &str,
+    ) -> Result<Vec<GroupTotals>> {
+        let (conditions, param_count) = TradeRepository::filter_conditions(filters);
+        let source = Self::source(currency.map(|_| param_count + 1));
+        let query = format!(
+            r#"
+            SELECT
+                {column} AS key,
+                COUNT(*)::INT4 AS total_trades,
+                (COUNT(*) FILTER (WHERE pnl > 0))::INT4 AS winning_trades,
+                COALESCE(SUM(pnl), 0) AS total_pnl
+            FROM {source}
+            WHERE {conditions} AND {column} IS NOT NULL
+            GROUP BY {column}
+            "#,
+        );
+
+        let q = TradeRepository::bind_filters(sqlx::query_as::<_, GroupTotals>(&query), user_id, filters);
+        let groups = Self::bind_currency(q, currency).fetch_all(&self.pool).await?;
+
+        Ok(groups)
+    }
+
+    /// Journal days with the P&L of their linked trades that match the
+    /// filters, oldest first. Days without such trades are included.
+    pub async fn journal_days(
+        &self,
+        user_id: Uuid,
+        filters: &TradeFilters,
+        currency: Option<&str>,
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+    ) -> Result<Vec<JournalDay>> {
+        self.ensure_rates(user_id, filters, currency).await?;
+
+        let (conditions, mut param_count) = TradeRepository::filter_conditions(filters);
+        if currency.is_some() {
+            param_count += 1;
+        }
+        let source = Self::source(currency.map(|_| param_count));
+
+        let query = format!(
+            r#"
+            SELECT
+                e.id AS entry_id,
+                e.entry_date AS date,
+                e.plan_followed,
+                e.mood,
+                e.energy,
+                COUNT(t.id)::INT4 AS trades,
+                SUM(t.pnl) AS pnl
+            FROM journal_entries e
+            LEFT JOIN journal_entry_trades jt ON jt.entry_id = e.id
+            LEFT JOIN (
+                SELECT id, pnl FROM {source}
+                WHERE {conditions} AND pnl IS NOT NULL
+            ) t ON t.id = jt.trade_id
+            WHERE e.user_id = $1
+                AND (${from_param}::DATE IS NULL OR e.entry_date >= ${from_param})
+                AND (${to_param}::DATE IS NULL OR e.entry_date <= ${to_param})
+            GROUP BY e.id
+            ORDER BY e.entry_date
+            "#,
+            from_param = param_count + 1,
+            to_param = param_count + 2,
+        );
+
+        let q = TradeRepository::bind_filters(sqlx::query_as::<_, JournalDay>(&query), user_id, filters);
+        let days = Self::bind_currency(q, currency)
+            .bind(from)
+            .bind(to)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(days)
+    }
+
+    /// Rates converting the filtered trades into the reporting currency.
+    ///
+    /// Fails when a trade's currency has no rate on or before its exit date,
+    /// so figures are never built from partially converted P&L. Empty
+    /// without a reporting currency.
+    pub async fn rates_used(
+        &self,
+        user_id: Uuid,
+        filters: &TradeFilters,
+        currency: Option<&str>,
+    ) -> Result<Vec<FxRateUsed>> {
+        let rates = self.rates_used_by(user_id, filters, currency, "NULL::TEXT", "").await?;
+        Ok(rates.into_values().flatten().collect())
+    }
+
+    /// `rates_used`, split by a grouping expression
+    async fn rates_used_by(
+        &self,
+        user_id: Uuid,
+        filters: &TradeFilters,
+        currency: Option<&str>,
+        key: &str,
+        join: &str,
+    ) -> Result<HashMap<String, Vec<FxRateUsed>>> {
+        let Some(currency) = currency else {
+            return Ok(HashMap::new());
+        };
+
+        self.ensure_rates(user_id, filters, Some(currency)).await?;
+
+        let (conditions, param_count) = TradeRepository::filter_conditions(filters);
+        let currency_param = param_count + 1;
+        let source = Self::source(Some(currency_param));
+
+        let usage_query = format!(
+            r#"
+            SELECT
+                {key} AS key,
+                currency::TEXT AS from_currency,
+                ${currency_param}::TEXT AS to_currency,
+                fx_rate_date AS rate_date,
+                fx_rate AS rate,
+                COUNT(*)::INT4 AS trades
+            FROM {source}
+            {join}
+            WHERE {conditions} AND original_pnl IS NOT NULL AND currency <> ${currency_param}
+            GROUP BY 1, 2, 4, 5
+            ORDER BY 2, 4
+            "#,
+        );
+
+        let usage = TradeRepository::bind_filters(sqlx::query_as::<_, FxUsage>(&usage_query), user_id, filters)
+            .bind(currency)
+            .fetch_all(&self.pool)
+            .await?;
+
+        let mut rates: HashMap<String, Vec<FxRateUsed>> = HashMap::new();
+        for row in usage {
+            rates.entry(row.key.unwrap_or_default()).or_default().push(row.rate);
+        }
+
+        Ok(rates)
+    }
+
+    /// Fail when any filtered trade's currency has no rate into the
+    /// reporting currency on or before its exit date, naming every missing
+    /// pair, so figures are never built from partially converted P&L
+    async fn ensure_rates(&self, user_id: Uuid, filters: &TradeFilters, currency: Option<&str>) -> Result<()> {
+        let Some(currency) = currency else {
+            return Ok(());
+        };
+
+        let (conditions, param_count) = TradeRepository::filter_conditions(filters);
+        let currency_param = param_count + 1;
+        let source = Self::source(Some(currency_param));
+        let query = format!(
+            r#"
+            SELECT currency::TEXT, MIN((COALESCE(exit_time, entry_time) AT TIME ZONE 'UTC')::DATE)
+            FROM {source}
+            WHERE {conditions} AND original_pnl IS NOT NULL AND fx_rate IS NULL AND currency <> ${currency_param}
+            GROUP BY currency
+            ORDER BY currency
+            "#,
+        );
+
+        let missing: Vec<(String, NaiveDate)> = TradeRepository::bind_filters(sqlx::query_as(&query), user_id, filters)
+            .bind(currency)
+            .fetch_all(&self.pool)
+            .await?;
+        if missing.is_empty() {
+            return Ok(());
+        }
+
+        let pairs: Vec<String> = missing
+            .iter()
+            .map(|(from, date)| format!("{}/{} on or before {}", from, currency, date))
+            .collect();
+        Err(AppError::ValidationError(format!("Missing FX rates: {}", pairs.join(", "))))
+    }
+
+    /// Capital behind the P&L percentage, converted into the reporting
+    /// currency at the latest rates, with the rates applied
+    async fn capital(
+        &self,
+        user_id: Uuid,
+        account_id: Option<Uuid>,
+        currency: Option<&str>,
+    ) -> Result<(Decimal, Vec<FxRateUsed>)> {
+        let capital = AccountRepository::new(self.pool.clone())
+            .capital(user_id, account_id)
+            .await?;
+
+        let Some(currency) = currency else {
+            return Ok((capital.into_iter().map(|(_, amount)| amount).sum(), Vec::new()));
+        };
+
+        let currencies: Vec<String> = capital.iter().map(|(from, _)| from.clone()).collect();
+        let rates = self.latest_rates(user_id, &currencies, currency).await?;
+
+        let total = capital
+            .into_iter()
+            .map(|(from, amount)| match rates.iter().find(|rate| rate.from_currency == from) {
+                Some(rate) => amount * rate.rate,
+                None => amount,
+            })
+            .sum();
+
+        Ok((total, rates))
+    }
+
+    /// Latest rates converting each currency into the reporting currency,
+    /// which needs none; `trades` is left at zero
+    pub async fn latest_rates(&self, user_id: Uuid, currencies: &[String], currency: &str) -> Result<Vec<FxRateUsed>> {
+        let fx_repo = FxRateRepository::new(self.pool.clone());
+        let today = Utc::now().date_naive();
+        let mut rates: Vec<FxRateUsed> = Vec::new();
+
+        for from in currencies {
+            if from == currency || rates.iter().any(|rate| rate.from_currency == *from) {
+                continue;
+            }
+
+            let (rate, rate_date) = fx_repo
+                .rate(user_id, from, currency, today)
+                .await?
+                .ok_or_else(|| {
+                    AppError::ValidationError(format!("No {}/{} FX rate on or before {}", from, currency, today))
+                })?;
+
+            rates.push(FxRateUsed {
+                from_currency: from.clone(),
+                to_currency: currency.to_string(),
+                rate_date,
+                rate,
+                trades: 0,
+            });
+        }
+
+        Ok(rates)
+    }
+
+    /// Trades as read by the analytics queries.
+    ///
+    /// Without a currency parameter this is the table itself. Otherwise a
+    /// subquery named `trades` where `pnl` is converted into the currency
+    /// bound at `$currency_param`, at the latest rate on or before the exit
+    /// date (UTC); a stored inverse pair is used inverted. `original_pnl`,
+    /// `fx_rate` and `fx_rate_date` tell what was applied, and `pnl` is NULL
+    /// when no rate exists, so queries reading it call `ensure_rates`
+    /// first. Columns read by `filter_conditions` and the
+    /// queries in this file must be listed here.
+    fn source(currency_param: Option<usize>) -> String {
+        let Some(p) = currency_param else {
+            return "trades".to_string();
+        };
+
+        format!(
+            r#"(
+                SELECT
+                    t.id, t.user_id, t.symbol, t.underlying, t.direction, t.status, t.entry_time, t.exit_time,
+                    t.setup_type, t.playbook_id, t.checklist_met, t.tags, t.mistakes, t.broker, t.account_id,
+                    t.currency, t.quantity, t.multiplier, t.fees, t.fee_fx_rate, t.r_multiple, t.planned_r_multiple,
+                    t.mae, t.mfe, t.entry_efficiency, t.exit_efficiency,
+                    CASE WHEN t.currency = ${p} THEN t.pnl ELSE ROUND(t.pnl * fx.rate, 8) END AS pnl,
+                    t.pnl AS original_pnl,
+                    fx.rate AS fx_rate,
+                    fx.rate_date AS fx_rate_date
+                FROM trades t
+                LEFT JOIN LATERAL (
+                    SELECT
+                        CASE WHEN base_currency = t.currency THEN rate ELSE ROUND(1 / rate, 10) END AS rate,
+                        rate_date
+                    FROM fx_rates
+                    WHERE user_id = t.user_id
+                        AND ((base_currency = t.currency AND quote_currency = ${p})
+                            OR (base_currency = ${p} AND quote_currency = t.currency))
+                        AND rate_date <= (COALESCE(t.exit_time, t.entry_time) AT TIME ZONE 'UTC')::DATE
+                    ORDER BY rate_date DESC, base_currency = t.currency DESC
+                    LIMIT 1
+                ) fx ON t.currency <> ${p}
+            ) AS trades"#,
+        )
+    }
+
+    /// Bind the reporting currency after the filter parameters
+    fn bind_currency<'q, O>(
+        q: QueryAs<'q, Postgres, O, PgArguments>,
+        currency: Option<&'q str>,
+    ) -> QueryAs<'q, Postgres, O, PgArguments> {
+        match currency {
+            Some(currency) => q.bind(currency),
+            None => q,
+        }
+    }
+}
+
+/// Grouping key of the symbol breakdown: option trades count towards their
+/// underlying
+const SYMBOL_KEY: &str = "COALESCE(underlying, symbol)";
+
+/// One row per mistake of a trade
+const MISTAKES_JOIN: &str = "CROSS JOIN LATERAL UNNEST(mistakes) AS m(mistake)";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{models::CreateTradeRequest, test_support};
+    use chrono::{DateTime, Datelike, Duration, TimeZone};
+
+    /// Long trade of one unit opened `day` days into 2025, closed a day
+    /// later for `pnl` unless it is still open
+    fn trade(day: i64, symbol: &str, pnl: Option<i64>, setup_type: Option<&str>, mistakes: &[&str]) -> CreateTradeRequest {
+        let entry_time: DateTime<Utc> = Utc.with_ymd_and_hms(2025, 1, 1, 15, 0, 0).unwrap() + Duration::days(day);
+
+        CreateTradeRequest {
+            symbol: symbol.to_string(),
+            direction: "long".to_string(),
+            entry_price: Decimal::from(100),
+            exit_price: pnl.map(|pnl| Decimal::from(100 + pnl)),
+            quantity: Decimal::ONE,
+            entry_time,
+            exit_time: pnl.map(|_| entry_time + Duration::days(1)),
+            setup_type: setup_type.map(str::to_string),
+            mistakes: Some(mistakes.iter().map(|m| m.to_string()).collect()),
+            ..Default::default()
+        }
+    }
+
+    /// Trade closed at `exit_time` for `pnl`, an hour after it was opened
+    fn closed_at(exit_time: DateTime<Utc>, pnl: i64) -> CreateTradeRequest {
+        CreateTradeRequest {
+            entry_time: exit_time - Duration::hours(1),
+            exit_time: Some(exit_time),
+            ..trade(0, "AAPL", Some(pnl), None, &[])
+        }
+    }
+
+    fn json(value: &impl serde::Serialize) -> serde_json::Value {
+        serde_json::to_value(value).unwrap()
+    }
+
+    #[tokio::test]
+    #[ignore = "needs PostgreSQL: `docker compose up -d postgres`, then `cargo test -- --ignored`"]
+    async fn sql_aggregates_match_the_in_memory_reference() {
+        let pool = test_support::pool().await;
+        let user_id = test_support::user(&pool).await;
+        let trades = TradeRepository::new(pool.clone());
+
+        let requests = [
+            trade(0, "AAPL", Some(100), Some("breakout"), &[]),
+            trade(1, "AAPL", Some(50), None, &[]),
+            trade(2, "MSFT", Some(-30), Some("breakout"), &["early exit"]),
+            // Break-even: neither extends nor breaks the losing streak
+            trade(3, "MSFT", Some(0), Some("pullback"), &["fomo"]),
+            trade(4, "SPY", Some(-20), None, &["fomo", "early exit"]),
+            trade(5, "SPY   250117C00600000", Some(-10), Some("pullback"), &[]),
+            trade(6, "TSLA", Some(40), Some("reversal"), &[]),
+            // Still open: counted, but without P&L for its setup or mistake
+            trade(7, "TSLA", None, Some("reversal"), &["fomo", "chased"]),
+        ];
+        for request in requests {
+            trades.create(user_id, request).await.unwrap();
+        }
+
+        let stored = trades.list(user_id, TradeFilters::default()).await.unwrap();
+        let repo = AnalyticsRepository::new(pool.clone());
+        let filters = TradeFilters::default();
+
+        let overview = repo.overview(user_id, &filters, None).await.unwrap();
+        assert_eq!(
+            (overview.total_trades, overview.winning_trades, overview.losing_trades),
+            (8, 3, 3)
+        );
+        assert_eq!(overview.longest_win_streak, 2);
+        assert_eq!(overview.longest_loss_streak, 3);
+        assert_eq!(overview.current_streak, 1);
+        assert_eq!(json(&overview), json(&AnalyticsService::calculate_overview(&stored).unwrap()));
+
+        let by_symbol = repo.by_symbol(user_id, &filters, None).await.unwrap();
+        let symbols: Vec<(&str, i32)> = by_symbol.iter().map(|s| (s.symbol.as_str(), s.total_trades)).collect();
+        assert_eq!(symbols, [("AAPL", 2), ("TSLA", 2), ("MSFT", 2), ("SPY", 2)]);
+        assert_eq!(json(&by_symbol), json(&AnalyticsService::calculate_by_symbol(&stored).unwrap()));
+
+        let by_setup = repo.by_setup(user_id, &filters, None).await.unwrap();
+        assert!(by_setup.iter().all(|s| !s.setup_type.is_empty()));
+        assert_eq!(by_setup.iter().map(|s| s.total_trades).sum::<i32>(), 6);
+        assert_eq!(json(&by_setup), json(&AnalyticsService::calculate_by_setup(&stored).unwrap()));
+
+        let mistakes = repo.mistakes(user_id, &filters, None).await.unwrap();
+        let counts: Vec<(&str, i32)> = mistakes.iter().map(|m| (m.mistake.as_str(), m.count)).collect();
+        assert_eq!(counts, [("early exit", 2), ("fomo", 2)]);
+        assert_eq!(json(&mistakes), json(&AnalyticsService::analyze_mistakes(&stored).unwrap()));
+
+        test_support::delete_user(&pool, user_id).await;
+    }
+
+    #[tokio::test]
+    #[ignore = "needs PostgreSQL: `docker compose up -d postgres`, then `cargo test -- --ignored`"]
+    async fn calendar_days_end_at_the_rollover_hour() {
+        let pool = test_support::pool().await;
+        let user_id = test_support::user(&pool).await;
+        let trades = TradeRepository::new(pool.clone());
+
+        let utc = |y, mo, d, h, mi| Utc.with_ymd_and_hms(y, mo, d, h, mi, 0).unwrap();
+        let requests = [
+            // 18:00 on New Year's Eve in New York
+            closed_at(utc(2024, 12, 31, 23, 0), 1),
+            // 23:30 on 1 January
+            closed_at(utc(2025, 1, 2, 4, 30), 2),
+            // 16:59 and 17:00 on 31 January
+            closed_at(utc(2025, 1, 31, 21, 59), 4),
+            closed_at(utc(2025, 1, 31, 22, 0), 8),
+        ];
+        for request in requests {
+            trades.create(user_id, request).await.unwrap();
+        }
+
+        let repo = AnalyticsRepository::new(pool.clone());
+        let timezone = chrono_tz::America::New_York;
+        let january = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+        let days = |buckets: &[CalendarBucket]| -> Vec<(u32, i64)> {
+            buckets.iter().map(|b| (b.date.day(), b.net_pnl.try_into().unwrap())).collect()
+        };
+
+        let midnight = repo
+            .calendar_buckets(user_id, &TradeFilters::default(), timezone, 0, None, january)
+            .await
+            .unwrap();
+        assert_eq!(days(&midnight), [(1, 2), (31, 12)]);
+
+        // From 17:00 trades count toward the next day, moving one into
+        // January and one out of it
+        let rollover = repo
+            .calendar_buckets(user_id, &TradeFilters::default(), timezone, 17, None, january)
+            .await
+            .unwrap();
+        assert_eq!(days(&rollover), [(1, 1), (2, 2), (31, 4)]);
+
+        let calendar = AnalyticsService::calendar(january, rollover, &[], timezone, 17);
+        assert_eq!(calendar.day_rollover_hour, 17);
+        assert_eq!(calendar.totals.total_trades, 3);
+        assert_eq!(calendar.totals.net_pnl, Decimal::from(7));
+        assert_eq!(calendar.weeks[0].totals.net_pnl, Decimal::from(3));
+
+        test_support::delete_user(&pool, user_id).await;
+    }
+}
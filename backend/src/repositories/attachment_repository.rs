@@ -0,0 +1,219 @@
+use crate::{
+    error::{AppError, Result},
+    models::{Attachment, NewAttachment, Trade},
+    repositories::TradeRevisionRepository,
+};
+use sqlx::{PgConnection, PgPool};
+use uuid::Uuid;
+
+pub struct AttachmentRepository {
+    pool: PgPool,
+}
+
+impl AttachmentRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Record attachments whose files are already in the blob store, listing
+    /// images among the trade's screenshots.
+    ///
+    /// Fails when they would take the user's attachments past `quota_bytes`.
+    /// The user row stays locked until commit, so concurrent uploads are
+    /// checked against the quota one after another.
+    pub async fn create_many(
+        &self,
+        trade_id: Uuid,
+        user_id: Uuid,
+        quota_bytes: i64,
+        new_attachments: &[NewAttachment],
+    ) -> Result<Vec<Attachment>> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query(
+            r#"
+            SELECT id FROM trades WHERE id = $1 AND user_id = $2 FOR UPDATE
+            "#,
+        )
+        .bind(trade_id)
+        .bind(user_id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or(AppError::ValidationError("Trade not found".to_string()))?;
+
+        sqlx::query("SELECT id FROM users WHERE id = $1 FOR UPDATE")
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+        let used = Self::storage_used_with(&mut tx, user_id).await?;
+        let added = new_attachments.iter().map(|new| new.size_bytes).sum();
+        Self::check_quota(used, added, quota_bytes)?;
+
+        // clock_timestamp() keeps the files of one upload in their order
+        let mut attachments = Vec::with_capacity(new_attachments.len());
+        for new in new_attachments {
+            let attachment = sqlx::query_as::<_, Attachment>(
+                r#"
+                INSERT INTO trade_attachments (
+                    id, trade_id, user_id, file_name, content_type, size_bytes,
+                    width, height, storage_key, thumbnail_key, created_at
+                )
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, clock_timestamp())
+                RETURNING *
+                "#,
+            )
+            .bind(new.id)
+            .bind(trade_id)
+            .bind(user_id)
+            .bind(&new.file_name)
+            .bind(&new.content_type)
+            .bind(new.size_bytes)
+            .bind(new.width)
+            .bind(new.height)
+            .bind(&new.storage_key)
+            .bind(&new.thumbnail_key)
+            .fetch_one(&mut *tx)
+            .await?;
+
+            attachments.push(attachment);
+        }
+
+        Self::sync_screenshots(&mut tx, trade_id).await?;
+
+        tx.commit().await?;
+
+        Ok(attachments)
+    }
+
+    /// List attachments of a trade in upload order
+    pub async fn list_for_trade(&self, trade_id: Uuid, user_id: Uuid) -> Result<Vec<Attachment>> {
+        let attachments = sqlx::query_as::<_, Attachment>(
+            r#"
+            SELECT * FROM trade_attachments
+            WHERE trade_id = $1 AND user_id = $2
+            ORDER BY created_at ASC, id ASC
+            "#,
+        )
+        .bind(trade_id)
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(attachments)
+    }
+
+    /// Get an attachment of a trade by ID
+    pub async fn get(&self, attachment_id: Uuid, trade_id: Uuid, user_id: Uuid) -> Result<Option<Attachment>> {
+        let attachment = sqlx::query_as::<_, Attachment>(
+            r#"
+            SELECT * FROM trade_attachments WHERE id = $1 AND trade_id = $2 AND user_id = $3
+            "#,
+        )
+        .bind(attachment_id)
+        .bind(trade_id)
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(attachment)
+    }
+
+    /// Bytes of stored files a user's attachments take up
+    pub async fn storage_used(&self, user_id: Uuid) -> Result<i64> {
+        let mut conn = self.pool.acquire().await?;
+        Self::storage_used_with(&mut conn, user_id).await
+    }
+
+    pub(crate) async fn storage_used_with(conn: &mut PgConnection, user_id: Uuid) -> Result<i64> {
+        let used: i64 = sqlx::query_scalar(
+            r#"
+            SELECT COALESCE(SUM(size_bytes), 0)::BIGINT FROM trade_attachments WHERE user_id = $1
+            "#,
+        )
+        .bind(user_id)
+        .fetch_one(conn)
+        .await?;
+
+        Ok(used)
+    }
+
+    /// Fail when adding `added` bytes to `used` would exceed the quota
+    pub fn check_quota(used: i64, added: i64, quota_bytes: i64) -> Result<()> {
+        if used + added > quota_bytes {
+            return Err(AppError::ValidationError(format!(
+                "Uploading would exceed the {} MB attachment storage of your plan ({} MB used)",
+                quota_bytes / (1024 * 1024),
+                used / (1024 * 1024)
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Delete an attachment's record and return it so its blobs can be
+    /// removed
+    pub async fn delete(&self, attachment_id: Uuid, trade_id: Uuid, user_id: Uuid) -> Result<Attachment> {
+        let mut tx = self.pool.begin().await?;
+
+        let attachment = sqlx::query_as::<_, Attachment>(
+            r#"
+            DELETE FROM trade_attachments WHERE id = $1 AND trade_id = $2 AND user_id = $3
+            RETURNING *
+            "#,
+        )
+        .bind(attachment_id)
+        .bind(trade_id)
+        .bind(user_id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or(AppError::ValidationError("Attachment not found".to_string()))?;
+
+        Self::sync_screenshots(&mut tx, trade_id).await?;
+
+        tx.commit().await?;
+
+        Ok(attachment)
+    }
+
+    /// Point the trade's screenshots at its image attachments, recording
+    /// the change in the trade's history
+    async fn sync_screenshots(conn: &mut PgConnection, trade_id: Uuid) -> Result<()> {
+        let before = sqlx::query_as::<_, Trade>(
+            r#"
+            SELECT * FROM trades WHERE id = $1 FOR UPDATE
+            "#,
+        )
+        .bind(trade_id)
+        .fetch_one(&mut *conn)
+        .await?;
+
+        let image_ids: Vec<Uuid> = sqlx::query_scalar(
+            r#"
+            SELECT id FROM trade_attachments
+            WHERE trade_id = $1 AND content_type LIKE 'image/%'
+            ORDER BY created_at ASC, id ASC
+            "#,
+        )
+        .bind(trade_id)
+        .fetch_all(&mut *conn)
+        .await?;
+
+        let screenshots: Vec<String> = image_ids
+            .into_iter()
+            .map(|id| Attachment::download_path(trade_id, id))
+            .collect();
+
+        let after = sqlx::query_as::<_, Trade>(
+            r#"
+            UPDATE trades SET screenshots = $2, updated_at = NOW() WHERE id = $1
+            RETURNING *
+            "#,
+        )
+        .bind(trade_id)
+        .bind(screenshots)
+        .fetch_one(&mut *conn)
+        .await?;
+
+        TradeRevisionRepository::record_with(conn, after.user_id, "update", Some(&before), Some(&after), None).await
+    }
+}
@@ -0,0 +1,87 @@
+use crate::{
+    error::Result,
+    models::{CreateExecutionRequest, TradeExecution},
+};
+use rust_decimal::Decimal;
+use sqlx::{PgConnection, PgPool};
+use uuid::Uuid;
+
+pub struct ExecutionRepository {
+    pool: PgPool,
+}
+
+impl ExecutionRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// List executions of a trade in fill order
+    pub async fn list_for_trade(&self, trade_id: Uuid, user_id: Uuid) -> Result<Vec<TradeExecution>> {
+        let mut conn = self.pool.acquire().await?;
+        Self::list_for_trade_with(&mut conn, trade_id, user_id).await
+    }
+
+    /// List executions of a trade using an existing connection or transaction
+    pub(crate) async fn list_for_trade_with(
+        conn: &mut PgConnection,
+        trade_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<Vec<TradeExecution>> {
+        let executions = sqlx::query_as::<_, TradeExecution>(
+            r#"
+            SELECT * FROM trade_executions
+            WHERE trade_id = $1 AND user_id = $2
+            ORDER BY executed_at ASC
+            "#,
+        )
+        .bind(trade_id)
+        .bind(user_id)
+        .fetch_all(conn)
+        .await?;
+
+        Ok(executions)
+    }
+
+    /// Replace all executions of a trade
+    pub(crate) async fn replace_for_trade(
+        conn: &mut PgConnection,
+        trade_id: Uuid,
+        user_id: Uuid,
+        executions: &[CreateExecutionRequest],
+    ) -> Result<Vec<TradeExecution>> {
+        sqlx::query(
+            r#"
+            DELETE FROM trade_executions WHERE trade_id = $1 AND user_id = $2
+            "#,
+        )
+        .bind(trade_id)
+        .bind(user_id)
+        .execute(&mut *conn)
+        .await?;
+
+        let mut inserted = Vec::with_capacity(executions.len());
+
+        for execution in executions {
+            let row = sqlx::query_as::<_, TradeExecution>(
+                r#"
+                INSERT INTO trade_executions (trade_id, user_id, side, price, quantity, executed_at, fee)
+                VALUES ($1, $2, $3, $4, $5, $6, $7)
+                RETURNING *
+                "#,
+            )
+            .bind(trade_id)
+            .bind(user_id)
+            .bind(&execution.side)
+            .bind(execution.price)
+            .bind(execution.quantity)
+            .bind(execution.executed_at)
+            .bind(execution.fee.unwrap_or(Decimal::ZERO))
+            .fetch_one(&mut *conn)
+            .await?;
+
+            inserted.push(row);
+        }
+
+        Ok(inserted)
+    }
+}
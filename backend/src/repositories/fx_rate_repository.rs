@@ -0,0 +1,128 @@
+use crate::{
+    error::{AppError, Result},
+    models::{CreateFxRateRequest, FxRate, FxRateQuery},
+};
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use sqlx::{PgConnection, PgPool};
+use uuid::Uuid;
+
+pub struct FxRateRepository {
+    pool: PgPool,
+}
+
+impl FxRateRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Store the rate of a pair on a date, replacing any rate already
+    /// recorded for that day
+    pub async fn upsert(&self, user_id: Uuid, req: &CreateFxRateRequest, source: &str) -> Result<FxRate> {
+        let rate = sqlx::query_as::<_, FxRate>(
+            r#"
+            INSERT INTO fx_rates (user_id, base_currency, quote_currency, rate_date, rate, source)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            ON CONFLICT (user_id, base_currency, quote_currency, rate_date)
+            DO UPDATE SET rate = EXCLUDED.rate, source = EXCLUDED.source, updated_at = NOW()
+            RETURNING *
+            "#,
+        )
+        .bind(user_id)
+        .bind(&req.base_currency)
+        .bind(&req.quote_currency)
+        .bind(req.rate_date)
+        .bind(req.rate)
+        .bind(source)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(rate)
+    }
+
+    /// List rates, newest first
+    pub async fn list(&self, user_id: Uuid, query: &FxRateQuery) -> Result<Vec<FxRate>> {
+        let rates = sqlx::query_as::<_, FxRate>(
+            r#"
+            SELECT * FROM fx_rates
+            WHERE user_id = $1
+                AND ($2::TEXT IS NULL OR base_currency = $2)
+                AND ($3::TEXT IS NULL OR quote_currency = $3)
+                AND ($4::DATE IS NULL OR rate_date >= $4)
+                AND ($5::DATE IS NULL OR rate_date <= $5)
+            ORDER BY rate_date DESC, base_currency, quote_currency
+            "#,
+        )
+        .bind(user_id)
+        .bind(&query.base_currency)
+        .bind(&query.quote_currency)
+        .bind(query.from_date)
+        .bind(query.to_date)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rates)
+    }
+
+    /// Delete a rate
+    pub async fn delete(&self, rate_id: Uuid, user_id: Uuid) -> Result<()> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM fx_rates WHERE id = $1 AND user_id = $2
+            "#,
+        )
+        .bind(rate_id)
+        .bind(user_id)
+        .execute(&self.pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::ValidationError("FX rate not found".to_string()));
+        }
+
+        Ok(())
+    }
+
+    /// Latest rate converting `from` into `to` on or before `date`
+    pub async fn rate(&self, user_id: Uuid, from: &str, to: &str, date: NaiveDate) -> Result<Option<(Decimal, NaiveDate)>> {
+        let mut conn = self.pool.acquire().await?;
+        Self::rate_with(&mut conn, user_id, from, to, date).await
+    }
+
+    /// Latest rate converting `from` into `to` on or before `date`, using an
+    /// existing connection or transaction.
+    ///
+    /// A stored `to/from` pair is used inverted when it is more recent than
+    /// the direct pair.
+    pub(crate) async fn rate_with(
+        conn: &mut PgConnection,
+        user_id: Uuid,
+        from: &str,
+        to: &str,
+        date: NaiveDate,
+    ) -> Result<Option<(Decimal, NaiveDate)>> {
+        if from == to {
+            return Ok(Some((Decimal::ONE, date)));
+        }
+
+        let rate: Option<(Decimal, NaiveDate)> = sqlx::query_as(
+            r#"
+            SELECT CASE WHEN base_currency = $2 THEN rate ELSE ROUND(1 / rate, 10) END, rate_date
+            FROM fx_rates
+            WHERE user_id = $1
+                AND ((base_currency = $2 AND quote_currency = $3) OR (base_currency = $3 AND quote_currency = $2))
+                AND rate_date <= $4
+            ORDER BY rate_date DESC, base_currency = $2 DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(user_id)
+        .bind(from)
+        .bind(to)
+        .bind(date)
+        .fetch_optional(conn)
+        .await?;
+
+        Ok(rate)
+    }
+}
@@ -0,0 +1,150 @@
+use crate::{
+    error::{AppError, Result},
+    models::{CreateInstrumentRequest, Instrument, UpdateInstrumentRequest},
+    services::InstrumentService,
+};
+use rust_decimal::Decimal;
+use sqlx::{PgConnection, PgPool};
+use uuid::Uuid;
+
+pub struct InstrumentRepository {
+    pool: PgPool,
+}
+
+impl InstrumentRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// List the instruments visible to a user; the user's own rows replace
+    /// catalog rows with the same symbol
+    pub async fn list(&self, user_id: Uuid) -> Result<Vec<Instrument>> {
+        let instruments = sqlx::query_as::<_, Instrument>(
+            r#"
+            SELECT DISTINCT ON (symbol) * FROM instruments
+            WHERE user_id = $1 OR user_id IS NULL
+            ORDER BY symbol, user_id IS NULL
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(instruments)
+    }
+
+    /// Create a per-user instrument
+    pub async fn create(&self, user_id: Uuid, req: CreateInstrumentRequest) -> Result<Instrument> {
+        let instrument = sqlx::query_as::<_, Instrument>(
+            r#"
+            INSERT INTO instruments (
+                user_id, symbol, asset_class, multiplier, tick_size, tick_value, quote_currency, exchange
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            RETURNING *
+            "#,
+        )
+        .bind(user_id)
+        .bind(req.symbol.trim().to_uppercase())
+        .bind(&req.asset_class)
+        .bind(req.multiplier.unwrap_or(Decimal::ONE))
+        .bind(req.tick_size)
+        .bind(req.tick_value)
+        .bind(req.quote_currency.as_deref().unwrap_or("USD"))
+        .bind(&req.exchange)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(db_err) if db_err.is_unique_violation() => {
+                AppError::ValidationError("You already have an instrument with this symbol".to_string())
+            }
+            _ => AppError::DatabaseError(e),
+        })?;
+
+        Ok(instrument)
+    }
+
+    /// Update a per-user instrument; catalog rows can't be changed
+    pub async fn update(&self, instrument_id: Uuid, user_id: Uuid, req: UpdateInstrumentRequest) -> Result<Instrument> {
+        let instrument = sqlx::query_as::<_, Instrument>(
+            r#"
+            UPDATE instruments SET
+                asset_class = COALESCE($3, asset_class),
+                multiplier = COALESCE($4, multiplier),
+                tick_size = COALESCE($5, tick_size),
+                tick_value = COALESCE($6, tick_value),
+                quote_currency = COALESCE($7, quote_currency),
+                exchange = COALESCE($8, exchange),
+                updated_at = NOW()
+            WHERE id = $1 AND user_id = $2
+            RETURNING *
+            "#,
+        )
+        .bind(instrument_id)
+        .bind(user_id)
+        .bind(&req.asset_class)
+        .bind(req.multiplier)
+        .bind(req.tick_size)
+        .bind(req.tick_value)
+        .bind(&req.quote_currency)
+        .bind(&req.exchange)
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or(AppError::ValidationError("Instrument not found".to_string()))?;
+
+        Ok(instrument)
+    }
+
+    /// Delete a per-user instrument
+    pub async fn delete(&self, instrument_id: Uuid, user_id: Uuid) -> Result<()> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM instruments WHERE id = $1 AND user_id = $2
+            "#,
+        )
+        .bind(instrument_id)
+        .bind(user_id)
+        .execute(&self.pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::ValidationError("Instrument not found".to_string()));
+        }
+
+        Ok(())
+    }
+
+    /// Find the instrument describing a traded symbol
+    pub async fn resolve(&self, user_id: Uuid, symbol: &str) -> Result<Option<Instrument>> {
+        let mut conn = self.pool.acquire().await?;
+        Self::resolve_with(&mut conn, user_id, symbol).await
+    }
+
+    /// Find the instrument describing a traded symbol using an existing
+    /// connection or transaction.
+    ///
+    /// The exact symbol wins over a futures root, and the user's own row
+    /// over the shared catalog.
+    pub(crate) async fn resolve_with(
+        conn: &mut PgConnection,
+        user_id: Uuid,
+        symbol: &str,
+    ) -> Result<Option<Instrument>> {
+        let candidates = InstrumentService::symbol_candidates(symbol);
+
+        let instrument = sqlx::query_as::<_, Instrument>(
+            r#"
+            SELECT * FROM instruments
+            WHERE symbol = ANY($2) AND (user_id = $1 OR user_id IS NULL)
+            ORDER BY array_position($2, symbol::TEXT), user_id IS NULL
+            LIMIT 1
+            "#,
+        )
+        .bind(user_id)
+        .bind(&candidates)
+        .fetch_optional(conn)
+        .await?;
+
+        Ok(instrument)
+    }
+}
@@ -0,0 +1,228 @@
+use crate::{
+    error::{AppError, Result},
+    models::{CreateJournalEntryRequest, JournalEntry, JournalEntryQuery, UpdateJournalEntryRequest},
+};
+use chrono::NaiveDate;
+use sqlx::{PgConnection, PgPool};
+use uuid::Uuid;
+
+/// Entry columns plus the linked trades in the order they were entered
+const ENTRY_COLUMNS: &str = r#"
+    e.*,
+    ARRAY(
+        SELECT jt.trade_id FROM journal_entry_trades jt
+        JOIN trades t ON t.id = jt.trade_id
+        WHERE jt.entry_id = e.id
+        ORDER BY t.entry_time, t.id
+    ) AS trade_ids
+"#;
+
+pub struct JournalRepository {
+    pool: PgPool,
+}
+
+impl JournalRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Create the journal entry of a day
+    pub async fn create(&self, user_id: Uuid, req: CreateJournalEntryRequest) -> Result<JournalEntry> {
+        let mut tx = self.pool.begin().await?;
+
+        let entry_id: Uuid = sqlx::query_scalar(
+            r#"
+            INSERT INTO journal_entries (
+                user_id, entry_date, pre_market_plan, post_session_review, market_notes,
+                plan_followed, mood, energy
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            RETURNING id
+            "#,
+        )
+        .bind(user_id)
+        .bind(req.entry_date)
+        .bind(&req.pre_market_plan)
+        .bind(&req.post_session_review)
+        .bind(&req.market_notes)
+        .bind(req.plan_followed)
+        .bind(req.mood)
+        .bind(req.energy)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| Self::duplicate_date(e, req.entry_date))?;
+
+        if let Some(trade_ids) = &req.trade_ids {
+            Self::link_trades(&mut tx, entry_id, user_id, trade_ids).await?;
+        }
+
+        let entry = Self::get_with(&mut tx, entry_id, user_id)
+            .await?
+            .ok_or(AppError::ValidationError("Journal entry not found".to_string()))?;
+
+        tx.commit().await?;
+
+        Ok(entry)
+    }
+
+    /// List journal entries, newest day first
+    pub async fn list(&self, user_id: Uuid, query: &JournalEntryQuery) -> Result<Vec<JournalEntry>> {
+        let sql = format!(
+            r#"
+            SELECT {} FROM journal_entries e
+            WHERE e.user_id = $1
+                AND ($2::DATE IS NULL OR e.entry_date >= $2)
+                AND ($3::DATE IS NULL OR e.entry_date <= $3)
+            ORDER BY e.entry_date DESC
+            LIMIT $4 OFFSET $5
+            "#,
+            ENTRY_COLUMNS
+        );
+
+        let entries = sqlx::query_as::<_, JournalEntry>(&sql)
+            .bind(user_id)
+            .bind(query.from)
+            .bind(query.to)
+            .bind(query.limit.unwrap_or(100))
+            .bind(query.offset.unwrap_or(0))
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(entries)
+    }
+
+    /// Get a journal entry by ID
+    pub async fn get(&self, entry_id: Uuid, user_id: Uuid) -> Result<Option<JournalEntry>> {
+        let mut conn = self.pool.acquire().await?;
+        Self::get_with(&mut conn, entry_id, user_id).await
+    }
+
+    /// Get a journal entry by ID using an existing connection or transaction
+    pub(crate) async fn get_with(
+        conn: &mut PgConnection,
+        entry_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<Option<JournalEntry>> {
+        let sql = format!(
+            "SELECT {} FROM journal_entries e WHERE e.id = $1 AND e.user_id = $2",
+            ENTRY_COLUMNS
+        );
+
+        let entry = sqlx::query_as::<_, JournalEntry>(&sql)
+            .bind(entry_id)
+            .bind(user_id)
+            .fetch_optional(conn)
+            .await?;
+
+        Ok(entry)
+    }
+
+    /// Update a journal entry; given trade IDs replace the linked trades
+    pub async fn update(&self, entry_id: Uuid, user_id: Uuid, req: UpdateJournalEntryRequest) -> Result<JournalEntry> {
+        let mut tx = self.pool.begin().await?;
+
+        let updated: Option<Uuid> = sqlx::query_scalar(
+            r#"
+            UPDATE journal_entries SET
+                entry_date = COALESCE($3, entry_date),
+                pre_market_plan = COALESCE($4, pre_market_plan),
+                post_session_review = COALESCE($5, post_session_review),
+                market_notes = COALESCE($6, market_notes),
+                plan_followed = COALESCE($7, plan_followed),
+                mood = COALESCE($8, mood),
+                energy = COALESCE($9, energy),
+                updated_at = NOW()
+            WHERE id = $1 AND user_id = $2
+            RETURNING id
+            "#,
+        )
+        .bind(entry_id)
+        .bind(user_id)
+        .bind(req.entry_date)
+        .bind(&req.pre_market_plan)
+        .bind(&req.post_session_review)
+        .bind(&req.market_notes)
+        .bind(req.plan_followed)
+        .bind(req.mood)
+        .bind(req.energy)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| match req.entry_date {
+            Some(date) => Self::duplicate_date(e, date),
+            None => AppError::DatabaseError(e),
+        })?;
+
+        if updated.is_none() {
+            return Err(AppError::ValidationError("Journal entry not found".to_string()));
+        }
+
+        if let Some(trade_ids) = &req.trade_ids {
+            sqlx::query("DELETE FROM journal_entry_trades WHERE entry_id = $1")
+                .bind(entry_id)
+                .execute(&mut *tx)
+                .await?;
+            Self::link_trades(&mut tx, entry_id, user_id, trade_ids).await?;
+        }
+
+        let entry = Self::get_with(&mut tx, entry_id, user_id)
+            .await?
+            .ok_or(AppError::ValidationError("Journal entry not found".to_string()))?;
+
+        tx.commit().await?;
+
+        Ok(entry)
+    }
+
+    /// Delete a journal entry; its trades are kept
+    pub async fn delete(&self, entry_id: Uuid, user_id: Uuid) -> Result<()> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM journal_entries WHERE id = $1 AND user_id = $2
+            "#,
+        )
+        .bind(entry_id)
+        .bind(user_id)
+        .execute(&self.pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::ValidationError("Journal entry not found".to_string()));
+        }
+
+        Ok(())
+    }
+
+    /// Link trades to an entry, failing unless the user owns all of them
+    async fn link_trades(conn: &mut PgConnection, entry_id: Uuid, user_id: Uuid, trade_ids: &[Uuid]) -> Result<()> {
+        let mut unique = trade_ids.to_vec();
+        unique.sort();
+        unique.dedup();
+
+        let result = sqlx::query(
+            r#"
+            INSERT INTO journal_entry_trades (entry_id, trade_id)
+            SELECT $1, id FROM trades WHERE id = ANY($2) AND user_id = $3
+            "#,
+        )
+        .bind(entry_id)
+        .bind(&unique)
+        .bind(user_id)
+        .execute(conn)
+        .await?;
+
+        if result.rows_affected() != unique.len() as u64 {
+            return Err(AppError::ValidationError("Trade not found".to_string()));
+        }
+
+        Ok(())
+    }
+
+    fn duplicate_date(e: sqlx::Error, date: NaiveDate) -> AppError {
+        match e {
+            sqlx::Error::Database(db_err) if db_err.is_unique_violation() => {
+                AppError::ValidationError(format!("A journal entry for {} already exists", date))
+            }
+            _ => AppError::DatabaseError(e),
+        }
+    }
+}
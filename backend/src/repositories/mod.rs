@@ -1,6 +1,36 @@
+pub mod account_repository;
+pub mod analytics_repository;
+pub mod attachment_repository;
+pub mod execution_repository;
+pub mod fx_rate_repository;
+pub mod instrument_repository;
+pub mod journal_repository;
+pub mod option_leg_repository;
+pub mod order_repository;
+pub mod playbook_repository;
+pub mod price_bar_repository;
+pub mod quote_repository;
 pub mod trade_repository;
+pub mod trade_revision_repository;
 pub mod user_repository;
+pub mod user_settings_repository;
+pub mod vocabulary_repository;
 
+pub use account_repository::AccountRepository;
+pub use analytics_repository::AnalyticsRepository;
+pub use attachment_repository::AttachmentRepository;
+pub use execution_repository::ExecutionRepository;
+pub use fx_rate_repository::FxRateRepository;
+pub use instrument_repository::InstrumentRepository;
+pub use journal_repository::JournalRepository;
+pub use option_leg_repository::OptionLegRepository;
+pub use order_repository::OrderRepository;
+pub use playbook_repository::PlaybookRepository;
+pub use price_bar_repository::PriceBarRepository;
+pub use quote_repository::QuoteRepository;
 pub use trade_repository::TradeRepository;
+pub use trade_revision_repository::TradeRevisionRepository;
 pub use user_repository::UserRepository;
+pub use user_settings_repository::UserSettingsRepository;
+pub use vocabulary_repository::VocabularyRepository;
 
@@ -0,0 +1,155 @@
+use crate::{
+    error::{AppError, Result},
+    models::{CreateOptionLegRequest, OptionLeg},
+};
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use sqlx::{PgConnection, PgPool};
+use uuid::Uuid;
+
+pub struct OptionLegRepository {
+    pool: PgPool,
+}
+
+impl OptionLegRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// List legs of a trade in opening order
+    pub async fn list_for_trade(&self, trade_id: Uuid, user_id: Uuid) -> Result<Vec<OptionLeg>> {
+        let mut conn = self.pool.acquire().await?;
+        Self::list_for_trade_with(&mut conn, trade_id, user_id).await
+    }
+
+    /// List legs of a trade using an existing connection or transaction
+    pub(crate) async fn list_for_trade_with(
+        conn: &mut PgConnection,
+        trade_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<Vec<OptionLeg>> {
+        let legs = sqlx::query_as::<_, OptionLeg>(
+            r#"
+            SELECT * FROM option_legs
+            WHERE trade_id = $1 AND user_id = $2
+            ORDER BY opened_at ASC, created_at ASC
+            "#,
+        )
+        .bind(trade_id)
+        .bind(user_id)
+        .fetch_all(conn)
+        .await?;
+
+        Ok(legs)
+    }
+
+    /// Replace all legs of a trade
+    pub(crate) async fn replace_for_trade(
+        conn: &mut PgConnection,
+        trade_id: Uuid,
+        user_id: Uuid,
+        legs: &[CreateOptionLegRequest],
+    ) -> Result<Vec<OptionLeg>> {
+        sqlx::query(
+            r#"
+            DELETE FROM option_legs WHERE trade_id = $1 AND user_id = $2
+            "#,
+        )
+        .bind(trade_id)
+        .bind(user_id)
+        .execute(&mut *conn)
+        .await?;
+
+        let mut inserted = Vec::with_capacity(legs.len());
+        for leg in legs {
+            inserted.push(Self::insert(conn, trade_id, user_id, leg).await?);
+        }
+
+        Ok(inserted)
+    }
+
+    /// Add one normalized leg to a trade
+    pub(crate) async fn insert(
+        conn: &mut PgConnection,
+        trade_id: Uuid,
+        user_id: Uuid,
+        leg: &CreateOptionLegRequest,
+    ) -> Result<OptionLeg> {
+        let leg = sqlx::query_as::<_, OptionLeg>(
+            r#"
+            INSERT INTO option_legs (
+                trade_id, user_id, underlying, option_type, expiry, strike, multiplier,
+                side, quantity, premium, opened_at, fees, close_premium, closed_at, close_reason
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15)
+            RETURNING *
+            "#,
+        )
+        .bind(trade_id)
+        .bind(user_id)
+        .bind(&leg.underlying)
+        .bind(&leg.option_type)
+        .bind(leg.expiry)
+        .bind(leg.strike)
+        .bind(leg.multiplier.unwrap_or(Decimal::ONE))
+        .bind(&leg.side)
+        .bind(leg.quantity)
+        .bind(leg.premium)
+        .bind(leg.opened_at)
+        .bind(leg.fees.unwrap_or(Decimal::ZERO))
+        .bind(leg.close_premium)
+        .bind(leg.closed_at)
+        .bind(&leg.close_reason)
+        .fetch_one(conn)
+        .await?;
+
+        Ok(leg)
+    }
+
+    /// Close an open leg
+    pub(crate) async fn close(
+        conn: &mut PgConnection,
+        leg_id: Uuid,
+        premium: Decimal,
+        closed_at: DateTime<Utc>,
+        reason: &str,
+    ) -> Result<OptionLeg> {
+        let leg = sqlx::query_as::<_, OptionLeg>(
+            r#"
+            UPDATE option_legs
+            SET close_premium = $2, closed_at = $3, close_reason = $4
+            WHERE id = $1 AND closed_at IS NULL
+            RETURNING *
+            "#,
+        )
+        .bind(leg_id)
+        .bind(premium)
+        .bind(closed_at)
+        .bind(reason)
+        .fetch_optional(conn)
+        .await?
+        .ok_or(AppError::ValidationError("Leg is already closed".to_string()))?;
+
+        Ok(leg)
+    }
+
+    /// Trades holding open option legs that expire before `as_of`
+    pub(crate) async fn trades_with_expired_legs(
+        conn: &mut PgConnection,
+        user_id: Uuid,
+        as_of: NaiveDate,
+    ) -> Result<Vec<Uuid>> {
+        let trade_ids = sqlx::query_scalar(
+            r#"
+            SELECT DISTINCT trade_id FROM option_legs
+            WHERE user_id = $1 AND closed_at IS NULL AND expiry < $2
+            "#,
+        )
+        .bind(user_id)
+        .bind(as_of)
+        .fetch_all(conn)
+        .await?;
+
+        Ok(trade_ids)
+    }
+}
@@ -0,0 +1,302 @@
+use crate::{
+    error::{AppError, Result},
+    models::{
+        CancelOrderRequest, CreateOrderRequest, CreateTradeRequest, FillOrderRequest, Order, OrderQuery,
+        OrderWithTrade, Trade,
+    },
+    repositories::TradeRepository,
+};
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use sqlx::{PgConnection, PgPool};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+pub struct OrderRepository {
+    pool: PgPool,
+}
+
+impl OrderRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Place an order, creating its trade as `pending` at the planned price
+    pub async fn create(&self, user_id: Uuid, req: CreateOrderRequest) -> Result<OrderWithTrade> {
+        let placed_at = req.placed_at.unwrap_or_else(Utc::now);
+        let entry_price = req
+            .limit_price
+            .or(req.stop_price)
+            .or(req.entry_price)
+            .ok_or(AppError::ValidationError("Market orders need an entry price".to_string()))?;
+
+        let mut tx = self.pool.begin().await?;
+
+        let trade_req = CreateTradeRequest {
+            symbol: req.symbol,
+            direction: req.direction,
+            entry_price,
+            quantity: req.quantity,
+            entry_time: placed_at,
+            currency: req.currency,
+            stop_loss: req.stop_loss,
+            take_profit: req.take_profit,
+            planned_risk: req.planned_risk,
+            notes: req.notes,
+            tags: req.tags,
+            setup_type: req.setup_type,
+            playbook_id: req.playbook_id,
+            checklist_met: req.checklist_met,
+            broker: req.broker,
+            account_id: req.account_id,
+            ..Default::default()
+        };
+        let trade = TradeRepository::create_with(&mut tx, user_id, trade_req, "pending").await?;
+
+        let order = sqlx::query_as::<_, Order>(
+            r#"
+            INSERT INTO orders (trade_id, user_id, order_type, limit_price, stop_price, placed_at, expires_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING *
+            "#,
+        )
+        .bind(trade.id)
+        .bind(user_id)
+        .bind(&req.order_type)
+        .bind(req.limit_price)
+        .bind(req.stop_price)
+        .bind(placed_at)
+        .bind(req.expires_at)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(OrderWithTrade { order, trade })
+    }
+
+    /// Get an order with its trade
+    pub async fn get(&self, order_id: Uuid, user_id: Uuid) -> Result<Option<OrderWithTrade>> {
+        let order = sqlx::query_as::<_, Order>(
+            r#"
+            SELECT * FROM orders WHERE id = $1 AND user_id = $2
+            "#,
+        )
+        .bind(order_id)
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        let Some(order) = order else {
+            return Ok(None);
+        };
+
+        let mut conn = self.pool.acquire().await?;
+        let mut orders = Self::with_trades(&mut conn, vec![order]).await?;
+
+        Ok(orders.pop())
+    }
+
+    /// List orders, most recently placed first
+    pub async fn list(&self, user_id: Uuid, query: &OrderQuery) -> Result<Vec<OrderWithTrade>> {
+        let orders = sqlx::query_as::<_, Order>(
+            r#"
+            SELECT o.* FROM orders o
+            JOIN trades t ON t.id = o.trade_id
+            WHERE o.user_id = $1
+                AND ($2::TEXT IS NULL OR o.status = $2)
+                AND ($3::TEXT IS NULL OR t.symbol = $3)
+            ORDER BY o.placed_at DESC, o.id
+            "#,
+        )
+        .bind(user_id)
+        .bind(&query.status)
+        .bind(&query.symbol)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut conn = self.pool.acquire().await?;
+        Self::with_trades(&mut conn, orders).await
+    }
+
+    /// Fill a pending order and open its trade at the fill.
+    ///
+    /// The fill must fall between placement and expiry, and limit orders
+    /// can't fill beyond their limit.
+    pub async fn fill(&self, order_id: Uuid, user_id: Uuid, req: FillOrderRequest) -> Result<OrderWithTrade> {
+        let mut tx = self.pool.begin().await?;
+        let order = Self::lock_pending(&mut tx, order_id, user_id).await?;
+        let trade = sqlx::query_as::<_, Trade>(
+            r#"
+            SELECT * FROM trades WHERE id = $1
+            "#,
+        )
+        .bind(order.trade_id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        let filled_at = req.filled_at.unwrap_or_else(Utc::now);
+        Self::validate_time(&order, filled_at)?;
+        if order.expires_at.is_some_and(|expires_at| filled_at > expires_at) {
+            return Err(AppError::ValidationError(
+                "The order had expired by the fill time".to_string(),
+            ));
+        }
+
+        let price = req.price.unwrap_or(trade.entry_price);
+        if price <= Decimal::ZERO {
+            return Err(AppError::ValidationError("Fill price must be positive".to_string()));
+        }
+        if let Some(limit) = order.limit_price {
+            let beyond_limit = match trade.direction.as_str() {
+                "short" => price < limit,
+                _ => price > limit,
+            };
+            if beyond_limit {
+                return Err(AppError::ValidationError(format!(
+                    "A {} limit order can't fill beyond its limit of {}",
+                    if trade.direction == "short" { "sell" } else { "buy" },
+                    limit
+                )));
+            }
+        }
+
+        let quantity = req.quantity.unwrap_or(trade.quantity);
+        if quantity <= Decimal::ZERO || quantity > trade.quantity {
+            return Err(AppError::ValidationError(
+                "Filled quantity must be positive and at most the ordered quantity".to_string(),
+            ));
+        }
+
+        let order = sqlx::query_as::<_, Order>(
+            r#"
+            UPDATE orders SET status = 'filled', filled_at = $2, fill_price = $3, updated_at = NOW()
+            WHERE id = $1
+            RETURNING *
+            "#,
+        )
+        .bind(order_id)
+        .bind(filled_at)
+        .bind(price)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        let trade = TradeRepository::fill_with(&mut tx, order.trade_id, user_id, price, filled_at, quantity, req.fees)
+            .await?;
+
+        tx.commit().await?;
+
+        Ok(OrderWithTrade { order, trade })
+    }
+
+    /// Cancel a pending order; its trade is kept as `cancelled`
+    pub async fn cancel(&self, order_id: Uuid, user_id: Uuid, req: CancelOrderRequest) -> Result<OrderWithTrade> {
+        let mut tx = self.pool.begin().await?;
+        let order = Self::lock_pending(&mut tx, order_id, user_id).await?;
+
+        let cancelled_at = req.cancelled_at.unwrap_or_else(Utc::now);
+        Self::validate_time(&order, cancelled_at)?;
+
+        let order = sqlx::query_as::<_, Order>(
+            r#"
+            UPDATE orders SET status = 'cancelled', cancelled_at = $2, cancel_reason = $3, updated_at = NOW()
+            WHERE id = $1
+            RETURNING *
+            "#,
+        )
+        .bind(order_id)
+        .bind(cancelled_at)
+        .bind(&req.reason)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        let trade = TradeRepository::set_unfilled_with(&mut tx, order.trade_id, user_id, "cancelled").await?;
+
+        tx.commit().await?;
+
+        Ok(OrderWithTrade { order, trade })
+    }
+
+    /// Expire every pending order whose expiry is at or before `as_of`;
+    /// their trades are kept as `expired`
+    pub async fn expire(&self, user_id: Uuid, as_of: DateTime<Utc>) -> Result<Vec<OrderWithTrade>> {
+        let mut tx = self.pool.begin().await?;
+
+        let orders = sqlx::query_as::<_, Order>(
+            r#"
+            UPDATE orders SET status = 'expired', updated_at = NOW()
+            WHERE user_id = $1 AND status = 'pending' AND expires_at <= $2
+            RETURNING *
+            "#,
+        )
+        .bind(user_id)
+        .bind(as_of)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        let mut expired = Vec::with_capacity(orders.len());
+        for order in orders {
+            let trade = TradeRepository::set_unfilled_with(&mut tx, order.trade_id, user_id, "expired").await?;
+            expired.push(OrderWithTrade { order, trade });
+        }
+
+        tx.commit().await?;
+
+        Ok(expired)
+    }
+
+    async fn lock_pending(conn: &mut PgConnection, order_id: Uuid, user_id: Uuid) -> Result<Order> {
+        let order = sqlx::query_as::<_, Order>(
+            r#"
+            SELECT * FROM orders WHERE id = $1 AND user_id = $2 FOR UPDATE
+            "#,
+        )
+        .bind(order_id)
+        .bind(user_id)
+        .fetch_optional(conn)
+        .await?
+        .ok_or(AppError::ValidationError("Order not found".to_string()))?;
+
+        if order.status != "pending" {
+            return Err(AppError::ValidationError(format!("Order is already {}", order.status)));
+        }
+
+        Ok(order)
+    }
+
+    /// Transitions can't predate the order
+    fn validate_time(order: &Order, time: DateTime<Utc>) -> Result<()> {
+        if time < order.placed_at {
+            return Err(AppError::ValidationError(
+                "Time must not be before the order was placed".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Attach their trades to orders, keeping the order
+    async fn with_trades(conn: &mut PgConnection, orders: Vec<Order>) -> Result<Vec<OrderWithTrade>> {
+        let trade_ids: Vec<Uuid> = orders.iter().map(|order| order.trade_id).collect();
+        let trades = sqlx::query_as::<_, Trade>(
+            r#"
+            SELECT * FROM trades WHERE id = ANY($1)
+            "#,
+        )
+        .bind(&trade_ids)
+        .fetch_all(conn)
+        .await?;
+
+        let mut trades: HashMap<Uuid, Trade> = trades.into_iter().map(|trade| (trade.id, trade)).collect();
+
+        orders
+            .into_iter()
+            .map(|order| {
+                let trade = trades
+                    .remove(&order.trade_id)
+                    .ok_or(AppError::ValidationError("Trade not found".to_string()))?;
+                Ok(OrderWithTrade { order, trade })
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,224 @@
+use crate::{
+    error::{AppError, Result},
+    models::{CreatePlaybookRequest, Playbook, Trade, UpdatePlaybookRequest},
+    repositories::TradeRevisionRepository,
+};
+use sqlx::{PgConnection, PgPool};
+use uuid::Uuid;
+
+pub struct PlaybookRepository {
+    pool: PgPool,
+}
+
+impl PlaybookRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// List a user's playbooks by name
+    pub async fn list(&self, user_id: Uuid) -> Result<Vec<Playbook>> {
+        let playbooks = sqlx::query_as::<_, Playbook>(
+            r#"
+            SELECT * FROM playbooks WHERE user_id = $1 ORDER BY LOWER(name)
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(playbooks)
+    }
+
+    /// Create a playbook
+    pub async fn create(&self, user_id: Uuid, req: CreatePlaybookRequest) -> Result<Playbook> {
+        let playbook = sqlx::query_as::<_, Playbook>(
+            r#"
+            INSERT INTO playbooks (user_id, name, description, entry_criteria, exit_criteria, checklist)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING *
+            "#,
+        )
+        .bind(user_id)
+        .bind(&req.name)
+        .bind(&req.description)
+        .bind(&req.entry_criteria)
+        .bind(&req.exit_criteria)
+        .bind(req.checklist.unwrap_or_default())
+        .fetch_one(&self.pool)
+        .await
+        .map_err(duplicate_name)?;
+
+        Ok(playbook)
+    }
+
+    /// Get a playbook by ID
+    pub async fn get(&self, playbook_id: Uuid, user_id: Uuid) -> Result<Option<Playbook>> {
+        let playbook = sqlx::query_as::<_, Playbook>(
+            r#"
+            SELECT * FROM playbooks WHERE id = $1 AND user_id = $2
+            "#,
+        )
+        .bind(playbook_id)
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(playbook)
+    }
+
+    /// Update a playbook.
+    ///
+    /// A new name is copied to the `setup_type` of its trades, and a new
+    /// checklist drops items no longer on it from their `checklist_met`.
+    pub async fn update(&self, playbook_id: Uuid, user_id: Uuid, req: UpdatePlaybookRequest) -> Result<Playbook> {
+        let mut tx = self.pool.begin().await?;
+
+        let playbook = sqlx::query_as::<_, Playbook>(
+            r#"
+            UPDATE playbooks SET
+                name = COALESCE($3, name),
+                description = COALESCE($4, description),
+                entry_criteria = COALESCE($5, entry_criteria),
+                exit_criteria = COALESCE($6, exit_criteria),
+                checklist = COALESCE($7, checklist),
+                updated_at = NOW()
+            WHERE id = $1 AND user_id = $2
+            RETURNING *
+            "#,
+        )
+        .bind(playbook_id)
+        .bind(user_id)
+        .bind(&req.name)
+        .bind(&req.description)
+        .bind(&req.entry_criteria)
+        .bind(&req.exit_criteria)
+        .bind(&req.checklist)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(duplicate_name)?
+        .ok_or(AppError::ValidationError("Playbook not found".to_string()))?;
+
+        let before = Self::lock_trades(&mut tx, playbook.id, user_id).await?;
+        let ids: Vec<Uuid> = before.iter().map(|trade| trade.id).collect();
+
+        let after = sqlx::query_as::<_, Trade>(
+            r#"
+            UPDATE trades SET
+                setup_type = $2,
+                checklist_met = ARRAY(SELECT item FROM UNNEST(checklist_met) AS item WHERE item = ANY($3))
+            WHERE id = ANY($1)
+            RETURNING *
+            "#,
+        )
+        .bind(&ids)
+        .bind(&playbook.name)
+        .bind(&playbook.checklist)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        TradeRevisionRepository::record_updates_with(&mut tx, &before, &after).await?;
+
+        tx.commit().await?;
+
+        Ok(playbook)
+    }
+
+    /// Delete a playbook; its trades keep their setup type but lose the
+    /// link and checklist
+    pub async fn delete(&self, playbook_id: Uuid, user_id: Uuid) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+
+        let before = Self::lock_trades(&mut tx, playbook_id, user_id).await?;
+        let ids: Vec<Uuid> = before.iter().map(|trade| trade.id).collect();
+
+        let after = sqlx::query_as::<_, Trade>(
+            r#"
+            UPDATE trades SET playbook_id = NULL, checklist_met = '{}'
+            WHERE id = ANY($1)
+            RETURNING *
+            "#,
+        )
+        .bind(&ids)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        TradeRevisionRepository::record_updates_with(&mut tx, &before, &after).await?;
+
+        let result = sqlx::query(
+            r#"
+            DELETE FROM playbooks WHERE id = $1 AND user_id = $2
+            "#,
+        )
+        .bind(playbook_id)
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::ValidationError("Playbook not found".to_string()));
+        }
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// Lock the user's trades taken from a playbook
+    async fn lock_trades(conn: &mut PgConnection, playbook_id: Uuid, user_id: Uuid) -> Result<Vec<Trade>> {
+        let trades = sqlx::query_as::<_, Trade>(
+            r#"
+            SELECT * FROM trades WHERE playbook_id = $1 AND user_id = $2 FOR UPDATE
+            "#,
+        )
+        .bind(playbook_id)
+        .bind(user_id)
+        .fetch_all(conn)
+        .await?;
+
+        Ok(trades)
+    }
+
+    /// Fetch a playbook, failing unless the user owns it
+    pub(crate) async fn ensure_owned(conn: &mut PgConnection, playbook_id: Uuid, user_id: Uuid) -> Result<Playbook> {
+        let playbook = sqlx::query_as::<_, Playbook>(
+            r#"
+            SELECT * FROM playbooks WHERE id = $1 AND user_id = $2
+            "#,
+        )
+        .bind(playbook_id)
+        .bind(user_id)
+        .fetch_optional(conn)
+        .await?
+        .ok_or(AppError::ValidationError("Playbook not found".to_string()))?;
+
+        Ok(playbook)
+    }
+
+    /// Find a user's playbook by name, ignoring case and surrounding spaces
+    pub(crate) async fn find_by_name_with(
+        conn: &mut PgConnection,
+        user_id: Uuid,
+        name: &str,
+    ) -> Result<Option<Playbook>> {
+        let playbook = sqlx::query_as::<_, Playbook>(
+            r#"
+            SELECT * FROM playbooks WHERE user_id = $1 AND LOWER(name) = LOWER(TRIM($2))
+            "#,
+        )
+        .bind(user_id)
+        .bind(name)
+        .fetch_optional(conn)
+        .await?;
+
+        Ok(playbook)
+    }
+}
+
+fn duplicate_name(e: sqlx::Error) -> AppError {
+    match e {
+        sqlx::Error::Database(db_err) if db_err.is_unique_violation() => {
+            AppError::ValidationError("You already have a playbook with this name".to_string())
+        }
+        _ => AppError::DatabaseError(e),
+    }
+}
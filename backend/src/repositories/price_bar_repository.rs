@@ -0,0 +1,230 @@
+use crate::{
+    error::Result,
+    models::{CreatePriceBarRequest, PriceBar, PriceBarQuery, ReplayBar},
+};
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use sqlx::{PgConnection, PgPool};
+use uuid::Uuid;
+
+/// Bars written per INSERT statement
+const UPSERT_CHUNK: usize = 1000;
+
+pub struct PriceBarRepository {
+    pool: PgPool,
+}
+
+impl PriceBarRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Store bars of one symbol and timeframe, replacing bars that start at
+    /// the same time
+    pub async fn upsert_many(
+        &self,
+        user_id: Uuid,
+        symbol: &str,
+        timeframe: &str,
+        bars: &[CreatePriceBarRequest],
+    ) -> Result<usize> {
+        let mut tx = self.pool.begin().await?;
+        let mut written = 0;
+
+        for chunk in bars.chunks(UPSERT_CHUNK) {
+            let result = sqlx::query(
+                r#"
+                INSERT INTO price_bars (user_id, symbol, timeframe, bar_time, open, high, low, close, volume)
+                SELECT $1, $2, $3, *
+                FROM UNNEST($4::TIMESTAMPTZ[], $5::DECIMAL[], $6::DECIMAL[], $7::DECIMAL[], $8::DECIMAL[], $9::DECIMAL[])
+                ON CONFLICT (user_id, symbol, timeframe, bar_time)
+                DO UPDATE SET
+                    open = EXCLUDED.open, high = EXCLUDED.high, low = EXCLUDED.low,
+                    close = EXCLUDED.close, volume = EXCLUDED.volume
+                "#,
+            )
+            .bind(user_id)
+            .bind(symbol)
+            .bind(timeframe)
+            .bind(chunk.iter().map(|b| b.bar_time).collect::<Vec<_>>())
+            .bind(chunk.iter().map(|b| b.open).collect::<Vec<_>>())
+            .bind(chunk.iter().map(|b| b.high).collect::<Vec<_>>())
+            .bind(chunk.iter().map(|b| b.low).collect::<Vec<_>>())
+            .bind(chunk.iter().map(|b| b.close).collect::<Vec<_>>())
+            .bind(chunk.iter().map(|b| b.volume).collect::<Vec<_>>())
+            .execute(&mut *tx)
+            .await?;
+
+            written += result.rows_affected() as usize;
+        }
+
+        tx.commit().await?;
+
+        Ok(written)
+    }
+
+    /// List bars of a symbol in time order
+    pub async fn list(&self, user_id: Uuid, query: &PriceBarQuery) -> Result<Vec<PriceBar>> {
+        let bars = sqlx::query_as::<_, PriceBar>(
+            r#"
+            SELECT * FROM price_bars
+            WHERE user_id = $1
+                AND symbol = $2
+                AND ($3::TEXT IS NULL OR timeframe = $3)
+                AND ($4::TIMESTAMPTZ IS NULL OR bar_time >= $4)
+                AND ($5::TIMESTAMPTZ IS NULL OR bar_time <= $5)
+            ORDER BY bar_time, timeframe::INTERVAL
+            LIMIT $6
+            "#,
+        )
+        .bind(user_id)
+        .bind(&query.symbol)
+        .bind(&query.timeframe)
+        .bind(query.from)
+        .bind(query.to)
+        .bind(query.limit.unwrap_or(1000))
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(bars)
+    }
+
+    /// Delete the bars of a symbol matching the filters
+    pub async fn delete(&self, user_id: Uuid, query: &PriceBarQuery) -> Result<()> {
+        sqlx::query(
+            r#"
+            DELETE FROM price_bars
+            WHERE user_id = $1
+                AND symbol = $2
+                AND ($3::TEXT IS NULL OR timeframe = $3)
+                AND ($4::TIMESTAMPTZ IS NULL OR bar_time >= $4)
+                AND ($5::TIMESTAMPTZ IS NULL OR bar_time <= $5)
+            "#,
+        )
+        .bind(user_id)
+        .bind(&query.symbol)
+        .bind(&query.timeframe)
+        .bind(query.from)
+        .bind(query.to)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Timeframe to read a symbol's bars in between two times: the finest
+    /// one spanning the whole window, else the finest one overlapping it
+    pub async fn timeframe_for(
+        &self,
+        user_id: Uuid,
+        symbol: &str,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Option<String>> {
+        let mut conn = self.pool.acquire().await?;
+        Self::timeframe_with(&mut conn, user_id, symbol, from, to).await
+    }
+
+    /// `timeframe_for` using an existing connection or transaction
+    pub(crate) async fn timeframe_with(
+        conn: &mut PgConnection,
+        user_id: Uuid,
+        symbol: &str,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Option<String>> {
+        let timeframe: Option<String> = sqlx::query_scalar(
+            r#"
+            SELECT timeframe
+            FROM price_bars
+            WHERE user_id = $1
+                AND symbol = $2
+                AND bar_time <= $4
+                AND bar_time > $3 - INTERVAL '1 day'
+                AND bar_time + timeframe::INTERVAL > $3
+            GROUP BY timeframe
+            ORDER BY MIN(bar_time) <= $3 AND MAX(bar_time + timeframe::INTERVAL) >= $4 DESC, timeframe::INTERVAL
+            LIMIT 1
+            "#,
+        )
+        .bind(user_id)
+        .bind(symbol)
+        .bind(from)
+        .bind(to)
+        .fetch_optional(conn)
+        .await?;
+
+        Ok(timeframe)
+    }
+
+    /// Bars of one timeframe overlapping a window, at most `limit`
+    pub async fn bars(
+        &self,
+        user_id: Uuid,
+        symbol: &str,
+        timeframe: &str,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<ReplayBar>> {
+        let bars = sqlx::query_as::<_, ReplayBar>(
+            r#"
+            SELECT bar_time AS time, open, high, low, close, volume
+            FROM price_bars
+            WHERE user_id = $1
+                AND symbol = $2
+                AND timeframe = $3
+                AND bar_time + timeframe::INTERVAL > $4
+                AND bar_time <= $5
+            ORDER BY bar_time
+            LIMIT $6
+            "#,
+        )
+        .bind(user_id)
+        .bind(symbol)
+        .bind(timeframe)
+        .bind(from)
+        .bind(to)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(bars)
+    }
+
+    /// Highest high and lowest low of a symbol between two times, from the
+    /// bars of the timeframe `timeframe_with` picks. Bars at the edges may
+    /// reach slightly outside the window.
+    pub(crate) async fn range_with(
+        conn: &mut PgConnection,
+        user_id: Uuid,
+        symbol: &str,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Option<(Decimal, Decimal)>> {
+        let Some(timeframe) = Self::timeframe_with(conn, user_id, symbol, from, to).await? else {
+            return Ok(None);
+        };
+
+        let range: (Decimal, Decimal) = sqlx::query_as(
+            r#"
+            SELECT MAX(high), MIN(low)
+            FROM price_bars
+            WHERE user_id = $1
+                AND symbol = $2
+                AND timeframe = $3
+                AND bar_time <= $5
+                AND bar_time + timeframe::INTERVAL > $4
+            "#,
+        )
+        .bind(user_id)
+        .bind(symbol)
+        .bind(&timeframe)
+        .bind(from)
+        .bind(to)
+        .fetch_one(conn)
+        .await?;
+
+        Ok(Some(range))
+    }
+}
@@ -0,0 +1,90 @@
+use crate::{
+    error::{AppError, Result},
+    models::Quote,
+};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+pub struct QuoteRepository {
+    pool: PgPool,
+}
+
+impl QuoteRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Store the price of a symbol unless a newer one is already stored.
+    /// Returns whether the quote was stored.
+    pub async fn upsert(&self, user_id: Uuid, quote: &Quote, source: &str) -> Result<bool> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO quotes (user_id, symbol, price, as_of, source)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (user_id, symbol) DO UPDATE SET
+                price = EXCLUDED.price,
+                as_of = EXCLUDED.as_of,
+                source = EXCLUDED.source,
+                updated_at = NOW()
+            WHERE quotes.as_of <= EXCLUDED.as_of
+            "#,
+        )
+        .bind(user_id)
+        .bind(&quote.symbol)
+        .bind(quote.price)
+        .bind(quote.as_of)
+        .bind(source)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// List stored quotes by symbol
+    pub async fn list(&self, user_id: Uuid) -> Result<Vec<Quote>> {
+        let quotes = sqlx::query_as::<_, Quote>(
+            r#"
+            SELECT symbol, price, as_of FROM quotes WHERE user_id = $1 ORDER BY symbol
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(quotes)
+    }
+
+    /// Stored quotes of the given symbols
+    pub async fn latest(&self, user_id: Uuid, symbols: &[String]) -> Result<Vec<Quote>> {
+        let quotes = sqlx::query_as::<_, Quote>(
+            r#"
+            SELECT symbol, price, as_of FROM quotes WHERE user_id = $1 AND symbol = ANY($2)
+            "#,
+        )
+        .bind(user_id)
+        .bind(symbols)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(quotes)
+    }
+
+    /// Delete the quote of a symbol
+    pub async fn delete(&self, user_id: Uuid, symbol: &str) -> Result<()> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM quotes WHERE user_id = $1 AND symbol = $2
+            "#,
+        )
+        .bind(user_id)
+        .bind(symbol)
+        .execute(&self.pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::ValidationError("Quote not found".to_string()));
+        }
+
+        Ok(())
+    }
+}
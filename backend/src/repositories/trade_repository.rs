@@ -1,11 +1,30 @@
 use crate::{
     error::{AppError, Result},
-    models::{CreateTradeRequest, Trade, TradeFilters, UpdateTradeRequest},
+    models::{
+        expiration_time, CloseOptionLegRequest, CreateExecutionRequest, CreateOptionLegRequest, CreateTradeRequest,
+        Excursion, ExecutionSummary, LegSummary, Playbook, Trade, TradeFilters, UpdateTradeRequest, VocabularyKind,
+    },
+    repositories::{
+        AccountRepository, ExecutionRepository, FxRateRepository, InstrumentRepository, OptionLegRepository,
+        PlaybookRepository, PriceBarRepository, TradeRevisionRepository, VocabularyRepository,
+    },
+    services::{InstrumentService, RevisionService},
 };
+use chrono::{DateTime, NaiveDate, Utc};
+use futures_util::StreamExt;
 use rust_decimal::Decimal;
-use sqlx::PgPool;
+use sqlx::{
+    postgres::PgArguments,
+    query::QueryAs,
+    PgConnection, PgPool, Postgres,
+};
+use std::collections::HashSet;
+use tokio::sync::mpsc;
 use uuid::Uuid;
 
+/// Rows buffered between the database and a streaming consumer
+const STREAM_BUFFER: usize = 256;
+
 pub struct TradeRepository {
     pool: PgPool,
 }
@@ -17,34 +36,71 @@ impl TradeRepository {
 
     /// Create a new trade
     pub async fn create(&self, user_id: Uuid, req: CreateTradeRequest) -> Result<Trade> {
-        // Calculate P&L if exit price is provided
-        let (pnl, pnl_percentage) = if let Some(exit_price) = req.exit_price {
-            let price_diff = if req.direction == "long" {
-                exit_price - req.entry_price
-            } else {
-                req.entry_price - exit_price
-            };
-            
-            let fees = req.fees.unwrap_or(Decimal::ZERO);
-            let pnl = price_diff * req.quantity - fees;
-            let pnl_pct = (price_diff / req.entry_price) * Decimal::from(100);
-            
-            (Some(pnl), Some(pnl_pct))
-        } else {
-            (None, None)
+        let mut tx = self.pool.begin().await?;
+        let trade = Self::create_with(&mut tx, user_id, req, "open").await?;
+
+        tx.commit().await?;
+
+        Ok(trade)
+    }
+
+    /// Create a trade with the given initial status on an existing
+    /// connection; the status still follows fills, legs and exit
+    pub(crate) async fn create_with(
+        conn: &mut PgConnection,
+        user_id: Uuid,
+        mut req: CreateTradeRequest,
+        status: &str,
+    ) -> Result<Trade> {
+        let executions = req.executions.take().filter(|e| !e.is_empty());
+        let legs = Self::normalize_legs(req.legs.take(), req.entry_time)?;
+        if executions.is_some() && legs.is_some() {
+            return Err(both_fills_and_legs());
+        }
+
+        let account = match req.account_id {
+            Some(account_id) => Some(AccountRepository::ensure_owned(conn, account_id, user_id).await?),
+            None => None,
         };
+        let (multiplier, quote_currency) = Self::instrument_for(conn, user_id, &req.symbol).await?;
+        let currency = req
+            .currency
+            .or(quote_currency)
+            .or(account.map(|a| a.base_currency))
+            .unwrap_or_else(|| "USD".to_string());
+        let fee_currency = req.fee_currency.unwrap_or_else(|| currency.clone());
 
-        let status = if req.exit_price.is_some() { "closed" } else { "open" };
+        let playbook = Self::playbook_for(conn, user_id, req.playbook_id, req.setup_type.as_deref()).await?;
+        let checklist_met = req.checklist_met.unwrap_or_default();
+        Self::validate_checklist_met(playbook.as_ref(), &checklist_met)?;
+        let setup_type = playbook.as_ref().map(|p| p.name.clone()).or(req.setup_type);
 
-        let trade = sqlx::query_as::<_, Trade>(
+        let (tags, mistakes, emotions) = (
+            req.tags.unwrap_or_default(),
+            req.mistakes.unwrap_or_default(),
+            req.emotions.unwrap_or_default(),
+        );
+        let tags = VocabularyRepository::normalize_with(conn, user_id, VocabularyKind::Tags, tags).await?;
+        let mistakes = VocabularyRepository::normalize_with(conn, user_id, VocabularyKind::Mistakes, mistakes).await?;
+        let emotions = VocabularyRepository::normalize_with(conn, user_id, VocabularyKind::Emotions, emotions).await?;
+
+        let mut trade = sqlx::query_as::<_, Trade>(
             r#"
             INSERT INTO trades (
-                user_id, symbol, direction, entry_price, exit_price, quantity,
+                user_id, symbol, direction, entry_price, exit_price, quantity, open_quantity, multiplier,
                 entry_time, exit_time, pnl, pnl_percentage, fees,
+                stop_loss, initial_stop, take_profit, planned_risk,
                 notes, tags, setup_type, mistakes, emotions, screenshots,
-                broker, account_id, status
+                broker, account_id, external_id, currency, fee_currency, strategy, underlying,
+                playbook_id, checklist_met, status
+            )
+            VALUES (
+                $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, NULL, NULL, $11,
+                $12, $13, $14, $15,
+                $16, $17, $18, $19, $20, $21,
+                $22, $23, $24, $25, $26, $27, $28,
+                $29, $30, $31
             )
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20)
             RETURNING *
             "#,
         )
@@ -54,23 +110,44 @@ impl TradeRepository {
         .bind(req.entry_price)
         .bind(req.exit_price)
         .bind(req.quantity)
+        .bind(req.quantity)
+        .bind(multiplier)
         .bind(req.entry_time)
         .bind(req.exit_time)
-        .bind(pnl)
-        .bind(pnl_percentage)
         .bind(req.fees.unwrap_or(Decimal::ZERO))
+        .bind(req.stop_loss)
+        .bind(req.initial_stop.or(req.stop_loss))
+        .bind(req.take_profit.unwrap_or_default())
+        .bind(req.planned_risk)
         .bind(req.notes)
-        .bind(req.tags.unwrap_or_default())
-        .bind(req.setup_type)
-        .bind(req.mistakes.unwrap_or_default())
-        .bind(req.emotions.unwrap_or_default())
-        .bind(Vec::<String>::new()) // screenshots - empty for now
+        .bind(tags)
+        .bind(setup_type)
+        .bind(mistakes)
+        .bind(emotions)
+        .bind(req.screenshots.unwrap_or_default())
         .bind(req.broker)
         .bind(req.account_id)
+        .bind(req.external_id)
+        .bind(currency)
+        .bind(fee_currency)
+        .bind(req.strategy)
+        .bind(InstrumentService::option_underlying(&req.symbol))
+        .bind(playbook.map(|p| p.id))
+        .bind(checklist_met)
         .bind(status)
-        .fetch_one(&self.pool)
+        .fetch_one(&mut *conn)
         .await?;
 
+        if let Some(executions) = &executions {
+            ExecutionRepository::replace_for_trade(conn, trade.id, user_id, executions).await?;
+        }
+        if let Some(legs) = &legs {
+            OptionLegRepository::replace_for_trade(conn, trade.id, user_id, legs).await?;
+        }
+
+        let trade = Self::recalculate(conn, &mut trade).await?;
+        TradeRevisionRepository::record_with(conn, user_id, "create", None, Some(&trade), None).await?;
+
         Ok(trade)
     }
 
@@ -89,15 +166,76 @@ impl TradeRepository {
         Ok(trade)
     }
 
+    /// Find which (broker, external ID) pairs are already stored.
+    ///
+    /// Trades without an external ID match on their own ID, so re-importing
+    /// a journal export doesn't duplicate trades that were entered by hand.
+    pub async fn find_external_ids(
+        &self,
+        user_id: Uuid,
+        external_ids: &[String],
+    ) -> Result<HashSet<(Option<String>, String)>> {
+        let existing: Vec<(Option<String>, String)> = sqlx::query_as(
+            r#"
+            SELECT broker, COALESCE(external_id, id::text) FROM trades
+            WHERE user_id = $1 AND (external_id = ANY($2) OR id::text = ANY($2))
+            "#,
+        )
+        .bind(user_id)
+        .bind(external_ids)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(existing.into_iter().collect())
+    }
+
     /// List trades with filters
     pub async fn list(&self, user_id: Uuid, filters: TradeFilters) -> Result<Vec<Trade>> {
-        let mut query = String::from("SELECT * FROM trades WHERE user_id = $1");
+        let query = Self::list_query(&filters);
+        let trades = Self::bind_list(sqlx::query_as::<_, Trade>(&query), user_id, &filters)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(trades)
+    }
+
+    /// Stream trades matching the filters without collecting them.
+    ///
+    /// Rows are fetched by a background task and handed over through a
+    /// bounded channel, so memory use stays flat for large result sets.
+    pub fn stream(&self, user_id: Uuid, filters: TradeFilters) -> mpsc::Receiver<Result<Trade>> {
+        let (tx, rx) = mpsc::channel(STREAM_BUFFER);
+        let pool = self.pool.clone();
+
+        tokio::spawn(async move {
+            let query = Self::list_query(&filters);
+            let mut rows = Self::bind_list(sqlx::query_as::<_, Trade>(&query), user_id, &filters).fetch(&pool);
+
+            while let Some(row) = rows.next().await {
+                let failed = row.is_err();
+                if tx.send(row.map_err(AppError::from)).await.is_err() || failed {
+                    break;
+                }
+            }
+        });
+
+        rx
+    }
+
+    /// Build the WHERE conditions for a filter set.
+    ///
+    /// `$1` is the user ID; the remaining parameters are numbered in the
+    /// order `bind_filters` binds them. A symbol also matches option trades
+    /// on that underlying. Returns the clause and the number of
+    /// parameters used. `limit` and `offset` are not part of the clause.
+    pub(crate) fn filter_conditions(filters: &TradeFilters) -> (String, usize) {
+        let mut query = String::from("user_id = $1");
         let mut param_count = 1;
 
         // Build dynamic query based on filters
         if filters.symbol.is_some() {
             param_count += 1;
-            query.push_str(&format!(" AND symbol = ${}", param_count));
+            query.push_str(&format!(" AND (symbol = ${0} OR underlying = ${0})", param_count));
         }
         if filters.direction.is_some() {
             param_count += 1;
@@ -119,28 +257,42 @@ impl TradeRepository {
             param_count += 1;
             query.push_str(&format!(" AND setup_type = ${}", param_count));
         }
-
-        query.push_str(" ORDER BY entry_time DESC");
-
-        if let Some(limit) = filters.limit {
+        if filters.playbook_id.is_some() {
             param_count += 1;
-            query.push_str(&format!(" LIMIT ${}", param_count));
+            query.push_str(&format!(" AND playbook_id = ${}", param_count));
         }
-        if let Some(offset) = filters.offset {
+        if filters.tags.is_some() {
             param_count += 1;
-            query.push_str(&format!(" OFFSET ${}", param_count));
+            query.push_str(&format!(" AND tags @> ${}", param_count));
+        }
+        if filters.broker.is_some() {
+            param_count += 1;
+            query.push_str(&format!(" AND broker = ${}", param_count));
+        }
+        if filters.account_id.is_some() {
+            param_count += 1;
+            query.push_str(&format!(" AND account_id = ${}", param_count));
         }
 
-        let mut q = sqlx::query_as::<_, Trade>(&query).bind(user_id);
+        (query, param_count)
+    }
+
+    /// Bind the user ID and the parameters of `filter_conditions`
+    pub(crate) fn bind_filters<'q, O>(
+        mut q: QueryAs<'q, Postgres, O, PgArguments>,
+        user_id: Uuid,
+        filters: &TradeFilters,
+    ) -> QueryAs<'q, Postgres, O, PgArguments> {
+        q = q.bind(user_id);
 
-        if let Some(symbol) = filters.symbol {
-            q = q.bind(symbol);
+        if let Some(symbol) = &filters.symbol {
+            q = q.bind(symbol.clone());
         }
-        if let Some(direction) = filters.direction {
-            q = q.bind(direction);
+        if let Some(direction) = &filters.direction {
+            q = q.bind(direction.clone());
         }
-        if let Some(status) = filters.status {
-            q = q.bind(status);
+        if let Some(status) = &filters.status {
+            q = q.bind(status.clone());
         }
         if let Some(from_date) = filters.from_date {
             q = q.bind(from_date);
@@ -148,9 +300,50 @@ impl TradeRepository {
         if let Some(to_date) = filters.to_date {
             q = q.bind(to_date);
         }
-        if let Some(setup_type) = filters.setup_type {
-            q = q.bind(setup_type);
+        if let Some(setup_type) = &filters.setup_type {
+            q = q.bind(setup_type.clone());
+        }
+        if let Some(playbook_id) = filters.playbook_id {
+            q = q.bind(playbook_id);
         }
+        if let Some(tags) = &filters.tags {
+            q = q.bind(tags.clone());
+        }
+        if let Some(broker) = &filters.broker {
+            q = q.bind(broker.clone());
+        }
+        if let Some(account_id) = filters.account_id {
+            q = q.bind(account_id);
+        }
+
+        q
+    }
+
+    /// Build the list SELECT, including ordering and pagination
+    fn list_query(filters: &TradeFilters) -> String {
+        let (conditions, mut param_count) = Self::filter_conditions(filters);
+        let mut query = format!("SELECT * FROM trades WHERE {} ORDER BY entry_time DESC", conditions);
+
+        if filters.limit.is_some() {
+            param_count += 1;
+            query.push_str(&format!(" LIMIT ${}", param_count));
+        }
+        if filters.offset.is_some() {
+            param_count += 1;
+            query.push_str(&format!(" OFFSET ${}", param_count));
+        }
+
+        query
+    }
+
+    /// Bind the parameters of `list_query`
+    fn bind_list<'q>(
+        q: QueryAs<'q, Postgres, Trade, PgArguments>,
+        user_id: Uuid,
+        filters: &TradeFilters,
+    ) -> QueryAs<'q, Postgres, Trade, PgArguments> {
+        let mut q = Self::bind_filters(q, user_id, filters);
+
         if let Some(limit) = filters.limit {
             q = q.bind(limit);
         }
@@ -158,120 +351,792 @@ impl TradeRepository {
             q = q.bind(offset);
         }
 
-        let trades = q.fetch_all(&self.pool).await?;
-
-        Ok(trades)
+        q
     }
 
     /// Update trade
+    ///
+    /// Applies a partial update: fields missing from the request keep their
+    /// stored value. P&L is recomputed from the merged trade (or from its
+    /// executions) and the status flips to `closed` once the position is
+    /// flat. The row is locked for the
+    /// duration of the transaction so concurrent readers never see a
+    /// half-updated trade.
     pub async fn update(&self, trade_id: Uuid, user_id: Uuid, req: UpdateTradeRequest) -> Result<Trade> {
-        // Get existing trade
-        let existing = self.get(trade_id, user_id).await?
-            .ok_or(AppError::ValidationError("Trade not found".to_string()))?;
+        req.validate()?;
 
-        // Build update query dynamically
-        let mut updates = Vec::new();
-        let mut param_count = 1;
+        let mut tx = self.pool.begin().await?;
+        let mut trade = Self::lock(&mut tx, trade_id, user_id).await?;
+        let before = trade.clone();
 
-        if req.symbol.is_some() {
-            param_count += 1;
-            updates.push(format!("symbol = ${}", param_count));
+        // Orders open their trade when they fill
+        let records_fill = req.exit_price.is_some() || req.executions.is_some() || req.legs.is_some();
+        if trade.is_unfilled() && (records_fill || req.status.is_some()) {
+            return Err(AppError::ValidationError(
+                "The trade's order hasn't filled; fill it before recording executions, legs, an exit or a status"
+                    .to_string(),
+            ));
         }
-        if req.direction.is_some() {
-            param_count += 1;
-            updates.push(format!("direction = ${}", param_count));
+
+        // Merge requested changes
+        let previous_currency = trade.currency.clone();
+        if let Some(symbol) = req.symbol {
+            if symbol != trade.symbol {
+                let (multiplier, quote_currency) = Self::instrument_for(&mut tx, user_id, &symbol).await?;
+                trade.multiplier = multiplier;
+                if let Some(quote_currency) = quote_currency {
+                    trade.currency = quote_currency;
+                }
+                trade.underlying = InstrumentService::option_underlying(&symbol);
+            }
+            trade.symbol = symbol;
         }
-        if req.entry_price.is_some() {
-            param_count += 1;
-            updates.push(format!("entry_price = ${}", param_count));
+        if let Some(direction) = req.direction {
+            trade.direction = direction;
+        }
+        if let Some(entry_price) = req.entry_price {
+            trade.entry_price = entry_price;
         }
         if req.exit_price.is_some() {
-            param_count += 1;
-            updates.push(format!("exit_price = ${}", param_count));
+            trade.exit_price = req.exit_price;
         }
-        if req.quantity.is_some() {
-            param_count += 1;
-            updates.push(format!("quantity = ${}", param_count));
+        if let Some(quantity) = req.quantity {
+            trade.quantity = quantity;
         }
-        if req.entry_time.is_some() {
-            param_count += 1;
-            updates.push(format!("entry_time = ${}", param_count));
+        if let Some(entry_time) = req.entry_time {
+            trade.entry_time = entry_time;
         }
         if req.exit_time.is_some() {
-            param_count += 1;
-            updates.push(format!("exit_time = ${}", param_count));
+            trade.exit_time = req.exit_time;
         }
-        if req.fees.is_some() {
-            param_count += 1;
-            updates.push(format!("fees = ${}", param_count));
+        if let Some(fees) = req.fees {
+            trade.fees = fees;
+        }
+        if let Some(currency) = req.currency {
+            trade.currency = currency;
+        }
+        // Fees charged in the trade currency follow it unless set explicitly
+        match req.fee_currency {
+            Some(fee_currency) => trade.fee_currency = fee_currency,
+            None if trade.fee_currency == previous_currency => trade.fee_currency = trade.currency.clone(),
+            None => {}
+        }
+        if req.stop_loss.is_some() {
+            trade.stop_loss = req.stop_loss;
+        }
+        if req.initial_stop.is_some() {
+            trade.initial_stop = req.initial_stop;
+        }
+        if let Some(take_profit) = req.take_profit {
+            trade.take_profit = take_profit;
+        }
+        if req.planned_risk.is_some() {
+            trade.planned_risk = req.planned_risk;
         }
         if req.notes.is_some() {
-            param_count += 1;
-            updates.push(format!("notes = ${}", param_count));
+            trade.notes = req.notes;
         }
-        if req.tags.is_some() {
-            param_count += 1;
-            updates.push(format!("tags = ${}", param_count));
+        if let Some(tags) = req.tags {
+            trade.tags = VocabularyRepository::normalize_with(&mut tx, user_id, VocabularyKind::Tags, tags).await?;
         }
-        if req.setup_type.is_some() {
-            param_count += 1;
-            updates.push(format!("setup_type = ${}", param_count));
+        if req.playbook_id.is_some() || req.setup_type.is_some() {
+            let playbook = Self::playbook_for(&mut tx, user_id, req.playbook_id, req.setup_type.as_deref()).await?;
+            // Checklist items only carry over to a playbook that has them
+            trade
+                .checklist_met
+                .retain(|item| playbook.as_ref().is_some_and(|p| p.checklist.contains(item)));
+            trade.playbook_id = playbook.as_ref().map(|p| p.id);
+            trade.setup_type = playbook.map(|p| p.name).or(req.setup_type);
         }
-        if req.mistakes.is_some() {
-            param_count += 1;
-            updates.push(format!("mistakes = ${}", param_count));
+        if let Some(checklist_met) = req.checklist_met {
+            let playbook = match trade.playbook_id {
+                Some(playbook_id) => Some(PlaybookRepository::ensure_owned(&mut tx, playbook_id, user_id).await?),
+                None => None,
+            };
+            Self::validate_checklist_met(playbook.as_ref(), &checklist_met)?;
+            trade.checklist_met = checklist_met;
         }
-        if req.emotions.is_some() {
-            param_count += 1;
-            updates.push(format!("emotions = ${}", param_count));
+        if req.strategy.is_some() {
+            trade.strategy = req.strategy;
+        }
+        if let Some(mistakes) = req.mistakes {
+            trade.mistakes = VocabularyRepository::normalize_with(&mut tx, user_id, VocabularyKind::Mistakes, mistakes).await?;
+        }
+        if let Some(emotions) = req.emotions {
+            trade.emotions = VocabularyRepository::normalize_with(&mut tx, user_id, VocabularyKind::Emotions, emotions).await?;
         }
         if req.broker.is_some() {
-            param_count += 1;
-            updates.push(format!("broker = ${}", param_count));
+            trade.broker = req.broker;
         }
-        if req.account_id.is_some() {
-            param_count += 1;
-            updates.push(format!("account_id = ${}", param_count));
+        if let Some(account_id) = req.account_id {
+            AccountRepository::ensure_owned(&mut tx, account_id, user_id).await?;
+            trade.account_id = Some(account_id);
         }
-        if req.status.is_some() {
-            param_count += 1;
-            updates.push(format!("status = ${}", param_count));
+        if let Some(status) = req.status {
+            trade.status = status;
         }
 
-        updates.push("updated_at = NOW()".to_string());
+        // Recalculate P&L and status from fills, legs or the merged values
+        if let Some(executions) = req.executions {
+            ExecutionRepository::replace_for_trade(&mut tx, trade_id, user_id, &executions).await?;
+        }
+        if let Some(legs) = req.legs {
+            let legs = Self::normalize_legs(Some(legs), trade.entry_time)?.unwrap_or_default();
+            OptionLegRepository::replace_for_trade(&mut tx, trade_id, user_id, &legs).await?;
+        }
 
-        if updates.is_empty() {
-            return Ok(existing);
+        let trade = Self::recalculate(&mut tx, &mut trade).await?;
+        TradeRevisionRepository::record_with(&mut tx, user_id, "update", Some(&before), Some(&trade), None).await?;
+
+        tx.commit().await?;
+
+        Ok(trade)
+    }
+
+    /// Open a pending trade at the fill of its order
+    pub(crate) async fn fill_with(
+        conn: &mut PgConnection,
+        trade_id: Uuid,
+        user_id: Uuid,
+        price: Decimal,
+        filled_at: DateTime<Utc>,
+        quantity: Decimal,
+        fees: Option<Decimal>,
+    ) -> Result<Trade> {
+        let mut trade = Self::lock(conn, trade_id, user_id).await?;
+        let before = trade.clone();
+        trade.status = "open".to_string();
+        trade.entry_price = price;
+        trade.entry_time = filled_at;
+        trade.quantity = quantity;
+        if let Some(fees) = fees {
+            trade.fees = fees;
         }
 
-        let query = format!(
-            "UPDATE trades SET {} WHERE id = $1 AND user_id = ${} RETURNING *",
-            updates.join(", "),
-            param_count + 1
-        );
+        let trade = Self::recalculate(conn, &mut trade).await?;
+        TradeRevisionRepository::record_with(conn, user_id, "update", Some(&before), Some(&trade), None).await?;
 
-        // This is a simplified version - in production, use a proper query builder
-        // For now, return the existing trade (update logic would be more complex)
-        Ok(existing)
+        Ok(trade)
     }
 
-    /// Delete trade
-    pub async fn delete(&self, trade_id: Uuid, user_id: Uuid) -> Result<()> {
-        let result = sqlx::query(
+    /// Give a pending trade the final status of its unfilled order
+    pub(crate) async fn set_unfilled_with(
+        conn: &mut PgConnection,
+        trade_id: Uuid,
+        user_id: Uuid,
+        status: &str,
+    ) -> Result<Trade> {
+        let mut trade = Self::lock(conn, trade_id, user_id).await?;
+        let before = trade.clone();
+        trade.status = status.to_string();
+
+        let trade = Self::write(conn, &trade).await?;
+        TradeRevisionRepository::record_with(conn, user_id, "update", Some(&before), Some(&trade), None).await?;
+
+        Ok(trade)
+    }
+
+    /// Bring a trade back to how it was right after a revision.
+    ///
+    /// The recorded fields are restored and the derived values recomputed;
+    /// executions and legs are not part of revisions, so trades built from
+    /// them keep the values of their current fills. Pending, cancelled and
+    /// expired statuses follow the trade's order and aren't restored across.
+    ///
+    /// A deleted trade is re-inserted under its ID from the fields its
+    /// delete revision recorded. Its executions, legs, attachments and
+    /// order were deleted with it and don't come back: the trade keeps the
+    /// prices and quantities they last gave it, without screenshots, and
+    /// can't be restored to a pending, cancelled or expired revision.
+    pub async fn restore(&self, trade_id: Uuid, user_id: Uuid, revision: i32) -> Result<Trade> {
+        let mut tx = self.pool.begin().await?;
+        let current = sqlx::query_as::<_, Trade>(
             r#"
-            DELETE FROM trades WHERE id = $1 AND user_id = $2
+            SELECT * FROM trades WHERE id = $1 AND user_id = $2 FOR UPDATE
             "#,
         )
         .bind(trade_id)
         .bind(user_id)
-        .execute(&self.pool)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let revisions = TradeRevisionRepository::list_with(&mut tx, trade_id, user_id).await?;
+        let before = match &current {
+            Some(trade) => trade.clone(),
+            None => RevisionService::deleted(&revisions)?,
+        };
+
+        let mut trade = RevisionService::restore(&before, &revisions, revision)?;
+        if current.is_none() && trade.is_unfilled() {
+            return Err(AppError::ValidationError(format!(
+                "The trade was {} at that revision; its order was deleted with the trade",
+                trade.status
+            )));
+        }
+        if trade.status != before.status && (trade.is_unfilled() || before.is_unfilled()) {
+            return Err(AppError::ValidationError(format!(
+                "The trade is {} and was {} at that revision; its order sets this status",
+                before.status, trade.status
+            )));
+        }
+
+        // Links may point at records deleted since
+        if let Some(account_id) = trade.account_id {
+            AccountRepository::ensure_owned(&mut tx, account_id, user_id).await?;
+        }
+        if let Some(playbook_id) = trade.playbook_id {
+            PlaybookRepository::ensure_owned(&mut tx, playbook_id, user_id).await?;
+        }
+
+        if current.is_none() {
+            trade.screenshots = Vec::new();
+            Self::reinsert(&mut tx, &trade).await?;
+        }
+
+        let trade = Self::recalculate(&mut tx, &mut trade).await?;
+        TradeRevisionRepository::record_with(
+            &mut tx,
+            user_id,
+            "restore",
+            current.as_ref(),
+            Some(&trade),
+            Some(revision),
+        )
         .await?;
 
-        if result.rows_affected() == 0 {
-            return Err(AppError::ValidationError("Trade not found".to_string()));
+        tx.commit().await?;
+
+        Ok(trade)
+    }
+
+    /// Close one open leg of an option trade.
+    ///
+    /// Assigned and exercised legs close at zero and the shares change
+    /// hands at the strike: an open stock leg of the opposite side and the
+    /// same size is closed at the strike, otherwise a new stock leg opens.
+    pub async fn close_leg(
+        &self,
+        trade_id: Uuid,
+        leg_id: Uuid,
+        user_id: Uuid,
+        req: CloseOptionLegRequest,
+    ) -> Result<Trade> {
+        let mut tx = self.pool.begin().await?;
+        let mut trade = Self::lock(&mut tx, trade_id, user_id).await?;
+        let before = trade.clone();
+
+        let legs = OptionLegRepository::list_for_trade_with(&mut tx, trade_id, user_id).await?;
+        let leg = legs
+            .iter()
+            .find(|leg| leg.id == leg_id)
+            .ok_or(AppError::ValidationError("Leg not found".to_string()))?;
+        if leg.closed_at.is_some() {
+            return Err(AppError::ValidationError("Leg is already closed".to_string()));
         }
 
+        let invalid = |message: &str| Err(AppError::ValidationError(message.to_string()));
+        let is_option = leg.option_type != "stock";
+        let (premium, closed_at) = match req.reason.as_str() {
+            "closed" => match req.premium {
+                Some(premium) if premium >= Decimal::ZERO => (premium, req.closed_at.unwrap_or_else(Utc::now)),
+                _ => return invalid("A close premium of zero or more is required"),
+            },
+            "expired" => {
+                let Some(expiry) = leg.expiry else {
+                    return invalid("Only option legs can expire");
+                };
+                let closed_at = req.closed_at.unwrap_or_else(|| expiration_time(expiry));
+                if closed_at.date_naive() < expiry {
+                    return invalid("A leg can't expire before its expiry date");
+                }
+                (Decimal::ZERO, closed_at)
+            }
+            "assigned" if is_option && leg.side == "sell" => (Decimal::ZERO, req.closed_at.unwrap_or_else(Utc::now)),
+            "exercised" if is_option && leg.side == "buy" => (Decimal::ZERO, req.closed_at.unwrap_or_else(Utc::now)),
+            "assigned" => return invalid("Only sold option legs can be assigned"),
+            "exercised" => return invalid("Only bought option legs can be exercised"),
+            _ => return invalid("Reason must be 'closed', 'expired', 'assigned' or 'exercised'"),
+        };
+        if closed_at < leg.opened_at {
+            return invalid("A leg can't close before it opens");
+        }
+
+        OptionLegRepository::close(&mut tx, leg.id, premium, closed_at, &req.reason).await?;
+
+        if let Some(delivery) = leg.delivery(closed_at).filter(|_| req.reason == "assigned" || req.reason == "exercised") {
+            let offsetting = legs.iter().find(|other| {
+                other.option_type == "stock"
+                    && other.closed_at.is_none()
+                    && other.side != delivery.side
+                    && other.quantity == delivery.quantity
+            });
+
+            match offsetting {
+                Some(stock) => {
+                    OptionLegRepository::close(&mut tx, stock.id, delivery.premium, closed_at, &req.reason).await?;
+                }
+                None => {
+                    OptionLegRepository::insert(&mut tx, trade_id, user_id, &delivery).await?;
+                }
+            }
+        }
+
+        let trade = Self::recalculate(&mut tx, &mut trade).await?;
+        TradeRevisionRepository::record_with(&mut tx, user_id, "update", Some(&before), Some(&trade), None).await?;
+
+        tx.commit().await?;
+
+        Ok(trade)
+    }
+
+    /// Close open option legs that expired before `as_of` as worthless and
+    /// return the trades that changed.
+    ///
+    /// Legs that finished in the money should be closed as assigned or
+    /// exercised before running this.
+    pub async fn expire_legs(&self, user_id: Uuid, as_of: NaiveDate) -> Result<Vec<Trade>> {
+        let mut tx = self.pool.begin().await?;
+        let trade_ids = OptionLegRepository::trades_with_expired_legs(&mut tx, user_id, as_of).await?;
+
+        let mut updated = Vec::with_capacity(trade_ids.len());
+        for trade_id in trade_ids {
+            let mut trade = Self::lock(&mut tx, trade_id, user_id).await?;
+            let before = trade.clone();
+
+            let legs = OptionLegRepository::list_for_trade_with(&mut tx, trade_id, user_id).await?;
+            for leg in legs.iter().filter(|leg| leg.closed_at.is_none()) {
+                if let Some(expiry) = leg.expiry.filter(|expiry| *expiry < as_of) {
+                    OptionLegRepository::close(&mut tx, leg.id, Decimal::ZERO, expiration_time(expiry), "expired").await?;
+                }
+            }
+
+            let trade = Self::recalculate(&mut tx, &mut trade).await?;
+            TradeRevisionRepository::record_with(&mut tx, user_id, "update", Some(&before), Some(&trade), None).await?;
+            updated.push(trade);
+        }
+
+        tx.commit().await?;
+
+        Ok(updated)
+    }
+
+    /// Lock a trade for the rest of the transaction
+    async fn lock(conn: &mut PgConnection, trade_id: Uuid, user_id: Uuid) -> Result<Trade> {
+        let trade = sqlx::query_as::<_, Trade>(
+            r#"
+            SELECT * FROM trades WHERE id = $1 AND user_id = $2 FOR UPDATE
+            "#,
+        )
+        .bind(trade_id)
+        .bind(user_id)
+        .fetch_optional(conn)
+        .await?
+        .ok_or(AppError::ValidationError("Trade not found".to_string()))?;
+
+        Ok(trade)
+    }
+
+    /// Playbook a trade is taken from: the one given by ID, otherwise the one
+    /// named by the setup type, if any
+    async fn playbook_for(
+        conn: &mut PgConnection,
+        user_id: Uuid,
+        playbook_id: Option<Uuid>,
+        setup_type: Option<&str>,
+    ) -> Result<Option<Playbook>> {
+        match (playbook_id, setup_type) {
+            (Some(playbook_id), _) => Ok(Some(PlaybookRepository::ensure_owned(conn, playbook_id, user_id).await?)),
+            (None, Some(setup_type)) => PlaybookRepository::find_by_name_with(conn, user_id, setup_type).await,
+            (None, None) => Ok(None),
+        }
+    }
+
+    /// Met checklist items need a playbook that lists them
+    fn validate_checklist_met(playbook: Option<&Playbook>, checklist_met: &[String]) -> Result<()> {
+        match playbook {
+            Some(playbook) => playbook.validate_checklist_met(checklist_met),
+            None if !checklist_met.is_empty() => Err(AppError::ValidationError(
+                "Checklist items can only be recorded for trades with a playbook".to_string(),
+            )),
+            None => Ok(()),
+        }
+    }
+
+    /// Validate legs and fill in their defaults; an empty list counts as none
+    fn normalize_legs(
+        legs: Option<Vec<CreateOptionLegRequest>>,
+        entry_time: DateTime<Utc>,
+    ) -> Result<Option<Vec<CreateOptionLegRequest>>> {
+        let Some(mut legs) = legs.filter(|legs| !legs.is_empty()) else {
+            return Ok(None);
+        };
+
+        for leg in &mut legs {
+            leg.normalize(entry_time)?;
+        }
+
+        Ok(Some(legs))
+    }
+
+    /// Derive and persist the trade's values from its stored executions or
+    /// legs, or from its flat fields when it has neither
+    async fn recalculate(conn: &mut PgConnection, trade: &mut Trade) -> Result<Trade> {
+        let executions: Vec<CreateExecutionRequest> =
+            ExecutionRepository::list_for_trade_with(conn, trade.id, trade.user_id)
+                .await?
+                .iter()
+                .map(CreateExecutionRequest::from)
+                .collect();
+        let legs: Vec<CreateOptionLegRequest> = OptionLegRepository::list_for_trade_with(conn, trade.id, trade.user_id)
+            .await?
+            .iter()
+            .map(CreateOptionLegRequest::from)
+            .collect();
+        if !executions.is_empty() && !legs.is_empty() {
+            return Err(both_fills_and_legs());
+        }
+
+        let last_activity = executions
+            .iter()
+            .map(|e| e.executed_at)
+            .chain(legs.iter().flat_map(|leg| leg.opened_at.into_iter().chain(leg.closed_at)))
+            .max();
+
+        let executions = (!executions.is_empty()).then_some(executions);
+        let legs = (!legs.is_empty()).then_some(legs);
+
+        Self::apply_fee_rate(conn, trade, last_activity).await?;
+        Self::apply_derived_values(trade, executions.as_deref(), legs.as_deref())?;
+        Self::apply_excursions(conn, trade, legs.is_some()).await?;
+        Self::write(conn, trade).await
+    }
+
+    /// Recalculate excursions of closed trades on a symbol from the stored
+    /// price bars, optionally only for trades overlapping a time window.
+    /// Returns the number of trades updated.
+    pub async fn refresh_excursions(
+        &self,
+        user_id: Uuid,
+        symbol: &str,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+    ) -> Result<usize> {
+        let mut tx = self.pool.begin().await?;
+
+        let mut trades = sqlx::query_as::<_, Trade>(
+            r#"
+            SELECT * FROM trades t
+            WHERE user_id = $1
+                AND symbol = $2
+                AND status = 'closed'
+                AND ($3::TIMESTAMPTZ IS NULL OR exit_time >= $3)
+                AND ($4::TIMESTAMPTZ IS NULL OR entry_time <= $4)
+                AND NOT EXISTS (SELECT 1 FROM option_legs l WHERE l.trade_id = t.id)
+            FOR UPDATE
+            "#,
+        )
+        .bind(user_id)
+        .bind(symbol)
+        .bind(from)
+        .bind(to)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        for trade in &mut trades {
+            let before = trade.clone();
+            Self::apply_excursions(&mut tx, trade, false).await?;
+
+            sqlx::query(
+                r#"
+                UPDATE trades SET mae = $2, mfe = $3, entry_efficiency = $4, exit_efficiency = $5
+                WHERE id = $1
+                "#,
+            )
+            .bind(trade.id)
+            .bind(trade.mae)
+            .bind(trade.mfe)
+            .bind(trade.entry_efficiency)
+            .bind(trade.exit_efficiency)
+            .execute(&mut *tx)
+            .await?;
+
+            TradeRevisionRepository::record_with(&mut tx, user_id, "update", Some(&before), Some(trade), None).await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(trades.len())
+    }
+
+    /// Derive prices, quantities, P&L, status and R multiples.
+    ///
+    /// With executions the values come from the fills, with option legs
+    /// from the legs; otherwise the flat entry/exit fields are used as a
+    /// single round trip.
+    fn apply_derived_values(
+        trade: &mut Trade,
+        executions: Option<&[CreateExecutionRequest]>,
+        legs: Option<&[CreateOptionLegRequest]>,
+    ) -> Result<()> {
+        Self::apply_pnl(trade, executions, legs)?;
+
+        let (r_multiple, planned_r_multiple) = trade.calculate_r_multiples();
+        trade.r_multiple = r_multiple;
+        trade.planned_r_multiple = planned_r_multiple;
+
+        Ok(())
+    }
+
+    fn apply_pnl(
+        trade: &mut Trade,
+        executions: Option<&[CreateExecutionRequest]>,
+        legs: Option<&[CreateOptionLegRequest]>,
+    ) -> Result<()> {
+        if let Some(legs) = legs {
+            let summary = LegSummary::from_legs(legs, trade.fee_fx_rate)?;
+
+            trade.underlying = Some(summary.underlying);
+            trade.direction = summary.direction;
+            trade.entry_price = summary.entry_price;
+            trade.exit_price = summary.exit_price;
+            trade.quantity = summary.quantity;
+            trade.open_quantity = summary.open_quantity;
+            trade.multiplier = summary.multiplier;
+            trade.entry_time = summary.entry_time;
+            trade.exit_time = summary.exit_time;
+            trade.fees = summary.fees;
+            trade.pnl = summary.pnl;
+            trade.pnl_percentage = summary.pnl_percentage;
+            trade.status = summary.status;
+
+            return Ok(());
+        }
+
+        if let Some(executions) = executions {
+            let summary = ExecutionSummary::from_executions(&trade.direction, trade.multiplier, trade.fee_fx_rate, executions)?;
+
+            trade.entry_price = summary.entry_price;
+            trade.exit_price = summary.exit_price;
+            trade.quantity = summary.quantity;
+            trade.open_quantity = summary.open_quantity;
+            trade.entry_time = summary.entry_time;
+            trade.exit_time = summary.exit_time;
+            trade.fees = summary.fees;
+            trade.pnl = summary.pnl;
+            trade.pnl_percentage = summary.pnl_percentage;
+            trade.status = summary.status;
+
+            return Ok(());
+        }
+
+        match trade.calculate_pnl() {
+            Some((pnl, pnl_percentage)) => {
+                trade.pnl = Some(pnl);
+                trade.pnl_percentage = pnl_percentage;
+                trade.open_quantity = Decimal::ZERO;
+                trade.status = "closed".to_string();
+            }
+            None => {
+                trade.pnl = None;
+                trade.pnl_percentage = None;
+                trade.open_quantity = if trade.is_unfilled() { Decimal::ZERO } else { trade.quantity };
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Derive MAE, MFE and efficiencies of a closed trade from the price
+    /// bars of its symbol between entry and exit.
+    ///
+    /// They are cleared while the trade is open, when no bars cover it, and
+    /// for option leg trades, whose prices are net premiums.
+    async fn apply_excursions(conn: &mut PgConnection, trade: &mut Trade, has_legs: bool) -> Result<()> {
+        let excursion = match (trade.status.as_str(), trade.exit_price, trade.exit_time) {
+            ("closed", Some(exit_price), Some(exit_time)) if !has_legs => {
+                PriceBarRepository::range_with(conn, trade.user_id, &trade.symbol, trade.entry_time, exit_time)
+                    .await?
+                    .map(|(high, low)| Excursion::from_range(&trade.direction, trade.entry_price, exit_price, high, low))
+            }
+            _ => None,
+        };
+
+        trade.mae = excursion.map(|e| e.mae);
+        trade.mfe = excursion.map(|e| e.mfe);
+        trade.entry_efficiency = excursion.and_then(|e| e.entry_efficiency);
+        trade.exit_efficiency = excursion.and_then(|e| e.exit_efficiency);
+
+        Ok(())
+    }
+
+    /// Multiplier and quote currency of the instrument a symbol resolves to
+    async fn instrument_for(conn: &mut PgConnection, user_id: Uuid, symbol: &str) -> Result<(Decimal, Option<String>)> {
+        let instrument = InstrumentRepository::resolve_with(conn, user_id, symbol).await?;
+        let multiplier = InstrumentService::multiplier(instrument.as_ref(), symbol);
+
+        Ok((multiplier, instrument.map(|i| i.quote_currency)))
+    }
+
+    /// Look up the rate converting fees into the trade currency.
+    ///
+    /// The rate is taken as of the last fill or leg activity, or the exit
+    /// (entry while the trade is open), and fails when no rate on or before
+    /// that day exists.
+    async fn apply_fee_rate(
+        conn: &mut PgConnection,
+        trade: &mut Trade,
+        last_activity: Option<DateTime<Utc>>,
+    ) -> Result<()> {
+        if trade.fee_currency == trade.currency {
+            trade.fee_fx_rate = Decimal::ONE;
+            trade.fee_fx_date = None;
+            return Ok(());
+        }
+
+        let date = last_activity
+            .unwrap_or(trade.exit_time.unwrap_or(trade.entry_time))
+            .date_naive();
+
+        let (rate, rate_date) =
+            FxRateRepository::rate_with(conn, trade.user_id, &trade.fee_currency, &trade.currency, date)
+                .await?
+                .ok_or_else(|| {
+                    AppError::ValidationError(format!(
+                        "No {}/{} FX rate on or before {}",
+                        trade.fee_currency, trade.currency, date
+                    ))
+                })?;
+
+        trade.fee_fx_rate = rate;
+        trade.fee_fx_date = Some(rate_date);
+
+        Ok(())
+    }
+
+    /// Insert a deleted trade back under its ID with the fields `write`
+    /// doesn't set and those of its external ID; `write` fills in the rest
+    async fn reinsert(conn: &mut PgConnection, trade: &Trade) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO trades (
+                id, user_id, symbol, direction, entry_price, quantity, entry_time,
+                fee_currency, screenshots, broker, external_id, created_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+            "#,
+        )
+        .bind(trade.id)
+        .bind(trade.user_id)
+        .bind(&trade.symbol)
+        .bind(&trade.direction)
+        .bind(trade.entry_price)
+        .bind(trade.quantity)
+        .bind(trade.entry_time)
+        .bind(&trade.fee_currency)
+        .bind(&trade.screenshots)
+        .bind(&trade.broker)
+        .bind(&trade.external_id)
+        .bind(trade.created_at)
+        .execute(conn)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(db_err) if db_err.is_unique_violation() => AppError::ValidationError(
+                "Another trade has since been imported with the same external ID".to_string(),
+            ),
+            _ => AppError::DatabaseError(e),
+        })?;
+
+        Ok(())
+    }
+
+    /// Persist all mutable columns of a trade
+    async fn write(conn: &mut PgConnection, trade: &Trade) -> Result<Trade> {
+        let trade = sqlx::query_as::<_, Trade>(
+            r#"
+            UPDATE trades SET
+                symbol = $3, direction = $4, entry_price = $5, exit_price = $6, quantity = $7,
+                open_quantity = $8, entry_time = $9, exit_time = $10, pnl = $11, pnl_percentage = $12,
+                fees = $13, notes = $14, tags = $15, setup_type = $16, mistakes = $17, emotions = $18,
+                broker = $19, account_id = $20, status = $21, stop_loss = $22, initial_stop = $23,
+                take_profit = $24, planned_risk = $25, r_multiple = $26, planned_r_multiple = $27,
+                multiplier = $28, currency = $29, fee_currency = $30, fee_fx_rate = $31,
+                fee_fx_date = $32, strategy = $33, underlying = $34, mae = $35, mfe = $36,
+                entry_efficiency = $37, exit_efficiency = $38, playbook_id = $39, checklist_met = $40,
+                updated_at = NOW()
+            WHERE id = $1 AND user_id = $2
+            RETURNING *
+            "#,
+        )
+        .bind(trade.id)
+        .bind(trade.user_id)
+        .bind(&trade.symbol)
+        .bind(&trade.direction)
+        .bind(trade.entry_price)
+        .bind(trade.exit_price)
+        .bind(trade.quantity)
+        .bind(trade.open_quantity)
+        .bind(trade.entry_time)
+        .bind(trade.exit_time)
+        .bind(trade.pnl)
+        .bind(trade.pnl_percentage)
+        .bind(trade.fees)
+        .bind(&trade.notes)
+        .bind(&trade.tags)
+        .bind(&trade.setup_type)
+        .bind(&trade.mistakes)
+        .bind(&trade.emotions)
+        .bind(&trade.broker)
+        .bind(trade.account_id)
+        .bind(&trade.status)
+        .bind(trade.stop_loss)
+        .bind(trade.initial_stop)
+        .bind(&trade.take_profit)
+        .bind(trade.planned_risk)
+        .bind(trade.r_multiple)
+        .bind(trade.planned_r_multiple)
+        .bind(trade.multiplier)
+        .bind(&trade.currency)
+        .bind(&trade.fee_currency)
+        .bind(trade.fee_fx_rate)
+        .bind(trade.fee_fx_date)
+        .bind(&trade.strategy)
+        .bind(&trade.underlying)
+        .bind(trade.mae)
+        .bind(trade.mfe)
+        .bind(trade.entry_efficiency)
+        .bind(trade.exit_efficiency)
+        .bind(trade.playbook_id)
+        .bind(&trade.checklist_met)
+        .fetch_one(conn)
+        .await?;
+
+        Ok(trade)
+    }
+
+    /// Delete trade; its revisions are kept
+    pub async fn delete(&self, trade_id: Uuid, user_id: Uuid) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+
+        let trade = sqlx::query_as::<_, Trade>(
+            r#"
+            DELETE FROM trades WHERE id = $1 AND user_id = $2 RETURNING *
+            "#,
+        )
+        .bind(trade_id)
+        .bind(user_id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or(AppError::ValidationError("Trade not found".to_string()))?;
+
+        TradeRevisionRepository::record_with(&mut tx, user_id, "delete", Some(&trade), None, None).await?;
+
+        tx.commit().await?;
+
         Ok(())
     }
 }
 
+
+fn both_fills_and_legs() -> AppError {
+    AppError::ValidationError("A trade can have executions or option legs, not both".to_string())
+}
@@ -0,0 +1,100 @@
+use crate::{
+    error::{AppError, Result},
+    middleware::current_request_id,
+    models::{Trade, TradeRevision},
+    services::RevisionService,
+};
+use serde_json::Value;
+use sqlx::{PgConnection, PgPool};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+pub struct TradeRevisionRepository {
+    pool: PgPool,
+}
+
+impl TradeRevisionRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Revisions of a trade, newest first; kept after the trade is deleted
+    pub async fn list(&self, trade_id: Uuid, user_id: Uuid) -> Result<Vec<TradeRevision>> {
+        let mut conn = self.pool.acquire().await?;
+        let revisions = Self::list_with(&mut conn, trade_id, user_id).await?;
+
+        if revisions.is_empty() {
+            return Err(AppError::ValidationError("Trade not found".to_string()));
+        }
+
+        Ok(revisions)
+    }
+
+    pub(crate) async fn list_with(conn: &mut PgConnection, trade_id: Uuid, user_id: Uuid) -> Result<Vec<TradeRevision>> {
+        let revisions = sqlx::query_as::<_, TradeRevision>(
+            r#"
+            SELECT * FROM trade_revisions WHERE trade_id = $1 AND user_id = $2 ORDER BY revision DESC
+            "#,
+        )
+        .bind(trade_id)
+        .bind(user_id)
+        .fetch_all(conn)
+        .await?;
+
+        Ok(revisions)
+    }
+
+    /// Record the change between two states of a trade, tagged with the
+    /// request being handled. Updates that change nothing are not recorded.
+    pub(crate) async fn record_with(
+        conn: &mut PgConnection,
+        user_id: Uuid,
+        action: &str,
+        before: Option<&Trade>,
+        after: Option<&Trade>,
+        restored_from: Option<i32>,
+    ) -> Result<()> {
+        let Some(trade_id) = after.or(before).map(|trade| trade.id) else {
+            return Ok(());
+        };
+
+        let changes = RevisionService::diff(before, after)?;
+        if changes.is_empty() && action == "update" {
+            return Ok(());
+        }
+
+        sqlx::query(
+            r#"
+            INSERT INTO trade_revisions (trade_id, user_id, revision, action, changes, request_id, restored_from)
+            VALUES (
+                $1, $2,
+                (SELECT COALESCE(MAX(revision), 0) + 1 FROM trade_revisions WHERE trade_id = $1),
+                $3, $4, $5, $6
+            )
+            "#,
+        )
+        .bind(trade_id)
+        .bind(user_id)
+        .bind(action)
+        .bind(Value::Object(changes))
+        .bind(current_request_id())
+        .bind(restored_from)
+        .execute(conn)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Record the changes a bulk update made to trades, pairing each trade's
+    /// state after the update with its state before by ID
+    pub(crate) async fn record_updates_with(conn: &mut PgConnection, before: &[Trade], after: &[Trade]) -> Result<()> {
+        let before: HashMap<Uuid, &Trade> = before.iter().map(|trade| (trade.id, trade)).collect();
+
+        for trade in after {
+            let previous = before.get(&trade.id).copied();
+            Self::record_with(conn, trade.user_id, "update", previous, Some(trade), None).await?;
+        }
+
+        Ok(())
+    }
+}
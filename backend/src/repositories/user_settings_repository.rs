@@ -0,0 +1,72 @@
+use crate::{
+    error::Result,
+    models::{UpdateUserSettingsRequest, UserSettings},
+    repositories::AccountRepository,
+};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+pub struct UserSettingsRepository {
+    pool: PgPool,
+}
+
+impl UserSettingsRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Get the user's settings, or the defaults if none were saved
+    pub async fn get(&self, user_id: Uuid) -> Result<UserSettings> {
+        let settings = sqlx::query_as::<_, UserSettings>(
+            r#"
+            SELECT * FROM user_settings WHERE user_id = $1
+            "#,
+        )
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(settings.unwrap_or_else(|| UserSettings::defaults(user_id)))
+    }
+
+    /// Save settings; fields missing from the request keep their value
+    pub async fn update(&self, user_id: Uuid, req: UpdateUserSettingsRequest) -> Result<UserSettings> {
+        let mut conn = self.pool.acquire().await?;
+
+        if let Some(account_id) = req.default_account_id {
+            AccountRepository::ensure_owned(&mut conn, account_id, user_id).await?;
+        }
+
+        let settings = sqlx::query_as::<_, UserSettings>(
+            r#"
+            INSERT INTO user_settings (
+                user_id, timezone, day_rollover_hour, reporting_currency, default_broker,
+                default_account_id, default_fees, risk_per_trade
+            )
+            VALUES ($1, COALESCE($2, 'UTC'), COALESCE($3, 0), $4, $5, $6, $7, $8)
+            ON CONFLICT (user_id) DO UPDATE SET
+                timezone = COALESCE($2, user_settings.timezone),
+                day_rollover_hour = COALESCE($3, user_settings.day_rollover_hour),
+                reporting_currency = COALESCE($4, user_settings.reporting_currency),
+                default_broker = COALESCE($5, user_settings.default_broker),
+                default_account_id = COALESCE($6, user_settings.default_account_id),
+                default_fees = COALESCE($7, user_settings.default_fees),
+                risk_per_trade = COALESCE($8, user_settings.risk_per_trade),
+                updated_at = NOW()
+            RETURNING *
+            "#,
+        )
+        .bind(user_id)
+        .bind(&req.timezone)
+        .bind(req.day_rollover_hour)
+        .bind(&req.reporting_currency)
+        .bind(&req.default_broker)
+        .bind(req.default_account_id)
+        .bind(req.default_fees)
+        .bind(req.risk_per_trade)
+        .fetch_one(&mut *conn)
+        .await?;
+
+        Ok(settings)
+    }
+}
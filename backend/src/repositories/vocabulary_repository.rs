@@ -0,0 +1,396 @@
+use crate::{
+    error::{AppError, Result},
+    models::{
+        CreateVocabularyTermRequest, MergeReport, MergeVocabularyRequest, UpdateVocabularyTermRequest, Vocabulary,
+        Trade, VocabularyKind, VocabularyTerm, VocabularyValue,
+    },
+    repositories::TradeRevisionRepository,
+};
+use sqlx::{PgConnection, PgPool};
+use uuid::Uuid;
+
+pub struct VocabularyRepository {
+    pool: PgPool,
+}
+
+impl VocabularyRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Get a vocabulary's options and terms, ordered by category and name
+    pub async fn get(&self, user_id: Uuid, kind: VocabularyKind) -> Result<Vocabulary> {
+        let mut conn = self.pool.acquire().await?;
+        let strict = Self::is_strict(&mut conn, user_id, kind).await?;
+
+        let query = Self::term_query(kind, "ORDER BY v.category NULLS LAST, LOWER(v.name)");
+        let terms = sqlx::query_as::<_, VocabularyTerm>(&query)
+            .bind(user_id)
+            .bind(kind.as_str())
+            .fetch_all(&mut *conn)
+            .await?;
+
+        Ok(Vocabulary { kind, strict, terms })
+    }
+
+    /// Turn strict mode of a vocabulary on or off
+    pub async fn set_strict(&self, user_id: Uuid, kind: VocabularyKind, strict: bool) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO vocabulary_settings (user_id, kind, strict)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (user_id, kind) DO UPDATE SET strict = EXCLUDED.strict
+            "#,
+        )
+        .bind(user_id)
+        .bind(kind.as_str())
+        .bind(strict)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Values used on the user's trades, most frequent first, with the term
+    /// each is registered as
+    pub async fn values(&self, user_id: Uuid, kind: VocabularyKind) -> Result<Vec<VocabularyValue>> {
+        let query = format!(
+            r#"
+            SELECT
+                value,
+                COUNT(DISTINCT t.id) AS trade_count,
+                v.id AS term_id
+            FROM trades t
+            CROSS JOIN LATERAL UNNEST(t.{column}) AS value
+            LEFT JOIN vocabulary_terms v ON v.user_id = t.user_id AND v.kind = $2 AND v.name = value
+            WHERE t.user_id = $1
+            GROUP BY value, v.id
+            ORDER BY trade_count DESC, value
+            "#,
+            column = kind.as_str(),
+        );
+
+        let values = sqlx::query_as::<_, VocabularyValue>(&query)
+            .bind(user_id)
+            .bind(kind.as_str())
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(values)
+    }
+
+    /// Add a term
+    pub async fn create_term(
+        &self,
+        user_id: Uuid,
+        kind: VocabularyKind,
+        req: CreateVocabularyTermRequest,
+    ) -> Result<VocabularyTerm> {
+        let mut conn = self.pool.acquire().await?;
+
+        let term_id: Uuid = sqlx::query_scalar(
+            r#"
+            INSERT INTO vocabulary_terms (user_id, kind, name, color, category)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING id
+            "#,
+        )
+        .bind(user_id)
+        .bind(kind.as_str())
+        .bind(&req.name)
+        .bind(&req.color)
+        .bind(&req.category)
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(|e| duplicate_name(e, kind, &req.name))?;
+
+        Self::term_with(&mut conn, user_id, kind, term_id).await
+    }
+
+    /// Update a term. Renaming rewrites the value on every trade carrying
+    /// it, in the same transaction.
+    pub async fn update_term(
+        &self,
+        user_id: Uuid,
+        kind: VocabularyKind,
+        term_id: Uuid,
+        req: UpdateVocabularyTermRequest,
+    ) -> Result<VocabularyTerm> {
+        let mut tx = self.pool.begin().await?;
+
+        let previous: String = sqlx::query_scalar(
+            r#"
+            SELECT name FROM vocabulary_terms WHERE id = $1 AND user_id = $2 AND kind = $3 FOR UPDATE
+            "#,
+        )
+        .bind(term_id)
+        .bind(user_id)
+        .bind(kind.as_str())
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or(AppError::ValidationError("Term not found".to_string()))?;
+
+        sqlx::query(
+            r#"
+            UPDATE vocabulary_terms SET
+                name = COALESCE($2, name),
+                color = COALESCE($3, color),
+                category = COALESCE($4, category),
+                updated_at = NOW()
+            WHERE id = $1
+            "#,
+        )
+        .bind(term_id)
+        .bind(&req.name)
+        .bind(&req.color)
+        .bind(&req.category)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| duplicate_name(e, kind, req.name.as_deref().unwrap_or_default()))?;
+
+        if let Some(name) = req.name.as_deref().filter(|name| *name != previous) {
+            Self::rewrite(&mut tx, user_id, kind, &[previous], name).await?;
+        }
+
+        let term = Self::term_with(&mut tx, user_id, kind, term_id).await?;
+
+        tx.commit().await?;
+
+        Ok(term)
+    }
+
+    /// Delete a term; trades keep the value
+    pub async fn delete_term(&self, user_id: Uuid, kind: VocabularyKind, term_id: Uuid) -> Result<()> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM vocabulary_terms WHERE id = $1 AND user_id = $2 AND kind = $3
+            "#,
+        )
+        .bind(term_id)
+        .bind(user_id)
+        .bind(kind.as_str())
+        .execute(&self.pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::ValidationError("Term not found".to_string()));
+        }
+
+        Ok(())
+    }
+
+    /// Replace values with one term on every trade and drop the terms of
+    /// the replaced values, all in one transaction. The target term is
+    /// created when the vocabulary doesn't have it yet.
+    pub async fn merge(&self, user_id: Uuid, kind: VocabularyKind, req: MergeVocabularyRequest) -> Result<MergeReport> {
+        let mut tx = self.pool.begin().await?;
+
+        let term_id: Uuid = sqlx::query_scalar(
+            r#"
+            INSERT INTO vocabulary_terms (user_id, kind, name)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (user_id, kind, LOWER(name)) DO UPDATE SET updated_at = NOW()
+            RETURNING id
+            "#,
+        )
+        .bind(user_id)
+        .bind(kind.as_str())
+        .bind(&req.into)
+        .fetch_one(&mut *tx)
+        .await?;
+        let term = Self::term_with(&mut tx, user_id, kind, term_id).await?;
+
+        let from: Vec<String> = req.from.into_iter().filter(|value| *value != term.name).collect();
+        let trades_updated = Self::rewrite(&mut tx, user_id, kind, &from, &term.name).await?;
+
+        let terms_removed = sqlx::query(
+            r#"
+            DELETE FROM vocabulary_terms
+            WHERE user_id = $1 AND kind = $2 AND name = ANY($3) AND id <> $4
+            "#,
+        )
+        .bind(user_id)
+        .bind(kind.as_str())
+        .bind(&from)
+        .bind(term_id)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+        let term = Self::term_with(&mut tx, user_id, kind, term_id).await?;
+
+        tx.commit().await?;
+
+        Ok(MergeReport {
+            term,
+            trades_updated,
+            terms_removed,
+        })
+    }
+
+    /// Bring trade values in line with a vocabulary: values matching a term
+    /// ignoring case and surrounding spaces take its spelling, and repeats
+    /// are dropped. In strict mode any other value is rejected.
+    pub(crate) async fn normalize_with(
+        conn: &mut PgConnection,
+        user_id: Uuid,
+        kind: VocabularyKind,
+        values: Vec<String>,
+    ) -> Result<Vec<String>> {
+        if values.is_empty() {
+            return Ok(values);
+        }
+
+        let strict = Self::is_strict(conn, user_id, kind).await?;
+        let terms: Vec<String> = sqlx::query_scalar(
+            r#"
+            SELECT name FROM vocabulary_terms WHERE user_id = $1 AND kind = $2
+            "#,
+        )
+        .bind(user_id)
+        .bind(kind.as_str())
+        .fetch_all(&mut *conn)
+        .await?;
+
+        let mut normalized: Vec<String> = Vec::with_capacity(values.len());
+        for value in values {
+            let trimmed = value.trim();
+            let value = match terms.iter().find(|term| term.to_lowercase() == trimmed.to_lowercase()) {
+                Some(term) => term.clone(),
+                None if strict => {
+                    return Err(AppError::ValidationError(format!(
+                        "Unknown {} '{}'; add it to your {} first",
+                        kind.singular(),
+                        trimmed,
+                        kind.as_str()
+                    )));
+                }
+                None => value,
+            };
+            if !normalized.contains(&value) {
+                normalized.push(value);
+            }
+        }
+
+        Ok(normalized)
+    }
+
+    async fn is_strict(conn: &mut PgConnection, user_id: Uuid, kind: VocabularyKind) -> Result<bool> {
+        let strict: Option<bool> = sqlx::query_scalar(
+            r#"
+            SELECT strict FROM vocabulary_settings WHERE user_id = $1 AND kind = $2
+            "#,
+        )
+        .bind(user_id)
+        .bind(kind.as_str())
+        .fetch_optional(conn)
+        .await?;
+
+        Ok(strict.unwrap_or(false))
+    }
+
+    async fn term_with(
+        conn: &mut PgConnection,
+        user_id: Uuid,
+        kind: VocabularyKind,
+        term_id: Uuid,
+    ) -> Result<VocabularyTerm> {
+        let query = Self::term_query(kind, "AND v.id = $3");
+        let term = sqlx::query_as::<_, VocabularyTerm>(&query)
+            .bind(user_id)
+            .bind(kind.as_str())
+            .bind(term_id)
+            .fetch_optional(conn)
+            .await?
+            .ok_or(AppError::ValidationError("Term not found".to_string()))?;
+
+        Ok(term)
+    }
+
+    /// Terms of a vocabulary with their trade counts; `$1` is the user ID
+    /// and `$2` the kind
+    fn term_query(kind: VocabularyKind, rest: &str) -> String {
+        format!(
+            r#"
+            SELECT
+                v.*,
+                (SELECT COUNT(*) FROM trades t WHERE t.user_id = v.user_id AND t.{column} @> ARRAY[v.name::TEXT])
+                    AS trade_count
+            FROM vocabulary_terms v
+            WHERE v.user_id = $1 AND v.kind = $2
+            {rest}
+            "#,
+            column = kind.as_str(),
+        )
+    }
+
+    /// Replace values on every trade of the user, keeping the first
+    /// occurrence of each resulting value, and record the change in each
+    /// trade's history. Returns the trades changed.
+    async fn rewrite(
+        conn: &mut PgConnection,
+        user_id: Uuid,
+        kind: VocabularyKind,
+        from: &[String],
+        into: &str,
+    ) -> Result<u64> {
+        if from.is_empty() {
+            return Ok(0);
+        }
+
+        let before = sqlx::query_as::<_, Trade>(&format!(
+            r#"
+            SELECT * FROM trades WHERE user_id = $1 AND {column} && $2 FOR UPDATE
+            "#,
+            column = kind.as_str(),
+        ))
+        .bind(user_id)
+        .bind(from)
+        .fetch_all(&mut *conn)
+        .await?;
+        let ids: Vec<Uuid> = before.iter().map(|trade| trade.id).collect();
+
+        let query = format!(
+            r#"
+            UPDATE trades SET
+                {column} = ARRAY(
+                    SELECT value FROM (
+                        SELECT DISTINCT ON (value) value, position
+                        FROM (
+                            SELECT CASE WHEN old = ANY($2) THEN $3 ELSE old END AS value, position
+                            FROM UNNEST({column}) WITH ORDINALITY AS u(old, position)
+                        ) replaced
+                        ORDER BY value, position
+                    ) kept
+                    ORDER BY position
+                ),
+                updated_at = NOW()
+            WHERE id = ANY($1)
+            RETURNING *
+            "#,
+            column = kind.as_str(),
+        );
+
+        let after = sqlx::query_as::<_, Trade>(&query)
+            .bind(&ids)
+            .bind(from)
+            .bind(into)
+            .fetch_all(&mut *conn)
+            .await?;
+
+        TradeRevisionRepository::record_updates_with(conn, &before, &after).await?;
+
+        Ok(after.len() as u64)
+    }
+}
+
+fn duplicate_name(e: sqlx::Error, kind: VocabularyKind, name: &str) -> AppError {
+    match e {
+        sqlx::Error::Database(db_err) if db_err.is_unique_violation() => AppError::ValidationError(format!(
+            "'{}' is already one of your {}; merge the values instead",
+            name,
+            kind.as_str()
+        )),
+        _ => AppError::DatabaseError(e),
+    }
+}
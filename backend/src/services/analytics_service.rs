@@ -1,7 +1,14 @@
-use crate::{error::Result, models::Trade};
-use rust_decimal::Decimal;
+use crate::{
+    error::{AppError, Result},
+    models::{Account, CashMovement, FxRateUsed, Quote, Trade},
+};
+use chrono::{DateTime, Datelike, NaiveDate, NaiveTime, Timelike, Utc, Weekday};
+use chrono_tz::Tz;
+use rust_decimal::{prelude::ToPrimitive, Decimal};
 use serde::Serialize;
-use std::collections::HashMap;
+use sqlx::FromRow;
+use std::collections::{BTreeMap, HashMap};
+use uuid::Uuid;
 
 #[derive(Debug, Serialize)]
 pub struct TradeAnalytics {
@@ -27,6 +34,21 @@ pub struct TradeAnalytics {
     pub current_streak: i32,
     pub longest_win_streak: i32,
     pub longest_loss_streak: i32,
+
+    // Open Positions
+    /// Open trades, marked to market through the price source
+    pub open_positions: i32,
+    /// Open trades without a price; the unrealized P&L leaves them out
+    pub unmarked_positions: i32,
+    pub unrealized_pnl: Decimal,
+    pub open_risk: Decimal,
+    
+    // Currency
+    /// Reporting currency amounts were converted into, if one was chosen
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub currency: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub fx_rates_used: Vec<FxRateUsed>,
 }
 
 #[derive(Debug, Serialize)]
@@ -37,6 +59,8 @@ pub struct SymbolPerformance {
     pub win_rate: f64,
     pub total_pnl: Decimal,
     pub average_pnl: Decimal,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub fx_rates_used: Vec<FxRateUsed>,
 }
 
 #[derive(Debug, Serialize)]
@@ -47,6 +71,8 @@ pub struct SetupPerformance {
     pub win_rate: f64,
     pub total_pnl: Decimal,
     pub average_pnl: Decimal,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub fx_rates_used: Vec<FxRateUsed>,
 }
 
 #[derive(Debug, Serialize)]
@@ -55,86 +81,46 @@ pub struct MistakeAnalysis {
     pub count: i32,
     pub average_pnl: Decimal,
     pub total_pnl: Decimal,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub fx_rates_used: Vec<FxRateUsed>,
 }
 
-pub struct AnalyticsService;
-
-impl AnalyticsService {
-    /// Calculate overall trade analytics
-    pub fn calculate_overview(trades: &[Trade]) -> Result<TradeAnalytics> {
-        let total_trades = trades.len() as i32;
-        
-        if total_trades == 0 {
-            return Ok(TradeAnalytics {
-                total_trades: 0,
-                winning_trades: 0,
-                losing_trades: 0,
-                win_rate: 0.0,
-                total_pnl: Decimal::ZERO,
-                total_pnl_percentage: 0.0,
-                average_win: Decimal::ZERO,
-                average_loss: Decimal::ZERO,
-                largest_win: Decimal::ZERO,
-                largest_loss: Decimal::ZERO,
-                profit_factor: 0.0,
-                risk_reward_ratio: 0.0,
-                current_streak: 0,
-                longest_win_streak: 0,
-                longest_loss_streak: 0,
-            });
-        }
-
-        let mut winning_trades = 0;
-        let mut losing_trades = 0;
-        let mut total_pnl = Decimal::ZERO;
-        let mut total_wins = Decimal::ZERO;
-        let mut total_losses = Decimal::ZERO;
-        let mut largest_win = Decimal::ZERO;
-        let mut largest_loss = Decimal::ZERO;
-        
-        let mut current_streak = 0;
-        let mut longest_win_streak = 0;
-        let mut longest_loss_streak = 0;
-        let mut temp_win_streak = 0;
-        let mut temp_loss_streak = 0;
-
-        for trade in trades {
-            if let Some(pnl) = trade.pnl {
-                total_pnl += pnl;
-                
-                if pnl > Decimal::ZERO {
-                    winning_trades += 1;
-                    total_wins += pnl;
-                    if pnl > largest_win {
-                        largest_win = pnl;
-                    }
-                    
-                    temp_win_streak += 1;
-                    temp_loss_streak = 0;
-                    if temp_win_streak > longest_win_streak {
-                        longest_win_streak = temp_win_streak;
-                    }
-                } else if pnl < Decimal::ZERO {
-                    losing_trades += 1;
-                    total_losses += pnl.abs();
-                    if pnl < largest_loss {
-                        largest_loss = pnl;
-                    }
-                    
-                    temp_loss_streak += 1;
-                    temp_win_streak = 0;
-                    if temp_loss_streak > longest_loss_streak {
-                        longest_loss_streak = temp_loss_streak;
-                    }
-                }
-            }
-        }
+/// Raw sums behind `TradeAnalytics`
+#[derive(Debug, Default, Clone, FromRow)]
+pub struct OverviewTotals {
+    /// Capital the P&L percentage is measured against; zero leaves the
+    /// percentage at zero
+    #[sqlx(default)]
+    pub capital: Decimal,
+    pub total_trades: i32,
+    pub winning_trades: i32,
+    pub losing_trades: i32,
+    pub total_pnl: Decimal,
+    pub total_wins: Decimal,
+    pub total_losses: Decimal,
+    pub largest_win: Decimal,
+    pub largest_loss: Decimal,
+    pub current_streak: i32,
+    pub longest_win_streak: i32,
+    pub longest_loss_streak: i32,
+}
 
-        current_streak = if temp_win_streak > 0 {
-            temp_win_streak
-        } else {
-            -temp_loss_streak
-        };
+impl From<OverviewTotals> for TradeAnalytics {
+    fn from(totals: OverviewTotals) -> Self {
+        let OverviewTotals {
+            capital,
+            total_trades,
+            winning_trades,
+            losing_trades,
+            total_pnl,
+            total_wins,
+            total_losses,
+            largest_win,
+            largest_loss,
+            current_streak,
+            longest_win_streak,
+            longest_loss_streak,
+        } = totals;
 
         let win_rate = if total_trades > 0 {
             (winning_trades as f64 / total_trades as f64) * 100.0
@@ -168,13 +154,19 @@ impl AnalyticsService {
             0.0
         };
 
-        Ok(TradeAnalytics {
+        let total_pnl_percentage = if capital > Decimal::ZERO {
+            decimal_to_f64(total_pnl / capital * Decimal::from(100))
+        } else {
+            0.0
+        };
+
+        TradeAnalytics {
             total_trades,
             winning_trades,
             losing_trades,
             win_rate,
             total_pnl,
-            total_pnl_percentage: 0.0, // TODO: Calculate based on account size
+            total_pnl_percentage,
             average_win,
             average_loss,
             largest_win,
@@ -184,150 +176,2000 @@ impl AnalyticsService {
             current_streak,
             longest_win_streak,
             longest_loss_streak,
-        })
+            open_positions: 0,
+            unmarked_positions: 0,
+            unrealized_pnl: Decimal::ZERO,
+            open_risk: Decimal::ZERO,
+            currency: None,
+            fx_rates_used: Vec::new(),
+        }
     }
+}
 
-    /// Calculate performance by symbol
-    pub fn calculate_by_symbol(trades: &[Trade]) -> Result<Vec<SymbolPerformance>> {
-        let mut symbol_map: HashMap<String, Vec<&Trade>> = HashMap::new();
+/// Per-group sums behind the symbol and setup breakdowns
+#[derive(Debug, Clone, FromRow)]
+pub struct GroupTotals {
+    pub key: String,
+    pub total_trades: i32,
+    pub winning_trades: i32,
+    pub total_pnl: Decimal,
+}
 
-        for trade in trades {
-            symbol_map.entry(trade.symbol.clone()).or_insert_with(Vec::new).push(trade);
-        }
-
-        let mut results: Vec<SymbolPerformance> = symbol_map
-            .into_iter()
-            .map(|(symbol, trades)| {
-                let total_trades = trades.len() as i32;
-                let mut winning_trades = 0;
-                let mut total_pnl = Decimal::ZERO;
-
-                for trade in &trades {
-                    if let Some(pnl) = trade.pnl {
-                        total_pnl += pnl;
-                        if pnl > Decimal::ZERO {
-                            winning_trades += 1;
-                        }
-                    }
-                }
+impl GroupTotals {
+    fn win_rate(&self) -> f64 {
+        if self.total_trades > 0 {
+            (self.winning_trades as f64 / self.total_trades as f64) * 100.0
+        } else {
+            0.0
+        }
+    }
 
-                let win_rate = if total_trades > 0 {
-                    (winning_trades as f64 / total_trades as f64) * 100.0
-                } else {
-                    0.0
-                };
+    fn average_pnl(&self) -> Decimal {
+        if self.total_trades > 0 {
+            self.total_pnl / Decimal::from(self.total_trades)
+        } else {
+            Decimal::ZERO
+        }
+    }
+}
 
-                let average_pnl = if total_trades > 0 {
-                    total_pnl / Decimal::from(total_trades)
-                } else {
-                    Decimal::ZERO
-                };
+impl From<GroupTotals> for SymbolPerformance {
+    fn from(totals: GroupTotals) -> Self {
+        SymbolPerformance {
+            win_rate: totals.win_rate(),
+            average_pnl: totals.average_pnl(),
+            symbol: totals.key,
+            total_trades: totals.total_trades,
+            winning_trades: totals.winning_trades,
+            total_pnl: totals.total_pnl,
+            fx_rates_used: Vec::new(),
+        }
+    }
+}
 
-                SymbolPerformance {
-                    symbol,
-                    total_trades,
-                    winning_trades,
-                    win_rate,
-                    total_pnl,
-                    average_pnl,
-                }
-            })
-            .collect();
+impl From<GroupTotals> for SetupPerformance {
+    fn from(totals: GroupTotals) -> Self {
+        SetupPerformance {
+            win_rate: totals.win_rate(),
+            average_pnl: totals.average_pnl(),
+            setup_type: totals.key,
+            total_trades: totals.total_trades,
+            winning_trades: totals.winning_trades,
+            total_pnl: totals.total_pnl,
+            fx_rates_used: Vec::new(),
+        }
+    }
+}
 
-        results.sort_by(|a, b| b.win_rate.partial_cmp(&a.win_rate).unwrap());
+/// Per-mistake sums behind the mistake analysis
+#[derive(Debug, Clone, FromRow)]
+pub struct MistakeTotals {
+    pub mistake: String,
+    pub count: i32,
+    pub total_pnl: Decimal,
+}
 
-        Ok(results)
+impl From<MistakeTotals> for MistakeAnalysis {
+    fn from(totals: MistakeTotals) -> Self {
+        let average_pnl = if totals.count > 0 {
+            totals.total_pnl / Decimal::from(totals.count)
+        } else {
+            Decimal::ZERO
+        };
+
+        MistakeAnalysis {
+            mistake: totals.mistake,
+            count: totals.count,
+            average_pnl,
+            total_pnl: totals.total_pnl,
+            fx_rates_used: Vec::new(),
+        }
     }
+}
 
-    /// Calculate performance by setup type
-    pub fn calculate_by_setup(trades: &[Trade]) -> Result<Vec<SetupPerformance>> {
-        let mut setup_map: HashMap<String, Vec<&Trade>> = HashMap::new();
+/// Spacing of equity curve points
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EquityInterval {
+    Trade,
+    Day,
+    Week,
+    Month,
+}
 
-        for trade in trades {
-            if let Some(setup) = &trade.setup_type {
-                setup_map.entry(setup.clone()).or_insert_with(Vec::new).push(trade);
-            }
+impl EquityInterval {
+    /// `date_trunc` unit for calendar buckets; `None` for one point per trade
+    pub fn date_trunc_unit(&self) -> Option<&'static str> {
+        match self {
+            EquityInterval::Trade => None,
+            EquityInterval::Day => Some("day"),
+            EquityInterval::Week => Some("week"),
+            EquityInterval::Month => Some("month"),
         }
+    }
+}
 
-        let mut results: Vec<SetupPerformance> = setup_map
-            .into_iter()
-            .map(|(setup_type, trades)| {
-                let total_trades = trades.len() as i32;
-                let mut winning_trades = 0;
-                let mut total_pnl = Decimal::ZERO;
+impl TryFrom<&str> for EquityInterval {
+    type Error = AppError;
 
-                for trade in &trades {
-                    if let Some(pnl) = trade.pnl {
-                        total_pnl += pnl;
-                        if pnl > Decimal::ZERO {
-                            winning_trades += 1;
-                        }
-                    }
-                }
+    fn try_from(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "trade" => Ok(EquityInterval::Trade),
+            "day" => Ok(EquityInterval::Day),
+            "week" => Ok(EquityInterval::Week),
+            "month" => Ok(EquityInterval::Month),
+            _ => Err(AppError::ValidationError(
+                "Interval must be 'trade', 'day', 'week' or 'month'".to_string(),
+            )),
+        }
+    }
+}
 
-                let win_rate = if total_trades > 0 {
-                    (winning_trades as f64 / total_trades as f64) * 100.0
-                } else {
-                    0.0
-                };
+/// P&L realized by one trade or within one calendar bucket
+#[derive(Debug, Clone, FromRow)]
+pub struct EquityBucket {
+    /// Exit time of the trade, or start of the bucket
+    pub time: DateTime<Utc>,
+    pub trade_id: Option<Uuid>,
+    pub symbol: Option<String>,
+    pub trades: i32,
+    pub pnl: Decimal,
+}
 
-                let average_pnl = if total_trades > 0 {
-                    total_pnl / Decimal::from(total_trades)
-                } else {
-                    Decimal::ZERO
-                };
+#[derive(Debug, Serialize)]
+pub struct EquityPoint {
+    pub time: DateTime<Utc>,
+    /// `time` as a calendar date in the requested timezone
+    pub date: NaiveDate,
+    pub trade_id: Option<Uuid>,
+    pub symbol: Option<String>,
+    pub trades: i32,
+    pub pnl: Decimal,
+    pub equity: Decimal,
+    pub peak: Decimal,
+    pub drawdown: Decimal,
+    pub drawdown_percentage: Option<f64>,
+}
 
-                SetupPerformance {
-                    setup_type,
-                    total_trades,
-                    winning_trades,
-                    win_rate,
-                    total_pnl,
-                    average_pnl,
-                }
-            })
-            .collect();
+/// One stretch of the curve below its previous peak
+#[derive(Debug, Clone, Serialize)]
+pub struct DrawdownPeriod {
+    pub amount: Decimal,
+    pub percentage: Option<f64>,
+    pub peak_equity: Decimal,
+    pub start: DateTime<Utc>,
+    pub trough: DateTime<Utc>,
+    pub recovery: Option<DateTime<Utc>>,
+    /// Start to recovery, or to the last point while still under water
+    pub duration_days: f64,
+}
 
-        results.sort_by(|a, b| b.win_rate.partial_cmp(&a.win_rate).unwrap());
+#[derive(Debug, Default, Serialize)]
+pub struct TimeUnderWater {
+    pub periods: i32,
+    pub total_days: f64,
+    pub longest_days: f64,
+    pub current_days: f64,
+}
 
-        Ok(results)
+#[derive(Debug, Serialize)]
+pub struct EquityCurve {
+    pub interval: EquityInterval,
+    pub timezone: String,
+    pub starting_equity: Decimal,
+    pub ending_equity: Decimal,
+    pub points: Vec<EquityPoint>,
+    pub max_drawdown: Option<DrawdownPeriod>,
+    pub time_under_water: TimeUnderWater,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub currency: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub fx_rates_used: Vec<FxRateUsed>,
+}
+
+/// Risk-adjusted performance.
+///
+/// Ratios that would divide by zero (no losses, no variance, no drawdown)
+/// are `None` rather than a sentinel value.
+#[derive(Debug, Serialize)]
+pub struct RiskMetrics {
+    pub total_trades: i32,
+    pub trading_days: i32,
+    /// Whether daily returns are currency amounts ("pnl") or fractions of
+    /// equity ("equity", when a starting equity is given)
+    pub returns_basis: &'static str,
+    /// Mean P&L per trade
+    pub expectancy: Decimal,
+    /// Sample standard deviation of P&L per trade
+    pub pnl_std_dev: f64,
+    /// Average win / average loss
+    pub payoff_ratio: Option<f64>,
+    /// `W - (1 - W) / R`, with W the win rate and R the payoff ratio
+    pub kelly_fraction: Option<f64>,
+    /// `sqrt(min(N, 100)) * expectancy / pnl_std_dev`
+    pub sqn: Option<f64>,
+    /// `mean(daily) / stdev(daily) * sqrt(252)`, risk-free rate of zero
+    pub sharpe_ratio: Option<f64>,
+    /// `mean(daily) / downside deviation * sqrt(252)`, where the downside
+    /// deviation is `sqrt(sum(min(r, 0)^2) / days)`
+    pub sortino_ratio: Option<f64>,
+    /// `mean(daily) * 252 / max drawdown`, both on the returns basis
+    pub calmar_ratio: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub currency: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub fx_rates_used: Vec<FxRateUsed>,
+}
+
+/// Raw sums over closed trades that have an R multiple
+#[derive(Debug, Default, Clone, FromRow)]
+pub struct RMultipleTotals {
+    pub total_trades: i32,
+    pub winning_trades: i32,
+    pub losing_trades: i32,
+    pub total_r: Decimal,
+    pub total_win_r: Decimal,
+    pub total_loss_r: Decimal,
+    pub planned_trades: i32,
+    pub total_planned_r: Decimal,
+}
+
+/// Histogram bucket covering `[from, to)` in R
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct RBucket {
+    pub from: Decimal,
+    pub to: Decimal,
+    pub count: i32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RMultipleAnalytics {
+    pub total_trades: i32,
+    pub total_r: Decimal,
+    /// Mean R per trade, i.e. expectancy in R
+    pub expectancy_r: Decimal,
+    pub average_win_r: Decimal,
+    pub average_loss_r: Decimal,
+    /// Mean planned R over trades with a target
+    pub average_planned_r: Option<Decimal>,
+    pub distribution: Vec<RBucket>,
+}
+
+impl RMultipleAnalytics {
+    pub fn new(totals: RMultipleTotals, distribution: Vec<RBucket>) -> Self {
+        let average = |sum: Decimal, count: i32| {
+            if count > 0 {
+                sum / Decimal::from(count)
+            } else {
+                Decimal::ZERO
+            }
+        };
+
+        RMultipleAnalytics {
+            total_trades: totals.total_trades,
+            total_r: totals.total_r,
+            expectancy_r: average(totals.total_r, totals.total_trades),
+            average_win_r: average(totals.total_win_r, totals.winning_trades),
+            average_loss_r: average(totals.total_loss_r, totals.losing_trades),
+            average_planned_r: (totals.planned_trades > 0)
+                .then(|| average(totals.total_planned_r, totals.planned_trades)),
+            distribution,
+        }
     }
+}
 
-    /// Analyze mistakes and their impact
-    pub fn analyze_mistakes(trades: &[Trade]) -> Result<Vec<MistakeAnalysis>> {
-        let mut mistake_map: HashMap<String, Vec<Decimal>> = HashMap::new();
+/// Excursions of one closed trade
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct ExcursionPoint {
+    pub trade_id: Uuid,
+    pub symbol: String,
+    pub direction: String,
+    pub exit_time: Option<DateTime<Utc>>,
+    pub pnl: Decimal,
+    pub r_multiple: Option<Decimal>,
+    /// Price distances per unit
+    pub mae: Decimal,
+    pub mfe: Decimal,
+    /// Excursions in currency, comparable to `pnl`
+    pub mae_amount: Decimal,
+    pub mfe_amount: Decimal,
+    pub entry_efficiency: Option<Decimal>,
+    pub exit_efficiency: Option<Decimal>,
+}
 
-        for trade in trades {
-            for mistake in &trade.mistakes {
-                if let Some(pnl) = trade.pnl {
-                    mistake_map.entry(mistake.clone()).or_insert_with(Vec::new).push(pnl);
-                }
+#[derive(Debug, Serialize)]
+pub struct ExcursionAnalytics {
+    pub total_trades: i32,
+    pub average_mae_amount: Decimal,
+    pub average_mfe_amount: Decimal,
+    pub average_entry_efficiency: Option<Decimal>,
+    pub average_exit_efficiency: Option<Decimal>,
+    pub points: Vec<ExcursionPoint>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub currency: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub fx_rates_used: Vec<FxRateUsed>,
+}
+
+impl ExcursionAnalytics {
+    pub fn new(points: Vec<ExcursionPoint>) -> Self {
+        let average = |values: Vec<Decimal>| {
+            (!values.is_empty()).then(|| values.iter().sum::<Decimal>() / Decimal::from(values.len()))
+        };
+
+        ExcursionAnalytics {
+            total_trades: points.len() as i32,
+            average_mae_amount: average(points.iter().map(|p| p.mae_amount).collect()).unwrap_or_default(),
+            average_mfe_amount: average(points.iter().map(|p| p.mfe_amount).collect()).unwrap_or_default(),
+            average_entry_efficiency: average(points.iter().filter_map(|p| p.entry_efficiency).collect()),
+            average_exit_efficiency: average(points.iter().filter_map(|p| p.exit_efficiency).collect()),
+            points,
+            currency: None,
+            fx_rates_used: Vec::new(),
+        }
+    }
+}
+
+/// One day of account activity
+#[derive(Debug, Serialize)]
+pub struct AccountEquityPoint {
+    pub date: NaiveDate,
+    /// Realized trading P&L
+    pub pnl: Decimal,
+    /// Interest less fees
+    pub income: Decimal,
+    /// Deposits less withdrawals
+    pub net_flow: Decimal,
+    /// Balance at the end of the day
+    pub equity: Decimal,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AccountPerformance {
+    pub account_id: Uuid,
+    pub name: String,
+    pub base_currency: String,
+    pub starting_balance: Decimal,
+    pub deposits: Decimal,
+    pub withdrawals: Decimal,
+    pub fees: Decimal,
+    pub interest: Decimal,
+    pub realized_pnl: Decimal,
+    pub equity: Decimal,
+    /// Gain over starting balance plus deposits, in percent
+    pub return_percentage: f64,
+    /// Daily returns chain-linked so deposits and withdrawals don't count
+    /// as performance, in percent
+    pub time_weighted_return: Option<f64>,
+    /// Modified Dietz return, weighting each flow by the share of the
+    /// period it was invested, in percent
+    pub money_weighted_return: Option<f64>,
+    pub equity_curve: Vec<AccountEquityPoint>,
+    /// Rates that converted trades in other currencies into the base currency
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub fx_rates_used: Vec<FxRateUsed>,
+}
+
+/// One journal day with the P&L of its linked closed trades
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct JournalDay {
+    pub entry_id: Uuid,
+    pub date: NaiveDate,
+    pub plan_followed: Option<bool>,
+    pub mood: Option<i16>,
+    pub energy: Option<i16>,
+    pub trades: i32,
+    /// None when no closed trade is linked to the day
+    pub pnl: Option<Decimal>,
+}
+
+/// Journal days sharing plan adherence or a mood or energy score
+#[derive(Debug, Default, Serialize)]
+pub struct JournalGroup {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub score: Option<i16>,
+    pub days: i32,
+    /// Days with closed trades; only these count towards the P&L figures
+    pub trading_days: i32,
+    pub winning_days: i32,
+    pub total_trades: i32,
+    pub total_pnl: Decimal,
+    pub average_daily_pnl: Option<Decimal>,
+    /// Share of trading days in profit, in percent
+    pub win_rate: f64,
+}
+
+impl JournalGroup {
+    fn add(&mut self, day: &JournalDay) {
+        self.days += 1;
+        self.total_trades += day.trades;
+        if let Some(pnl) = day.pnl {
+            self.trading_days += 1;
+            self.total_pnl += pnl;
+            if pnl > Decimal::ZERO {
+                self.winning_days += 1;
             }
         }
+        if self.trading_days > 0 {
+            self.average_daily_pnl = Some(self.total_pnl / Decimal::from(self.trading_days));
+            self.win_rate = self.winning_days as f64 / self.trading_days as f64 * 100.0;
+        }
+    }
+}
 
-        let mut results: Vec<MistakeAnalysis> = mistake_map
-            .into_iter()
-            .map(|(mistake, pnls)| {
-                let count = pnls.len() as i32;
-                let total_pnl: Decimal = pnls.iter().sum();
-                let average_pnl = if count > 0 {
-                    total_pnl / Decimal::from(count)
-                } else {
-                    Decimal::ZERO
-                };
+#[derive(Debug, Serialize)]
+pub struct JournalAnalytics {
+    pub plan_followed: JournalGroup,
+    pub plan_not_followed: JournalGroup,
+    pub plan_not_rated: JournalGroup,
+    /// Average daily P&L when the plan was followed less when it wasn't
+    pub plan_followed_edge: Option<Decimal>,
+    /// Correlation of following the plan with daily P&L, from -1 to 1
+    pub plan_pnl_correlation: Option<f64>,
+    pub by_mood: Vec<JournalGroup>,
+    pub by_energy: Vec<JournalGroup>,
+    pub mood_pnl_correlation: Option<f64>,
+    pub energy_pnl_correlation: Option<f64>,
+    pub days: Vec<JournalDay>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub currency: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub fx_rates_used: Vec<FxRateUsed>,
+}
 
-                MistakeAnalysis {
-                    mistake,
-                    count,
-                    average_pnl,
-                    total_pnl,
-                }
-            })
-            .collect();
+/// Closed trade taken from a playbook, with what it met of the checklist
+#[derive(Debug, Clone, FromRow)]
+pub struct PlaybookTrade {
+    pub playbook_id: Uuid,
+    pub name: String,
+    pub checklist: Vec<String>,
+    pub checklist_met: Vec<String>,
+    pub pnl: Decimal,
+}
+
+/// Count, wins and P&L of a group of closed trades, such as those sharing
+/// a playbook and level of checklist compliance
+#[derive(Debug, Default, Serialize)]
+pub struct ComplianceTotals {
+    pub total_trades: i32,
+    pub winning_trades: i32,
+    pub win_rate: f64,
+    pub total_pnl: Decimal,
+    pub average_pnl: Option<Decimal>,
+}
+
+impl ComplianceTotals {
+    fn add(&mut self, pnl: Decimal) {
+        self.total_trades += 1;
+        self.total_pnl += pnl;
+        if pnl > Decimal::ZERO {
+            self.winning_trades += 1;
+        }
+        self.win_rate = self.winning_trades as f64 / self.total_trades as f64 * 100.0;
+        self.average_pnl = Some(self.total_pnl / Decimal::from(self.total_trades));
+    }
+}
 
-        results.sort_by(|a, b| b.count.cmp(&a.count));
+/// Results of trades that met a checklist item against those that missed it
+#[derive(Debug, Serialize)]
+pub struct ChecklistItemPerformance {
+    pub item: String,
+    pub met: ComplianceTotals,
+    pub missed: ComplianceTotals,
+    /// Average P&L when met less when missed
+    pub edge: Option<Decimal>,
+}
 
-        Ok(results)
+#[derive(Debug, Serialize)]
+pub struct PlaybookPerformance {
+    pub playbook_id: Uuid,
+    pub name: String,
+    pub total_trades: i32,
+    pub winning_trades: i32,
+    pub win_rate: f64,
+    pub total_pnl: Decimal,
+    pub average_pnl: Decimal,
+    /// Average share of checklist items met, in percent; None without a
+    /// checklist
+    pub average_compliance: Option<f64>,
+    /// Trades that met every item
+    pub fully_compliant: ComplianceTotals,
+    /// Trades that met some but not all items
+    pub partially_compliant: ComplianceTotals,
+    /// Trades that met no item
+    pub non_compliant: ComplianceTotals,
+    pub items: Vec<ChecklistItemPerformance>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub fx_rates_used: Vec<FxRateUsed>,
+}
+
+/// Trading session used by the time breakdowns, with its hours in the
+/// local time of its market; a session ending before it starts runs past
+/// midnight
+#[derive(Debug, Clone)]
+pub struct TradingSession {
+    pub name: String,
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+    pub timezone: Tz,
+}
+
+impl TradingSession {
+    /// Tokyo, London and New York cash sessions
+    pub fn defaults() -> Vec<TradingSession> {
+        let session = |name: &str, start: (u32, u32), end: (u32, u32), timezone: Tz| TradingSession {
+            name: name.to_string(),
+            start: NaiveTime::from_hms_opt(start.0, start.1, 0).unwrap_or_default(),
+            end: NaiveTime::from_hms_opt(end.0, end.1, 0).unwrap_or_default(),
+            timezone,
+        };
+
+        vec![
+            session("Asia", (9, 0), (15, 0), chrono_tz::Asia::Tokyo),
+            session("London", (8, 0), (16, 30), chrono_tz::Europe::London),
+            session("New York", (9, 30), (16, 0), chrono_tz::America::New_York),
+        ]
+    }
+
+    /// Whether the session is open at `time`
+    pub fn contains(&self, time: DateTime<Utc>) -> bool {
+        let local = time.with_timezone(&self.timezone).time();
+        if self.start <= self.end {
+            self.start <= local && local < self.end
+        } else {
+            local >= self.start || local < self.end
+        }
+    }
+}
+
+/// Closed trade with the times the breakdowns are cut by
+#[derive(Debug, Clone, FromRow)]
+pub struct TimedTrade {
+    pub entry_time: DateTime<Utc>,
+    pub exit_time: Option<DateTime<Utc>>,
+    pub pnl: Decimal,
+}
+
+/// Trades falling into one hour, weekday, month, session or holding time
+#[derive(Debug, Serialize)]
+pub struct TimeBucket {
+    pub bucket: String,
+    #[serde(flatten)]
+    pub totals: ComplianceTotals,
+}
+
+/// Performance of closed trades by when they were entered and how long
+/// they were held
+#[derive(Debug, Serialize)]
+pub struct TimeBreakdowns {
+    /// Timezone hours, weekdays and months are read in
+    pub timezone: String,
+    /// Entry hour, 00:00 to 23:00
+    pub by_hour: Vec<TimeBucket>,
+    /// Entry weekday, Monday to Sunday
+    pub by_weekday: Vec<TimeBucket>,
+    /// Entry month of the year, January to December
+    pub by_month: Vec<TimeBucket>,
+    /// Sessions open at entry; a trade entered while sessions overlap
+    /// counts towards each of them
+    pub by_session: Vec<TimeBucket>,
+    /// Time from entry to exit
+    pub by_holding_time: Vec<TimeBucket>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub currency: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub fx_rates_used: Vec<FxRateUsed>,
+}
+
+const WEEKDAYS: [&str; 7] = ["Monday", "Tuesday", "Wednesday", "Thursday", "Friday", "Saturday", "Sunday"];
+
+const MONTHS: [&str; 12] = [
+    "January", "February", "March", "April", "May", "June", "July", "August", "September", "October", "November",
+    "December",
+];
+
+/// Upper bound of each holding-time bucket in minutes, exclusive
+const HOLDING_TIMES: [(i64, &str); 7] = [
+    (5, "under 5m"),
+    (15, "5m-15m"),
+    (60, "15m-1h"),
+    (240, "1h-4h"),
+    (1_440, "4h-1d"),
+    (10_080, "1d-1w"),
+    (i64::MAX, "over 1w"),
+];
+
+/// Session bucket of trades entered while no session is open
+const OFF_SESSION: &str = "Off-session";
+
+/// Closed trades of one trading day, as summed by the database
+#[derive(Debug, Clone, FromRow)]
+pub struct CalendarBucket {
+    pub date: NaiveDate,
+    pub total_trades: i32,
+    pub winning_trades: i32,
+    pub net_pnl: Decimal,
+    pub fees: Decimal,
+}
+
+/// Result of the closed trades of a day, week or month
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct CalendarTotals {
+    pub total_trades: i32,
+    pub winning_trades: i32,
+    pub win_rate: f64,
+    pub net_pnl: Decimal,
+    /// Fees in the trade currency, or the reporting currency if one was
+    /// chosen
+    pub fees: Decimal,
+}
+
+impl CalendarTotals {
+    fn add(&mut self, other: &CalendarTotals) {
+        self.total_trades += other.total_trades;
+        self.winning_trades += other.winning_trades;
+        self.net_pnl += other.net_pnl;
+        self.fees += other.fees;
+        if self.total_trades > 0 {
+            self.win_rate = self.winning_trades as f64 / self.total_trades as f64 * 100.0;
+        }
+    }
+}
+
+impl From<CalendarBucket> for CalendarTotals {
+    fn from(bucket: CalendarBucket) -> Self {
+        let mut totals = CalendarTotals::default();
+        totals.add(&CalendarTotals {
+            total_trades: bucket.total_trades,
+            winning_trades: bucket.winning_trades,
+            win_rate: 0.0,
+            net_pnl: bucket.net_pnl,
+            fees: bucket.fees,
+        });
+        totals
     }
 }
 
+#[derive(Debug, Serialize)]
+pub struct CalendarDay {
+    pub date: NaiveDate,
+    #[serde(flatten)]
+    pub totals: CalendarTotals,
+    pub has_journal_entry: bool,
+}
+
+/// Monday-to-Sunday week, limited to the days inside the month
+#[derive(Debug, Serialize)]
+pub struct CalendarWeek {
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+    #[serde(flatten)]
+    pub totals: CalendarTotals,
+}
+
+/// Daily results of a month for a heatmap calendar, with weekly and
+/// monthly totals
+#[derive(Debug, Serialize)]
+pub struct CalendarMonth {
+    /// `YYYY-MM`
+    pub month: String,
+    /// Timezone days are cut in
+    pub timezone: String,
+    /// Local hour days end at
+    pub day_rollover_hour: i16,
+    /// Every day of the month, with or without trades
+    pub days: Vec<CalendarDay>,
+    pub weeks: Vec<CalendarWeek>,
+    pub totals: CalendarTotals,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub currency: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub fx_rates_used: Vec<FxRateUsed>,
+}
+
+/// An open trade marked to market.
+///
+/// Prices are in the trade currency; amounts are in the reporting
+/// currency if one was chosen. Amounts are `None` while the symbol has no
+/// price; option trades are not marked.
+#[derive(Debug, Serialize)]
+pub struct OpenPosition {
+    pub trade_id: Uuid,
+    pub symbol: String,
+    pub direction: String,
+    pub open_quantity: Decimal,
+    pub entry_price: Decimal,
+    /// Currency of the prices
+    pub currency: String,
+    pub mark_price: Option<Decimal>,
+    pub marked_at: Option<DateTime<Utc>>,
+    /// Result of closing the open quantity at the mark, before fees
+    pub unrealized_pnl: Option<Decimal>,
+    pub market_value: Option<Decimal>,
+    /// Loss from the mark (or the entry while unmarked) to the stop, or the
+    /// open share of the planned risk without a stop
+    pub open_risk: Option<Decimal>,
+}
+
+/// Open positions of one symbol and direction; option trades count towards
+/// their underlying
+#[derive(Debug, Serialize)]
+pub struct Exposure {
+    pub symbol: String,
+    pub direction: String,
+    pub positions: i32,
+    pub open_quantity: Decimal,
+    /// Market value of the marked positions
+    pub market_value: Decimal,
+    pub unrealized_pnl: Decimal,
+}
+
+/// Open trades marked to market, with exposure and open risk
+#[derive(Debug, Serialize)]
+pub struct OpenPositions {
+    pub positions: Vec<OpenPosition>,
+    pub exposure: Vec<Exposure>,
+    /// Market value of long and short positions added up
+    pub gross_exposure: Decimal,
+    /// Market value of long positions less short positions
+    pub net_exposure: Decimal,
+    pub unrealized_pnl: Decimal,
+    pub open_risk: Decimal,
+    /// Positions without a price
+    pub unmarked: i32,
+    /// Price source the marks came from
+    pub price_source: String,
+    /// Whether the price source failed, leaving every position unmarked
+    pub quotes_unavailable: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub currency: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub fx_rates_used: Vec<FxRateUsed>,
+}
+
+pub struct AnalyticsService;
+
+impl AnalyticsService {
+    /// Build the cumulative P&L curve and its drawdowns.
+    ///
+    /// `buckets` must be in time order. Percent drawdown is relative to the
+    /// running peak and is only reported while that peak is positive. A
+    /// drawdown starts at the last point that set the peak (or at the first
+    /// point when the curve never rose above `starting_equity`) and recovers
+    /// at the first point back at or above that peak.
+    pub fn equity_curve(
+        buckets: Vec<EquityBucket>,
+        interval: EquityInterval,
+        timezone: Tz,
+        starting_equity: Decimal,
+    ) -> EquityCurve {
+        let mut points = Vec::with_capacity(buckets.len());
+        let mut equity = starting_equity;
+        let mut peak = starting_equity;
+        let mut peak_time = None;
+        let mut periods: Vec<DrawdownPeriod> = Vec::new();
+        let mut current: Option<DrawdownPeriod> = None;
+
+        for bucket in buckets {
+            equity += bucket.pnl;
+
+            if equity >= peak {
+                if let Some(mut period) = current.take() {
+                    period.recovery = Some(bucket.time);
+                    period.duration_days = days_between(period.start, bucket.time);
+                    periods.push(period);
+                }
+                peak = equity;
+                peak_time = Some(bucket.time);
+            } else {
+                let drawdown = peak - equity;
+                let period = current.get_or_insert_with(|| DrawdownPeriod {
+                    amount: Decimal::ZERO,
+                    percentage: None,
+                    peak_equity: peak,
+                    start: peak_time.unwrap_or(bucket.time),
+                    trough: bucket.time,
+                    recovery: None,
+                    duration_days: 0.0,
+                });
+                if drawdown > period.amount {
+                    period.amount = drawdown;
+                    period.percentage = drawdown_percentage(drawdown, peak);
+                    period.trough = bucket.time;
+                }
+            }
+
+            let drawdown = peak - equity;
+            points.push(EquityPoint {
+                time: bucket.time,
+                date: bucket.time.with_timezone(&timezone).date_naive(),
+                trade_id: bucket.trade_id,
+                symbol: bucket.symbol,
+                trades: bucket.trades,
+                pnl: bucket.pnl,
+                equity,
+                peak,
+                drawdown,
+                drawdown_percentage: drawdown_percentage(drawdown, peak),
+            });
+        }
+
+        let mut time_under_water = TimeUnderWater::default();
+
+        if let (Some(mut period), Some(last)) = (current, points.last()) {
+            period.duration_days = days_between(period.start, last.time);
+            time_under_water.current_days = period.duration_days;
+            periods.push(period);
+        }
+
+        let mut max_drawdown: Option<DrawdownPeriod> = None;
+        for period in &periods {
+            time_under_water.periods += 1;
+            time_under_water.total_days += period.duration_days;
+            time_under_water.longest_days = time_under_water.longest_days.max(period.duration_days);
+
+            if max_drawdown.as_ref().is_none_or(|max| period.amount > max.amount) {
+                max_drawdown = Some(period.clone());
+            }
+        }
+
+        EquityCurve {
+            interval,
+            timezone: timezone.name().to_string(),
+            starting_equity,
+            ending_equity: equity,
+            points,
+            max_drawdown,
+            time_under_water,
+            currency: None,
+            fx_rates_used: Vec::new(),
+        }
+    }
+
+    /// Compute risk-adjusted metrics.
+    ///
+    /// `trades` holds one bucket per closed trade and `days` one per trading
+    /// day, both in time order. Only days with closed trades count as
+    /// return observations; annualization assumes 252 of them per year.
+    pub fn risk_metrics(
+        trades: &[EquityBucket],
+        days: Vec<EquityBucket>,
+        timezone: Tz,
+        starting_equity: Option<Decimal>,
+    ) -> RiskMetrics {
+        let total_trades = trades.len() as i32;
+        let trade_pnls: Vec<f64> = trades.iter().map(|t| decimal_to_f64(t.pnl)).collect();
+
+        let total_pnl: Decimal = trades.iter().map(|t| t.pnl).sum();
+        let expectancy = if total_trades > 0 {
+            total_pnl / Decimal::from(total_trades)
+        } else {
+            Decimal::ZERO
+        };
+        let pnl_std_dev = sample_std_dev(&trade_pnls).unwrap_or(0.0);
+
+        let wins: Vec<f64> = trade_pnls.iter().copied().filter(|p| *p > 0.0).collect();
+        let losses: Vec<f64> = trade_pnls.iter().copied().filter(|p| *p < 0.0).map(f64::abs).collect();
+
+        let payoff_ratio = match (mean(&wins), mean(&losses)) {
+            (Some(average_win), Some(average_loss)) => Some(average_win / average_loss),
+            _ => None,
+        };
+        let kelly_fraction = payoff_ratio.map(|r| {
+            let w = wins.len() as f64 / trade_pnls.len() as f64;
+            w - (1.0 - w) / r
+        });
+        let sqn = (pnl_std_dev > 0.0).then(|| {
+            (trade_pnls.len().min(100) as f64).sqrt() * decimal_to_f64(expectancy) / pnl_std_dev
+        });
+
+        let trading_days = days.len() as i32;
+        let base = starting_equity.filter(|e| *e > Decimal::ZERO);
+        let curve = Self::equity_curve(days, EquityInterval::Day, timezone, base.unwrap_or_default());
+
+        let daily_returns: Vec<f64> = match base {
+            Some(_) => {
+                let mut previous = curve.starting_equity;
+                curve
+                    .points
+                    .iter()
+                    .map(|p| {
+                        let r = if previous > Decimal::ZERO {
+                            decimal_to_f64(p.pnl / previous)
+                        } else {
+                            0.0
+                        };
+                        previous = p.equity;
+                        r
+                    })
+                    .collect()
+            }
+            None => curve.points.iter().map(|p| decimal_to_f64(p.pnl)).collect(),
+        };
+
+        let annualization = 252f64.sqrt();
+        let mean_daily = mean(&daily_returns);
+
+        let sharpe_ratio = match (mean_daily, sample_std_dev(&daily_returns)) {
+            (Some(m), Some(sd)) if sd > 0.0 => Some(m / sd * annualization),
+            _ => None,
+        };
+
+        let downside = if daily_returns.is_empty() {
+            0.0
+        } else {
+            (daily_returns.iter().map(|r| r.min(0.0).powi(2)).sum::<f64>() / daily_returns.len() as f64).sqrt()
+        };
+        let sortino_ratio = match mean_daily {
+            Some(m) if downside > 0.0 => Some(m / downside * annualization),
+            _ => None,
+        };
+
+        let max_drawdown = curve.max_drawdown.and_then(|d| match base {
+            Some(_) => d.percentage.map(|p| p / 100.0),
+            None => Some(decimal_to_f64(d.amount)),
+        });
+        let calmar_ratio = match (mean_daily, max_drawdown) {
+            (Some(m), Some(dd)) if dd > 0.0 => Some(m * 252.0 / dd),
+            _ => None,
+        };
+
+        RiskMetrics {
+            total_trades,
+            trading_days,
+            returns_basis: if base.is_some() { "equity" } else { "pnl" },
+            expectancy,
+            pnl_std_dev,
+            payoff_ratio,
+            kelly_fraction,
+            sqn,
+            sharpe_ratio,
+            sortino_ratio,
+            calmar_ratio,
+            currency: None,
+            fx_rates_used: Vec::new(),
+        }
+    }
+
+    /// Build balances and returns of one account.
+    ///
+    /// `days` is the account's daily realized P&L. Interest and fees count
+    /// towards the return; deposits and withdrawals are external flows and
+    /// are assumed to arrive at the start of their day.
+    pub fn account_performance(
+        account: &Account,
+        days: Vec<EquityBucket>,
+        movements: &[CashMovement],
+        timezone: Tz,
+    ) -> AccountPerformance {
+        let mut activity: BTreeMap<NaiveDate, (Decimal, Decimal, Decimal)> = BTreeMap::new();
+        let mut deposits = Decimal::ZERO;
+        let mut withdrawals = Decimal::ZERO;
+        let mut fees = Decimal::ZERO;
+        let mut interest = Decimal::ZERO;
+
+        for day in days {
+            let date = day.time.with_timezone(&timezone).date_naive();
+            activity.entry(date).or_default().0 += day.pnl;
+        }
+        for movement in movements {
+            let date = movement.occurred_at.with_timezone(&timezone).date_naive();
+            let entry = activity.entry(date).or_default();
+            entry.1 += movement.income();
+            entry.2 += movement.external_flow();
+
+            match movement.kind.as_str() {
+                "deposit" => deposits += movement.amount,
+                "withdrawal" => withdrawals += movement.amount,
+                "fee" => fees += movement.amount,
+                "interest" => interest += movement.amount,
+                _ => {}
+            }
+        }
+
+        let mut equity = account.starting_balance;
+        let mut growth = 1.0;
+        let mut equity_curve = Vec::with_capacity(activity.len());
+
+        for (date, (pnl, income, net_flow)) in activity {
+            let invested = equity + net_flow;
+            if invested > Decimal::ZERO {
+                growth *= 1.0 + decimal_to_f64((pnl + income) / invested);
+            }
+
+            equity = invested + pnl + income;
+            equity_curve.push(AccountEquityPoint {
+                date,
+                pnl,
+                income,
+                net_flow,
+                equity,
+            });
+        }
+
+        let realized_pnl: Decimal = equity_curve.iter().map(|p| p.pnl).sum();
+        let gain = realized_pnl + interest - fees;
+
+        let contributed = account.starting_balance + deposits;
+        let return_percentage = if contributed > Decimal::ZERO {
+            decimal_to_f64(gain / contributed * Decimal::from(100))
+        } else {
+            0.0
+        };
+
+        let time_weighted_return = (!equity_curve.is_empty()).then_some((growth - 1.0) * 100.0);
+
+        let money_weighted_return = match (equity_curve.first(), equity_curve.last()) {
+            (Some(first), Some(last)) => {
+                let period_days = Decimal::from((last.date - first.date).num_days() + 1);
+                let weighted_capital = account.starting_balance
+                    + equity_curve
+                        .iter()
+                        .map(|p| {
+                            let invested_days = Decimal::from((last.date - p.date).num_days() + 1);
+                            p.net_flow * invested_days / period_days
+                        })
+                        .sum::<Decimal>();
+
+                (weighted_capital > Decimal::ZERO)
+                    .then(|| decimal_to_f64(gain / weighted_capital * Decimal::from(100)))
+            }
+            _ => None,
+        };
+
+        AccountPerformance {
+            account_id: account.id,
+            name: account.name.clone(),
+            base_currency: account.base_currency.clone(),
+            starting_balance: account.starting_balance,
+            deposits,
+            withdrawals,
+            fees,
+            interest,
+            realized_pnl,
+            equity,
+            return_percentage,
+            time_weighted_return,
+            money_weighted_return,
+            equity_curve,
+            fx_rates_used: Vec::new(),
+        }
+    }
+
+    /// Relate plan adherence, mood and energy of journal days to their P&L.
+    ///
+    /// Correlations are Pearson coefficients over the days with closed
+    /// trades that carry the rating, and need two differing ratings.
+    pub fn journal_analytics(days: Vec<JournalDay>) -> JournalAnalytics {
+        let mut plan_followed = JournalGroup::default();
+        let mut plan_not_followed = JournalGroup::default();
+        let mut plan_not_rated = JournalGroup::default();
+        let mut by_mood: BTreeMap<i16, JournalGroup> = BTreeMap::new();
+        let mut by_energy: BTreeMap<i16, JournalGroup> = BTreeMap::new();
+
+        for day in &days {
+            match day.plan_followed {
+                Some(true) => plan_followed.add(day),
+                Some(false) => plan_not_followed.add(day),
+                None => plan_not_rated.add(day),
+            }
+            if let Some(mood) = day.mood {
+                by_mood.entry(mood).or_default().add(day);
+            }
+            if let Some(energy) = day.energy {
+                by_energy.entry(energy).or_default().add(day);
+            }
+        }
+
+        let correlation = |rating: fn(&JournalDay) -> Option<f64>| {
+            let pairs: Vec<(f64, f64)> = days
+                .iter()
+                .filter_map(|day| Some((rating(day)?, decimal_to_f64(day.pnl?))))
+                .collect();
+            pearson(&pairs)
+        };
+        let plan_followed_edge = plan_followed
+            .average_daily_pnl
+            .zip(plan_not_followed.average_daily_pnl)
+            .map(|(followed, not_followed)| followed - not_followed);
+        let scored = |groups: BTreeMap<i16, JournalGroup>| {
+            groups
+                .into_iter()
+                .map(|(score, group)| JournalGroup { score: Some(score), ..group })
+                .collect()
+        };
+
+        JournalAnalytics {
+            plan_followed_edge,
+            plan_pnl_correlation: correlation(|day| day.plan_followed.map(|followed| if followed { 1.0 } else { 0.0 })),
+            mood_pnl_correlation: correlation(|day| day.mood.map(f64::from)),
+            energy_pnl_correlation: correlation(|day| day.energy.map(f64::from)),
+            plan_followed,
+            plan_not_followed,
+            plan_not_rated,
+            by_mood: scored(by_mood),
+            by_energy: scored(by_energy),
+            days,
+            currency: None,
+            fx_rates_used: Vec::new(),
+        }
+    }
+
+    /// Performance of each playbook, split by checklist compliance and by
+    /// checklist item. Compliance is measured against the playbook's
+    /// current checklist.
+    pub fn playbook_performance(trades: Vec<PlaybookTrade>) -> Vec<PlaybookPerformance> {
+        let mut playbooks: HashMap<Uuid, (PlaybookPerformance, Vec<f64>)> = HashMap::new();
+
+        for trade in trades {
+            let (performance, compliance) = playbooks.entry(trade.playbook_id).or_insert_with(|| {
+                let items = trade
+                    .checklist
+                    .iter()
+                    .map(|item| ChecklistItemPerformance {
+                        item: item.clone(),
+                        met: ComplianceTotals::default(),
+                        missed: ComplianceTotals::default(),
+                        edge: None,
+                    })
+                    .collect();
+                let performance = PlaybookPerformance {
+                    playbook_id: trade.playbook_id,
+                    name: trade.name.clone(),
+                    total_trades: 0,
+                    winning_trades: 0,
+                    win_rate: 0.0,
+                    total_pnl: Decimal::ZERO,
+                    average_pnl: Decimal::ZERO,
+                    average_compliance: None,
+                    fully_compliant: ComplianceTotals::default(),
+                    partially_compliant: ComplianceTotals::default(),
+                    non_compliant: ComplianceTotals::default(),
+                    items,
+                    fx_rates_used: Vec::new(),
+                };
+                (performance, Vec::new())
+            });
+
+            performance.total_trades += 1;
+            performance.total_pnl += trade.pnl;
+            if trade.pnl > Decimal::ZERO {
+                performance.winning_trades += 1;
+            }
+
+            if trade.checklist.is_empty() {
+                continue;
+            }
+            let met = trade.checklist.iter().filter(|item| trade.checklist_met.contains(item)).count();
+            compliance.push(met as f64 / trade.checklist.len() as f64 * 100.0);
+            match met {
+                0 => performance.non_compliant.add(trade.pnl),
+                met if met == trade.checklist.len() => performance.fully_compliant.add(trade.pnl),
+                _ => performance.partially_compliant.add(trade.pnl),
+            }
+            for item in &mut performance.items {
+                if trade.checklist_met.contains(&item.item) {
+                    item.met.add(trade.pnl);
+                } else {
+                    item.missed.add(trade.pnl);
+                }
+            }
+        }
+
+        let mut results: Vec<PlaybookPerformance> = playbooks
+            .into_values()
+            .map(|(mut performance, compliance)| {
+                performance.win_rate = performance.winning_trades as f64 / performance.total_trades as f64 * 100.0;
+                performance.average_pnl = performance.total_pnl / Decimal::from(performance.total_trades);
+                performance.average_compliance = mean(&compliance);
+                for item in &mut performance.items {
+                    item.edge = item.met.average_pnl.zip(item.missed.average_pnl).map(|(met, missed)| met - missed);
+                }
+                performance
+            })
+            .collect();
+        results.sort_by(|a, b| b.win_rate.total_cmp(&a.win_rate).then_with(|| a.name.cmp(&b.name)));
+
+        results
+    }
+
+    /// Break closed trades down by entry hour, weekday and month in
+    /// `timezone`, by the sessions open at entry and by holding time. Every
+    /// bucket is listed, empty ones included, in their natural order.
+    /// Hours and weekdays are read off the local clock; the day rollover
+    /// hour doesn't move trades into the next day here.
+    pub fn time_breakdowns(trades: &[TimedTrade], timezone: Tz, sessions: &[TradingSession]) -> TimeBreakdowns {
+        let buckets = |labels: Vec<String>| -> Vec<TimeBucket> {
+            labels
+                .into_iter()
+                .map(|bucket| TimeBucket {
+                    bucket,
+                    totals: ComplianceTotals::default(),
+                })
+                .collect()
+        };
+
+        let mut by_hour = buckets((0..24).map(|hour| format!("{:02}:00", hour)).collect());
+        let mut by_weekday = buckets(WEEKDAYS.iter().map(|day| day.to_string()).collect());
+        let mut by_month = buckets(MONTHS.iter().map(|month| month.to_string()).collect());
+        let mut by_session = buckets(
+            sessions
+                .iter()
+                .map(|session| session.name.clone())
+                .chain(std::iter::once(OFF_SESSION.to_string()))
+                .collect(),
+        );
+        let mut by_holding_time = buckets(HOLDING_TIMES.iter().map(|(_, label)| label.to_string()).collect());
+
+        for trade in trades {
+            let local = trade.entry_time.with_timezone(&timezone);
+            by_hour[local.hour() as usize].totals.add(trade.pnl);
+            by_weekday[local.weekday().num_days_from_monday() as usize].totals.add(trade.pnl);
+            by_month[local.month0() as usize].totals.add(trade.pnl);
+
+            let mut in_session = false;
+            for (session, bucket) in sessions.iter().zip(by_session.iter_mut()) {
+                if session.contains(trade.entry_time) {
+                    bucket.totals.add(trade.pnl);
+                    in_session = true;
+                }
+            }
+            if !in_session {
+                by_session[sessions.len()].totals.add(trade.pnl);
+            }
+
+            if let Some(exit_time) = trade.exit_time {
+                let minutes = (exit_time - trade.entry_time).num_minutes();
+                let index = HOLDING_TIMES.iter().position(|(limit, _)| minutes < *limit).unwrap_or(0);
+                by_holding_time[index].totals.add(trade.pnl);
+            }
+        }
+
+        TimeBreakdowns {
+            timezone: timezone.name().to_string(),
+            by_hour,
+            by_weekday,
+            by_month,
+            by_session,
+            by_holding_time,
+            currency: None,
+            fx_rates_used: Vec::new(),
+        }
+    }
+
+    /// Lay out a month starting on `first_day` from its days with trades
+    /// and the dates of its journal entries
+    pub fn calendar(
+        first_day: NaiveDate,
+        buckets: Vec<CalendarBucket>,
+        journal_dates: &[NaiveDate],
+        timezone: Tz,
+        day_rollover_hour: i16,
+    ) -> CalendarMonth {
+        let mut buckets: HashMap<NaiveDate, CalendarBucket> =
+            buckets.into_iter().map(|bucket| (bucket.date, bucket)).collect();
+
+        let mut days: Vec<CalendarDay> = Vec::new();
+        let mut weeks: Vec<CalendarWeek> = Vec::new();
+        let mut totals = CalendarTotals::default();
+
+        let dates = first_day.iter_days().take_while(|date| date.month() == first_day.month());
+        for date in dates {
+            let day_totals: CalendarTotals = buckets.remove(&date).map(Into::into).unwrap_or_default();
+
+            match weeks.last_mut() {
+                Some(week) if date.weekday() != Weekday::Mon => {
+                    week.end = date;
+                    week.totals.add(&day_totals);
+                }
+                _ => weeks.push(CalendarWeek {
+                    start: date,
+                    end: date,
+                    totals: day_totals.clone(),
+                }),
+            }
+            totals.add(&day_totals);
+
+            days.push(CalendarDay {
+                date,
+                totals: day_totals,
+                has_journal_entry: journal_dates.contains(&date),
+            });
+        }
+
+        CalendarMonth {
+            month: first_day.format("%Y-%m").to_string(),
+            timezone: timezone.name().to_string(),
+            day_rollover_hour,
+            days,
+            weeks,
+            totals,
+            currency: None,
+            fx_rates_used: Vec::new(),
+        }
+    }
+
+    /// Mark open trades to market at the given quotes.
+    ///
+    /// `rates` convert trade currencies into the reporting `currency`, and
+    /// a trade in any other currency without a rate is an error, so amounts
+    /// in different currencies are never added up. Only the rates applied
+    /// are reported, with the positions they converted.
+    pub fn open_positions(
+        trades: &[Trade],
+        quotes: &[Quote],
+        currency: Option<&str>,
+        mut rates: Vec<FxRateUsed>,
+    ) -> Result<OpenPositions> {
+        let quotes: HashMap<&str, &Quote> = quotes.iter().map(|quote| (quote.symbol.as_str(), quote)).collect();
+
+        let mut positions = Vec::with_capacity(trades.len());
+        let mut exposure: BTreeMap<(String, String), Exposure> = BTreeMap::new();
+        let mut totals = OpenPositions {
+            positions: Vec::new(),
+            exposure: Vec::new(),
+            gross_exposure: Decimal::ZERO,
+            net_exposure: Decimal::ZERO,
+            unrealized_pnl: Decimal::ZERO,
+            open_risk: Decimal::ZERO,
+            unmarked: 0,
+            price_source: String::new(),
+            quotes_unavailable: false,
+            currency: None,
+            fx_rates_used: Vec::new(),
+        };
+
+        for trade in trades.iter().filter(|t| t.open_quantity > Decimal::ZERO) {
+            let sign = if trade.direction == "short" { -Decimal::ONE } else { Decimal::ONE };
+            let units = trade.open_quantity * trade.multiplier;
+            let rate = match currency {
+                Some(currency) if trade.currency != currency => {
+                    let rate = rates
+                        .iter_mut()
+                        .find(|rate| rate.from_currency == trade.currency)
+                        .ok_or_else(|| {
+                            AppError::ValidationError(format!("No {}/{} FX rate", trade.currency, currency))
+                        })?;
+                    rate.trades += 1;
+                    rate.rate
+                }
+                _ => Decimal::ONE,
+            };
+
+            let quote = match trade.underlying {
+                Some(_) => None,
+                None => quotes.get(trade.symbol.as_str()),
+            };
+            let mark_price = quote.map(|quote| quote.price);
+
+            let unrealized_pnl = mark_price.map(|mark| ((mark - trade.entry_price) * units * sign * rate).round_dp(8));
+            let market_value = mark_price.map(|mark| (mark * units * rate).round_dp(8));
+            let open_risk = match trade.stop_loss.or(trade.initial_stop) {
+                Some(stop) => {
+                    let from = mark_price.unwrap_or(trade.entry_price);
+                    Some(((from - stop) * units * sign * rate).max(Decimal::ZERO).round_dp(8))
+                }
+                None => trade
+                    .planned_risk
+                    .filter(|_| trade.quantity > Decimal::ZERO)
+                    .map(|risk| (risk * trade.open_quantity / trade.quantity * rate).round_dp(8)),
+            };
+
+            let group = exposure
+                .entry((trade.underlying.clone().unwrap_or_else(|| trade.symbol.clone()), trade.direction.clone()))
+                .or_insert_with_key(|(symbol, direction)| Exposure {
+                    symbol: symbol.clone(),
+                    direction: direction.clone(),
+                    positions: 0,
+                    open_quantity: Decimal::ZERO,
+                    market_value: Decimal::ZERO,
+                    unrealized_pnl: Decimal::ZERO,
+                });
+            group.positions += 1;
+            group.open_quantity += trade.open_quantity;
+            group.market_value += market_value.unwrap_or_default();
+            group.unrealized_pnl += unrealized_pnl.unwrap_or_default();
+
+            totals.gross_exposure += market_value.unwrap_or_default();
+            totals.net_exposure += market_value.unwrap_or_default() * sign;
+            totals.unrealized_pnl += unrealized_pnl.unwrap_or_default();
+            totals.open_risk += open_risk.unwrap_or_default();
+            if mark_price.is_none() {
+                totals.unmarked += 1;
+            }
+
+            positions.push(OpenPosition {
+                trade_id: trade.id,
+                symbol: trade.symbol.clone(),
+                direction: trade.direction.clone(),
+                open_quantity: trade.open_quantity,
+                entry_price: trade.entry_price,
+                currency: trade.currency.clone(),
+                mark_price,
+                marked_at: quote.map(|quote| quote.as_of),
+                unrealized_pnl,
+                market_value,
+                open_risk,
+            });
+        }
+
+        totals.positions = positions;
+        totals.exposure = exposure.into_values().collect();
+        totals.fx_rates_used = rates.into_iter().filter(|rate| rate.trades > 0).collect();
+        Ok(totals)
+    }
+
+    /// Order symbols by win rate, best first, then by name
+    pub fn sort_symbols(results: &mut [SymbolPerformance]) {
+        results.sort_by(|a, b| b.win_rate.total_cmp(&a.win_rate).then_with(|| a.symbol.cmp(&b.symbol)));
+    }
+
+    /// Order setups by win rate, best first, then by name
+    pub fn sort_setups(results: &mut [SetupPerformance]) {
+        results.sort_by(|a, b| {
+            b.win_rate
+                .total_cmp(&a.win_rate)
+                .then_with(|| a.setup_type.cmp(&b.setup_type))
+        });
+    }
+
+    /// Order mistakes by frequency, most common first, then by name
+    pub fn sort_mistakes(results: &mut [MistakeAnalysis]) {
+        results.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.mistake.cmp(&b.mistake)));
+    }
+}
+
+/// In-memory reference for the SQL aggregates of `AnalyticsRepository`,
+/// which tests hold the queries to
+#[cfg(test)]
+impl AnalyticsService {
+    /// Calculate overall trade analytics.
+    ///
+    /// Streaks follow entry time (ties broken by ID); trades closed at
+    /// exactly zero neither extend nor break a streak.
+    pub(crate) fn calculate_overview(trades: &[Trade]) -> Result<TradeAnalytics> {
+        let mut ordered: Vec<&Trade> = trades.iter().collect();
+        ordered.sort_by_key(|t| (t.entry_time, t.id));
+
+        let mut totals = OverviewTotals {
+            total_trades: trades.len() as i32,
+            ..Default::default()
+        };
+
+        let mut temp_win_streak = 0;
+        let mut temp_loss_streak = 0;
+
+        for trade in ordered {
+            if let Some(pnl) = trade.pnl {
+                totals.total_pnl += pnl;
+
+                if pnl > Decimal::ZERO {
+                    totals.winning_trades += 1;
+                    totals.total_wins += pnl;
+                    if pnl > totals.largest_win {
+                        totals.largest_win = pnl;
+                    }
+
+                    temp_win_streak += 1;
+                    temp_loss_streak = 0;
+                    if temp_win_streak > totals.longest_win_streak {
+                        totals.longest_win_streak = temp_win_streak;
+                    }
+                } else if pnl < Decimal::ZERO {
+                    totals.losing_trades += 1;
+                    totals.total_losses += pnl.abs();
+                    if pnl < totals.largest_loss {
+                        totals.largest_loss = pnl;
+                    }
+
+                    temp_loss_streak += 1;
+                    temp_win_streak = 0;
+                    if temp_loss_streak > totals.longest_loss_streak {
+                        totals.longest_loss_streak = temp_loss_streak;
+                    }
+                }
+            }
+        }
+
+        totals.current_streak = if temp_win_streak > 0 {
+            temp_win_streak
+        } else {
+            -temp_loss_streak
+        };
+
+        Ok(totals.into())
+    }
+
+    /// Calculate performance by symbol, rolling option trades up into
+    /// their underlying
+    pub(crate) fn calculate_by_symbol(trades: &[Trade]) -> Result<Vec<SymbolPerformance>> {
+        let groups = Self::group_totals(trades.iter().map(|t| (t.underlying.as_ref().or(Some(&t.symbol)), t)));
+
+        let mut results: Vec<SymbolPerformance> = groups.into_iter().map(Into::into).collect();
+        Self::sort_symbols(&mut results);
+
+        Ok(results)
+    }
+
+    /// Calculate performance by setup type; a multi-leg option trade
+    /// counts once with the combined P&L of its legs
+    pub(crate) fn calculate_by_setup(trades: &[Trade]) -> Result<Vec<SetupPerformance>> {
+        let groups = Self::group_totals(trades.iter().map(|t| (t.setup_type.as_ref(), t)));
+
+        let mut results: Vec<SetupPerformance> = groups.into_iter().map(Into::into).collect();
+        Self::sort_setups(&mut results);
+
+        Ok(results)
+    }
+
+    /// Analyze mistakes and their impact
+    pub(crate) fn analyze_mistakes(trades: &[Trade]) -> Result<Vec<MistakeAnalysis>> {
+        let mut mistake_map: HashMap<String, MistakeTotals> = HashMap::new();
+
+        for trade in trades {
+            for mistake in &trade.mistakes {
+                if let Some(pnl) = trade.pnl {
+                    let totals = mistake_map.entry(mistake.clone()).or_insert_with(|| MistakeTotals {
+                        mistake: mistake.clone(),
+                        count: 0,
+                        total_pnl: Decimal::ZERO,
+                    });
+                    totals.count += 1;
+                    totals.total_pnl += pnl;
+                }
+            }
+        }
+
+        let mut results: Vec<MistakeAnalysis> = mistake_map.into_values().map(Into::into).collect();
+        Self::sort_mistakes(&mut results);
+
+        Ok(results)
+    }
+
+    /// Sum trades per group key, skipping trades without a key
+    fn group_totals<'a>(trades: impl Iterator<Item = (Option<&'a String>, &'a Trade)>) -> Vec<GroupTotals> {
+        let mut groups: HashMap<String, GroupTotals> = HashMap::new();
+
+        for (key, trade) in trades {
+            let Some(key) = key else { continue };
+
+            let totals = groups.entry(key.clone()).or_insert_with(|| GroupTotals {
+                key: key.clone(),
+                total_trades: 0,
+                winning_trades: 0,
+                total_pnl: Decimal::ZERO,
+            });
+
+            totals.total_trades += 1;
+            if let Some(pnl) = trade.pnl {
+                totals.total_pnl += pnl;
+                if pnl > Decimal::ZERO {
+                    totals.winning_trades += 1;
+                }
+            }
+        }
+
+        groups.into_values().collect()
+    }
+}
+
+/// Drawdown as a percentage of the peak, when the peak is positive
+fn drawdown_percentage(drawdown: Decimal, peak: Decimal) -> Option<f64> {
+    if peak > Decimal::ZERO {
+        (drawdown / peak * Decimal::from(100)).to_f64()
+    } else {
+        None
+    }
+}
+
+fn days_between(from: DateTime<Utc>, to: DateTime<Utc>) -> f64 {
+    (to - from).num_seconds() as f64 / 86_400.0
+}
+
+fn decimal_to_f64(d: Decimal) -> f64 {
+    d.to_f64().unwrap_or(0.0)
+}
+
+fn mean(values: &[f64]) -> Option<f64> {
+    if values.is_empty() {
+        None
+    } else {
+        Some(values.iter().sum::<f64>() / values.len() as f64)
+    }
+}
+
+/// Standard deviation with Bessel's correction; needs two observations
+fn sample_std_dev(values: &[f64]) -> Option<f64> {
+    if values.len() < 2 {
+        return None;
+    }
+
+    let m = mean(values)?;
+    let variance = values.iter().map(|v| (v - m).powi(2)).sum::<f64>() / (values.len() - 1) as f64;
+
+    Some(variance.sqrt())
+}
+
+/// Pearson correlation coefficient; None with fewer than two pairs or when
+/// either side doesn't vary
+fn pearson(pairs: &[(f64, f64)]) -> Option<f64> {
+    if pairs.len() < 2 {
+        return None;
+    }
+
+    let xs: Vec<f64> = pairs.iter().map(|(x, _)| *x).collect();
+    let ys: Vec<f64> = pairs.iter().map(|(_, y)| *y).collect();
+    let (mx, my) = (mean(&xs)?, mean(&ys)?);
+    let covariance: f64 = pairs.iter().map(|(x, y)| (x - mx) * (y - my)).sum();
+    let spread = |values: &[f64], m: f64| values.iter().map(|v| (v - m).powi(2)).sum::<f64>().sqrt();
+    let denominator = spread(&xs, mx) * spread(&ys, my);
+
+    (denominator > 0.0).then(|| covariance / denominator)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+
+    fn assert_close(actual: Option<f64>, expected: f64) {
+        let actual = actual.expect("metric should be defined");
+        assert!(
+            (actual - expected).abs() < 1e-9,
+            "expected {}, got {}",
+            expected,
+            actual
+        );
+    }
+
+    /// One bucket per day from 2025-01-06, with the given P&L
+    fn buckets(pnls: &[i64]) -> Vec<EquityBucket> {
+        pnls.iter()
+            .enumerate()
+            .map(|(i, pnl)| EquityBucket {
+                time: day(i as i64),
+                trade_id: None,
+                symbol: None,
+                trades: 1,
+                pnl: Decimal::from(*pnl),
+            })
+            .collect()
+    }
+
+    /// Start of the `n`th day of `buckets`
+    fn day(n: i64) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 1, 6, 0, 0, 0).unwrap() + Duration::days(n)
+    }
+
+    fn curve(pnls: &[i64], starting_equity: i64) -> EquityCurve {
+        AnalyticsService::equity_curve(
+            buckets(pnls),
+            EquityInterval::Day,
+            chrono_tz::UTC,
+            Decimal::from(starting_equity),
+        )
+    }
+
+    fn metrics(trades: &[i64], days: &[i64], starting_equity: Option<i64>) -> RiskMetrics {
+        AnalyticsService::risk_metrics(
+            &buckets(trades),
+            buckets(days),
+            chrono_tz::UTC,
+            starting_equity.map(Decimal::from),
+        )
+    }
+
+    #[test]
+    fn mean_and_sample_std_dev() {
+        let values = [2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0];
+
+        assert_close(mean(&values), 5.0);
+        // Squared deviations sum to 32 over 7 degrees of freedom
+        assert_close(sample_std_dev(&values), (32.0f64 / 7.0).sqrt());
+        assert_eq!(mean(&[]), None);
+        assert_eq!(sample_std_dev(&[1.0]), None);
+    }
+
+    #[test]
+    fn trade_metrics() {
+        // Mean 50, squared deviations 45000 over 3 degrees of freedom
+        let m = metrics(&[100, -50, 200, -50], &[], None);
+
+        assert_eq!(m.total_trades, 4);
+        assert_eq!(m.expectancy, Decimal::from(50));
+        assert_close(Some(m.pnl_std_dev), 15000f64.sqrt());
+        // Average win 150 over average loss 50
+        assert_close(m.payoff_ratio, 3.0);
+        // 0.5 - 0.5 / 3
+        assert_close(m.kelly_fraction, 1.0 / 3.0);
+        // sqrt(4) * 50 / sqrt(15000)
+        assert_close(m.sqn, 100.0 / 15000f64.sqrt());
+    }
+
+    #[test]
+    fn daily_pnl_ratios() {
+        let m = metrics(&[], &[100, -50, 200, -50], None);
+
+        assert_eq!(m.trading_days, 4);
+        assert_eq!(m.returns_basis, "pnl");
+        // 50 / sqrt(15000) * sqrt(252)
+        assert_close(m.sharpe_ratio, 42f64.sqrt());
+        // Downside deviation sqrt(5000 / 4): 50 / sqrt(1250) * sqrt(252)
+        assert_close(m.sortino_ratio, 504f64.sqrt());
+        // Largest drawdown is 50: 50 * 252 / 50
+        assert_close(m.calmar_ratio, 252.0);
+    }
+
+    #[test]
+    fn daily_equity_ratios() {
+        // Equity 1000 -> 1100 -> 990 -> 1089: returns 0.1, -0.1, 0.1
+        let m = metrics(&[], &[100, -110, 99], Some(1000));
+
+        assert_eq!(m.returns_basis, "equity");
+        // Mean 1/30, sample deviation sqrt(1/75)
+        assert_close(m.sharpe_ratio, 21f64.sqrt());
+        // Downside deviation sqrt(0.01 / 3)
+        assert_close(m.sortino_ratio, 84f64.sqrt());
+        // 10% drawdown from the 1100 peak: (1/30) * 252 / 0.1
+        assert_close(m.calmar_ratio, 84.0);
+    }
+
+    #[test]
+    fn no_losses_leave_payoff_and_kelly_undefined() {
+        let m = metrics(&[100, 50], &[100, 50], None);
+
+        assert_eq!(m.payoff_ratio, None);
+        assert_eq!(m.kelly_fraction, None);
+        assert_eq!(m.sortino_ratio, None);
+        assert_eq!(m.calmar_ratio, None);
+        assert!(m.sqn.is_some());
+    }
+
+    #[test]
+    fn single_day_leaves_daily_ratios_undefined() {
+        let m = metrics(&[100], &[100], None);
+
+        assert_eq!(m.trading_days, 1);
+        assert_eq!(m.pnl_std_dev, 0.0);
+        assert_eq!(m.sqn, None);
+        assert_eq!(m.sharpe_ratio, None);
+        assert_eq!(m.sortino_ratio, None);
+        assert_eq!(m.calmar_ratio, None);
+    }
+
+    #[test]
+    fn zero_variance_leaves_ratios_undefined() {
+        let m = metrics(&[50, 50, 50], &[50, 50, 50], None);
+
+        assert_eq!(m.expectancy, Decimal::from(50));
+        assert_eq!(m.pnl_std_dev, 0.0);
+        assert_eq!(m.sqn, None);
+        assert_eq!(m.sharpe_ratio, None);
+        assert_eq!(m.sortino_ratio, None);
+        assert_eq!(m.calmar_ratio, None);
+    }
+
+    #[test]
+    fn empty_input() {
+        let m = metrics(&[], &[], None);
+
+        assert_eq!(m.total_trades, 0);
+        assert_eq!(m.expectancy, Decimal::ZERO);
+        assert_eq!(m.payoff_ratio, None);
+        assert_eq!(m.sharpe_ratio, None);
+    }
+
+    #[test]
+    fn drawdown_depth_and_recovery() {
+        // Equity 1100 -> 1050 -> 950 -> 1030 -> 1150
+        let c = curve(&[100, -50, -100, 80, 120], 1000);
+
+        let drawdowns: Vec<i64> = c.points.iter().map(|p| p.drawdown.to_i64().unwrap()).collect();
+        assert_eq!(drawdowns, [0, 50, 150, 70, 0]);
+        assert_eq!(c.points[2].peak, Decimal::from(1100));
+        assert_close(c.points[2].drawdown_percentage, 150.0 / 11.0);
+
+        let max = c.max_drawdown.expect("curve has a drawdown");
+        assert_eq!(max.amount, Decimal::from(150));
+        assert_close(max.percentage, 150.0 / 11.0);
+        assert_eq!(max.peak_equity, Decimal::from(1100));
+        // From the day that set the peak to the first day back above it
+        assert_eq!((max.start, max.trough, max.recovery), (day(0), day(2), Some(day(4))));
+        assert_eq!(max.duration_days, 4.0);
+        assert_eq!(c.ending_equity, Decimal::from(1150));
+        assert_eq!(c.time_under_water.current_days, 0.0);
+    }
+
+    #[test]
+    fn time_under_water_adds_up_periods() {
+        // Under water for days 0-2, 2-5 (returning exactly to the peak) and 5-7
+        let c = curve(&[100, -50, 50, -20, -20, 60, -10, 10], 0);
+
+        let tuw = &c.time_under_water;
+        assert_eq!(tuw.periods, 3);
+        assert_eq!(tuw.total_days, 7.0);
+        assert_eq!(tuw.longest_days, 3.0);
+        assert_eq!(tuw.current_days, 0.0);
+
+        // The deepest period wins, not the longest
+        let max = c.max_drawdown.unwrap();
+        assert_eq!(max.amount, Decimal::from(50));
+        assert_close(max.percentage, 50.0);
+        assert_eq!(max.recovery, Some(day(2)));
+    }
+
+    #[test]
+    fn drawdown_still_open_at_the_end() {
+        // Equity never rises above the start: -50 -> -30 -> -70
+        let c = curve(&[-50, 20, -40], 0);
+
+        let max = c.max_drawdown.unwrap();
+        assert_eq!(max.amount, Decimal::from(70));
+        // No positive peak to measure against
+        assert_eq!(max.percentage, None);
+        assert_eq!((max.start, max.trough, max.recovery), (day(0), day(2), None));
+        assert_eq!(max.duration_days, 2.0);
+        assert_eq!(c.time_under_water.periods, 1);
+        assert_eq!(c.time_under_water.current_days, 2.0);
+        assert_eq!(c.ending_equity, Decimal::from(-70));
+    }
+
+    #[test]
+    fn drawdown_open_after_a_recovery() {
+        // Recovered on day 2, under water again from day 3 to the end
+        let c = curve(&[100, -40, 60, -10, -10], 1000);
+
+        let tuw = &c.time_under_water;
+        assert_eq!(tuw.periods, 2);
+        assert_eq!(tuw.current_days, 2.0);
+        assert_eq!(tuw.total_days, 4.0);
+        assert_eq!(c.max_drawdown.unwrap().amount, Decimal::from(40));
+        assert_eq!(c.points.last().unwrap().drawdown, Decimal::from(20));
+    }
+
+    #[test]
+    fn empty_curve() {
+        let c = curve(&[], 500);
+
+        assert!(c.points.is_empty());
+        assert!(c.max_drawdown.is_none());
+        assert_eq!(c.ending_equity, Decimal::from(500));
+        assert_eq!(c.time_under_water.periods, 0);
+        assert_eq!(c.time_under_water.total_days, 0.0);
+        assert_eq!(c.time_under_water.current_days, 0.0);
+    }
+
+    fn timed(entry_time: DateTime<Utc>, pnl: i64) -> TimedTrade {
+        TimedTrade {
+            entry_time,
+            exit_time: None,
+            pnl: Decimal::from(pnl),
+        }
+    }
+
+    /// Buckets with trades, as (label, total P&L)
+    fn filled(buckets: &[TimeBucket]) -> Vec<(&str, i64)> {
+        buckets
+            .iter()
+            .filter(|b| b.totals.total_trades > 0)
+            .map(|b| (b.bucket.as_str(), b.totals.total_pnl.to_i64().unwrap()))
+            .collect()
+    }
+
+    #[test]
+    fn hours_and_weekdays_follow_the_local_clock_around_midnight() {
+        let utc = |d, h, m| Utc.with_ymd_and_hms(2025, 3, d, h, m, 0).unwrap();
+        let trades = [
+            // Friday 16:59 and 17:00 EST, either side of a 17:00 rollover
+            timed(utc(7, 21, 59), 1),
+            timed(utc(7, 22, 0), 2),
+            // Friday 23:59 and Saturday 00:00 EST
+            timed(utc(8, 4, 59), 4),
+            timed(utc(8, 5, 0), 8),
+            // Sunday 23:59 and Monday 00:00 EDT, after the clocks went forward
+            timed(utc(10, 3, 59), 16),
+            timed(utc(10, 4, 0), 32),
+        ];
+
+        let b = AnalyticsService::time_breakdowns(&trades, chrono_tz::America::New_York, &[]);
+
+        assert_eq!(b.by_hour.len(), 24);
+        assert_eq!(filled(&b.by_hour), [("00:00", 40), ("16:00", 1), ("17:00", 2), ("23:00", 20)]);
+        assert_eq!(
+            filled(&b.by_weekday),
+            [("Monday", 32), ("Friday", 7), ("Saturday", 8), ("Sunday", 16)]
+        );
+        assert_eq!(filled(&b.by_session), [(OFF_SESSION, 63)]);
+    }
+
+    #[test]
+    fn new_year_falls_in_the_local_year() {
+        // 23:30 on New Year's Eve in New York
+        let trades = [timed(Utc.with_ymd_and_hms(2025, 1, 1, 4, 30, 0).unwrap(), 10)];
+
+        let local = AnalyticsService::time_breakdowns(&trades, chrono_tz::America::New_York, &[]);
+        assert_eq!(filled(&local.by_month), [("December", 10)]);
+        assert_eq!(filled(&local.by_weekday), [("Tuesday", 10)]);
+        assert_eq!(filled(&local.by_hour), [("23:00", 10)]);
+
+        let utc = AnalyticsService::time_breakdowns(&trades, chrono_tz::UTC, &[]);
+        assert_eq!(filled(&utc.by_month), [("January", 10)]);
+        assert_eq!(filled(&utc.by_weekday), [("Wednesday", 10)]);
+        assert_eq!(filled(&utc.by_hour), [("04:00", 10)]);
+    }
+
+    fn calendar_bucket(date: NaiveDate, total_trades: i32, winning_trades: i32, net_pnl: i64) -> CalendarBucket {
+        CalendarBucket {
+            date,
+            total_trades,
+            winning_trades,
+            net_pnl: Decimal::from(net_pnl),
+            fees: Decimal::from(total_trades),
+        }
+    }
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn calendar_month_starting_mid_week() {
+        // January 2025 starts on a Wednesday
+        let buckets = vec![
+            calendar_bucket(date(2025, 1, 1), 2, 1, 30),
+            calendar_bucket(date(2025, 1, 5), 1, 0, -10),
+            calendar_bucket(date(2025, 1, 6), 1, 1, 50),
+            calendar_bucket(date(2025, 1, 31), 1, 1, 20),
+        ];
+        let journal_dates = [date(2025, 1, 6)];
+
+        let c = AnalyticsService::calendar(date(2025, 1, 1), buckets, &journal_dates, chrono_tz::UTC, 0);
+
+        assert_eq!(c.month, "2025-01");
+        assert_eq!(c.days.len(), 31);
+        assert_eq!(c.days[0].totals.net_pnl, Decimal::from(30));
+        assert_eq!(c.days[0].totals.win_rate, 50.0);
+        assert_eq!(c.days[1].totals.total_trades, 0);
+        let journaled: Vec<NaiveDate> = c.days.iter().filter(|d| d.has_journal_entry).map(|d| d.date).collect();
+        assert_eq!(journaled, journal_dates);
+
+        let spans: Vec<(u32, u32)> = c.weeks.iter().map(|w| (w.start.day(), w.end.day())).collect();
+        assert_eq!(spans, [(1, 5), (6, 12), (13, 19), (20, 26), (27, 31)]);
+        let first = &c.weeks[0].totals;
+        assert_eq!((first.total_trades, first.winning_trades), (3, 1));
+        assert_eq!((first.net_pnl, first.fees), (Decimal::from(20), Decimal::from(3)));
+        assert_close(Some(first.win_rate), 100.0 / 3.0);
+        let week_pnls: Vec<i64> = c.weeks.iter().map(|w| w.totals.net_pnl.to_i64().unwrap()).collect();
+        assert_eq!(week_pnls, [20, 50, 0, 0, 20]);
+
+        assert_eq!((c.totals.total_trades, c.totals.winning_trades), (5, 3));
+        assert_eq!(c.totals.win_rate, 60.0);
+        assert_eq!((c.totals.net_pnl, c.totals.fees), (Decimal::from(90), Decimal::from(5)));
+    }
+
+    #[test]
+    fn calendar_splits_weeks_crossing_the_month() {
+        // ISO week 2026-W01 runs from Monday 29 December to Sunday 4 January
+        let buckets = || {
+            vec![
+                calendar_bucket(date(2025, 12, 30), 1, 1, 10),
+                calendar_bucket(date(2026, 1, 2), 1, 1, 20),
+            ]
+        };
+
+        let december = AnalyticsService::calendar(date(2025, 12, 1), buckets(), &[], chrono_tz::UTC, 0);
+        let january = AnalyticsService::calendar(date(2026, 1, 1), buckets(), &[], chrono_tz::UTC, 0);
+
+        let last = december.weeks.last().unwrap();
+        let first = &january.weeks[0];
+        assert_eq!((last.start, last.end), (date(2025, 12, 29), date(2025, 12, 31)));
+        assert_eq!((first.start, first.end), (date(2026, 1, 1), date(2026, 1, 4)));
+        assert_eq!(last.start.iso_week(), first.end.iso_week());
+
+        // Each month keeps only its own days of the shared week
+        assert_eq!(last.totals.net_pnl, Decimal::from(10));
+        assert_eq!(first.totals.net_pnl, Decimal::from(20));
+        assert_eq!(december.totals.net_pnl, Decimal::from(10));
+        assert_eq!(january.totals.net_pnl, Decimal::from(20));
+    }
+}
@@ -0,0 +1,381 @@
+use crate::{
+    error::{AppError, Result},
+    models::{Attachment, NewAttachment, ProcessedUpload, SubscriptionTier},
+    repositories::AttachmentRepository,
+    storage::BlobStore,
+};
+use axum::body::Bytes;
+use image::{
+    codecs::{jpeg::JpegEncoder, png::PngEncoder},
+    metadata::Orientation,
+    DynamicImage, ImageDecoder, ImageFormat, ImageReader, Limits,
+};
+use img_parts::{jpeg::Jpeg, png::Png, webp::WebP, ImageEXIF};
+use std::io::Cursor;
+use uuid::Uuid;
+
+/// Longest edge of a thumbnail in pixels
+const THUMBNAIL_SIZE: u32 = 320;
+
+/// Largest image width or height accepted, guarding against decompression bombs
+const MAX_IMAGE_DIMENSION: u32 = 16_384;
+
+/// Memory an image decoder may allocate
+const MAX_DECODE_ALLOC: u64 = 256 * 1024 * 1024;
+
+/// JPEG markers carrying metadata: APP1 (EXIF, XMP), APP13 (IPTC) and comments
+const JPEG_METADATA_MARKERS: &[u8] = &[0xE1, 0xED, 0xFE];
+
+/// PNG chunks carrying metadata
+const PNG_METADATA_CHUNKS: &[[u8; 4]] = &[*b"eXIf", *b"tEXt", *b"zTXt", *b"iTXt", *b"tIME"];
+
+pub struct AttachmentService;
+
+impl AttachmentService {
+    /// Attach files to a trade.
+    ///
+    /// Each file's type is sniffed from its contents, and only images, PDFs
+    /// and plain text are accepted. Files are checked against the tier's
+    /// per-file limit and storage quota. Images have their metadata
+    /// stripped, with EXIF orientation applied to the pixels first, and get
+    /// a thumbnail. Nothing is stored unless every file is accepted.
+    pub async fn upload(
+        attachment_repo: &AttachmentRepository,
+        blobs: &dyn BlobStore,
+        user_id: Uuid,
+        tier: &SubscriptionTier,
+        trade_id: Uuid,
+        files: Vec<(String, Bytes)>,
+    ) -> Result<Vec<Attachment>> {
+        if files.is_empty() {
+            return Err(AppError::ValidationError(
+                "No files uploaded; send them as multipart form fields".to_string(),
+            ));
+        }
+
+        let max_bytes = tier.max_attachment_bytes();
+        if let Some((name, _)) = files.iter().find(|(_, data)| data.len() as i64 > max_bytes) {
+            return Err(AppError::ValidationError(format!(
+                "'{}' exceeds the {} MB limit per file of your plan",
+                name,
+                max_bytes / (1024 * 1024)
+            )));
+        }
+
+        let mut processed = Vec::with_capacity(files.len());
+        for (file_name, data) in files {
+            let upload = tokio::task::spawn_blocking(move || Self::process(&file_name, data))
+                .await
+                .map_err(|e| AppError::InternalServerError(format!("Attachment processing failed: {}", e)))??;
+            processed.push(upload);
+        }
+
+        // Fail fast before writing blobs; the quota is enforced when the
+        // records are created
+        let used = attachment_repo.storage_used(user_id).await?;
+        let added: i64 = processed.iter().map(|upload| upload.data.len() as i64).sum();
+        AttachmentRepository::check_quota(used, added, tier.attachment_quota_bytes())?;
+
+        let mut stored = Vec::with_capacity(processed.len());
+        let mut keys = Vec::new();
+        for upload in processed {
+            let id = Uuid::new_v4();
+            let storage_key = format!("{}/{}/{}", user_id, trade_id, id);
+            let thumbnail_key = upload.thumbnail.as_ref().map(|(_, content_type)| {
+                let extension = if *content_type == "image/png" { "png" } else { "jpg" };
+                format!("{}_thumb.{}", storage_key, extension)
+            });
+
+            let mut writes = vec![(storage_key.clone(), upload.content_type, upload.data.clone())];
+            if let (Some(key), Some((data, content_type))) = (&thumbnail_key, upload.thumbnail) {
+                writes.push((key.clone(), content_type, data));
+            }
+            for (key, content_type, data) in writes {
+                if let Err(e) = blobs.put(&key, content_type, data).await {
+                    Self::delete_blobs(blobs, &keys).await;
+                    return Err(e);
+                }
+                keys.push(key);
+            }
+
+            stored.push(NewAttachment {
+                id,
+                file_name: upload.file_name,
+                content_type: upload.content_type.to_string(),
+                size_bytes: upload.data.len() as i64,
+                width: upload.width,
+                height: upload.height,
+                storage_key,
+                thumbnail_key,
+            });
+        }
+
+        let quota = tier.attachment_quota_bytes();
+        match attachment_repo.create_many(trade_id, user_id, quota, &stored).await {
+            Ok(attachments) => Ok(attachments),
+            Err(e) => {
+                Self::delete_blobs(blobs, &keys).await;
+                Err(e)
+            }
+        }
+    }
+
+    /// Blob keys holding an attachment's file and thumbnail
+    pub fn keys(attachments: &[Attachment]) -> Vec<String> {
+        attachments
+            .iter()
+            .flat_map(|a| std::iter::once(a.storage_key.clone()).chain(a.thumbnail_key.clone()))
+            .collect()
+    }
+
+    /// Remove blobs, logging rather than failing on errors since their
+    /// records are already gone
+    pub async fn delete_blobs(blobs: &dyn BlobStore, keys: &[String]) {
+        for key in keys {
+            if let Err(e) = blobs.delete(key).await {
+                tracing::warn!("Failed to delete attachment blob {}: {}", key, e);
+            }
+        }
+    }
+
+    /// Sniff, clean and thumbnail one uploaded file
+    fn process(file_name: &str, data: Bytes) -> Result<ProcessedUpload> {
+        let file_name = Self::clean_file_name(file_name);
+        let content_type = Self::sniff(&data, &file_name).ok_or_else(|| {
+            AppError::ValidationError(format!(
+                "'{}' is not a supported file type; upload PNG, JPEG, GIF or WebP images, PDFs or text files",
+                file_name
+            ))
+        })?;
+
+        let format = match content_type {
+            "image/png" => ImageFormat::Png,
+            "image/jpeg" => ImageFormat::Jpeg,
+            "image/gif" => ImageFormat::Gif,
+            "image/webp" => ImageFormat::WebP,
+            _ => {
+                return Ok(ProcessedUpload {
+                    file_name,
+                    content_type,
+                    data,
+                    width: None,
+                    height: None,
+                    thumbnail: None,
+                });
+            }
+        };
+
+        let invalid = |e: image::ImageError| AppError::ValidationError(format!("'{}' is not a valid image: {}", file_name, e));
+
+        let mut limits = Limits::default();
+        limits.max_image_width = Some(MAX_IMAGE_DIMENSION);
+        limits.max_image_height = Some(MAX_IMAGE_DIMENSION);
+        limits.max_alloc = Some(MAX_DECODE_ALLOC);
+
+        let mut reader = ImageReader::with_format(Cursor::new(&data), format);
+        reader.limits(limits);
+        let mut decoder = reader.into_decoder().map_err(invalid)?;
+        let orientation = decoder.orientation().unwrap_or(Orientation::NoTransforms);
+        let mut image = DynamicImage::from_decoder(decoder).map_err(invalid)?;
+
+        // Dropping the EXIF orientation would turn the picture, so bake it
+        // into the pixels; otherwise strip the metadata without re-encoding
+        let data = if orientation != Orientation::NoTransforms {
+            image.apply_orientation(orientation);
+            Self::encode(&image, format).map_err(invalid)?
+        } else {
+            Self::strip_metadata(data, format)
+                .map_err(|e| AppError::ValidationError(format!("'{}' is not a valid image: {}", file_name, e)))?
+        };
+
+        let thumbnail = if image.width() > THUMBNAIL_SIZE || image.height() > THUMBNAIL_SIZE {
+            image.thumbnail(THUMBNAIL_SIZE, THUMBNAIL_SIZE)
+        } else {
+            image.clone()
+        };
+        let thumbnail_format = if thumbnail.color().has_alpha() { ImageFormat::Png } else { ImageFormat::Jpeg };
+        let thumbnail_type = if thumbnail_format == ImageFormat::Png { "image/png" } else { "image/jpeg" };
+        let thumbnail = Self::encode(&thumbnail, thumbnail_format).map_err(invalid)?;
+
+        Ok(ProcessedUpload {
+            file_name,
+            content_type,
+            data,
+            width: Some(image.width() as i32),
+            height: Some(image.height() as i32),
+            thumbnail: Some((thumbnail, thumbnail_type)),
+        })
+    }
+
+    /// Content type of a file judged by its leading bytes; text is told
+    /// apart from binary by being UTF-8 without control characters
+    fn sniff(data: &[u8], file_name: &str) -> Option<&'static str> {
+        if data.starts_with(b"\x89PNG\r\n\x1a\n") {
+            Some("image/png")
+        } else if data.starts_with(&[0xFF, 0xD8, 0xFF]) {
+            Some("image/jpeg")
+        } else if data.starts_with(b"GIF87a") || data.starts_with(b"GIF89a") {
+            Some("image/gif")
+        } else if data.len() >= 12 && data.starts_with(b"RIFF") && &data[8..12] == b"WEBP" {
+            Some("image/webp")
+        } else if data.starts_with(b"%PDF-") {
+            Some("application/pdf")
+        } else if !data.is_empty()
+            && std::str::from_utf8(data)
+                .is_ok_and(|text| !text.chars().any(|c| c.is_control() && !matches!(c, '\t' | '\n' | '\r')))
+        {
+            if file_name.to_lowercase().ends_with(".csv") {
+                Some("text/csv")
+            } else {
+                Some("text/plain")
+            }
+        } else {
+            None
+        }
+    }
+
+    /// Drop EXIF, XMP, IPTC, comment and text metadata without touching the
+    /// pixels. GIFs carry no EXIF and are kept as they are.
+    fn strip_metadata(data: Bytes, format: ImageFormat) -> std::result::Result<Bytes, img_parts::Error> {
+        let stripped = match format {
+            ImageFormat::Jpeg => {
+                let mut jpeg = Jpeg::from_bytes(data)?;
+                for marker in JPEG_METADATA_MARKERS {
+                    jpeg.remove_segments_by_marker(*marker);
+                }
+                jpeg.encoder().bytes()
+            }
+            ImageFormat::Png => {
+                let mut png = Png::from_bytes(data)?;
+                for kind in PNG_METADATA_CHUNKS {
+                    png.remove_chunks_by_type(*kind);
+                }
+                png.encoder().bytes()
+            }
+            ImageFormat::WebP => {
+                let mut webp = WebP::from_bytes(data)?;
+                webp.set_exif(None);
+                webp.remove_chunks_by_id(*b"XMP ");
+                webp.encoder().bytes()
+            }
+            _ => data,
+        };
+
+        Ok(stripped)
+    }
+
+    fn encode(image: &DynamicImage, format: ImageFormat) -> image::ImageResult<Bytes> {
+        let mut buffer = Vec::new();
+        match format {
+            ImageFormat::Jpeg => {
+                let rgb = DynamicImage::ImageRgb8(image.to_rgb8());
+                rgb.write_with_encoder(JpegEncoder::new_with_quality(&mut buffer, 85))?;
+            }
+            ImageFormat::Png => image.write_with_encoder(PngEncoder::new(&mut buffer))?,
+            other => image.write_to(&mut Cursor::new(&mut buffer), other)?,
+        }
+
+        Ok(Bytes::from(buffer))
+    }
+
+    /// Keep the last path component of a client file name, without control
+    /// characters and at most 255 bytes long
+    fn clean_file_name(file_name: &str) -> String {
+        let base = file_name.rsplit(['/', '\\']).next().unwrap_or_default();
+        let mut cleaned: String = base.chars().filter(|c| !c.is_control()).collect();
+        cleaned = cleaned.trim().to_string();
+        while cleaned.len() > 255 {
+            cleaned.pop();
+        }
+
+        if cleaned.is_empty() {
+            "attachment".to_string()
+        } else {
+            cleaned
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{GenericImageView, Rgb, RgbImage, Rgba, RgbaImage};
+    use img_parts::png::PngChunk;
+
+    /// Big-endian TIFF header with one IFD entry: Orientation (0x0112) as a
+    /// SHORT
+    fn exif_with_orientation(orientation: u8) -> Bytes {
+        let mut exif = b"MM\x00\x2a\x00\x00\x00\x08\x00\x01".to_vec();
+        exif.extend_from_slice(&[0x01, 0x12, 0x00, 0x03, 0x00, 0x00, 0x00, 0x01, 0x00, orientation, 0x00, 0x00]);
+        exif.extend_from_slice(&[0x00, 0x00, 0x00, 0x00]);
+        Bytes::from(exif)
+    }
+
+    fn jpeg(width: u32, height: u32, orientation: u8) -> Bytes {
+        let image = DynamicImage::ImageRgb8(RgbImage::from_pixel(width, height, Rgb([200, 40, 40])));
+        let encoded = AttachmentService::encode(&image, ImageFormat::Jpeg).unwrap();
+        let mut jpeg = Jpeg::from_bytes(encoded).unwrap();
+        jpeg.set_exif(Some(exif_with_orientation(orientation)));
+        jpeg.encoder().bytes()
+    }
+
+    fn decode(data: &Bytes) -> DynamicImage {
+        image::load_from_memory(data).unwrap()
+    }
+
+    #[test]
+    fn jpeg_metadata_is_stripped_and_thumbnailed() {
+        let upload = AttachmentService::process("chart.jpg", jpeg(800, 400, 1)).unwrap();
+
+        assert_eq!(upload.content_type, "image/jpeg");
+        assert_eq!((upload.width, upload.height), (Some(800), Some(400)));
+        assert!(Jpeg::from_bytes(upload.data.clone()).unwrap().exif().is_none());
+
+        let (thumbnail, content_type) = upload.thumbnail.unwrap();
+        assert_eq!(content_type, "image/jpeg");
+        assert_eq!(decode(&thumbnail).dimensions(), (320, 160));
+    }
+
+    #[test]
+    fn jpeg_orientation_is_applied_to_the_pixels() {
+        // 6 = rotate 90 degrees clockwise
+        let upload = AttachmentService::process("phone.jpg", jpeg(400, 200, 6)).unwrap();
+
+        assert_eq!((upload.width, upload.height), (Some(200), Some(400)));
+        assert!(Jpeg::from_bytes(upload.data.clone()).unwrap().exif().is_none());
+        assert_eq!(decode(&upload.data).dimensions(), (200, 400));
+        assert_eq!(decode(&upload.thumbnail.unwrap().0).dimensions(), (160, 320));
+    }
+
+    #[test]
+    fn png_text_chunks_are_stripped_and_small_images_keep_their_size() {
+        let image = DynamicImage::ImageRgba8(RgbaImage::from_pixel(100, 50, Rgba([0, 0, 255, 128])));
+        let encoded = AttachmentService::encode(&image, ImageFormat::Png).unwrap();
+        let mut png = Png::from_bytes(encoded).unwrap();
+        png.chunks_mut()
+            .insert(1, PngChunk::new(*b"tEXt", Bytes::from_static(b"Author\0someone")));
+        let data = png.encoder().bytes();
+
+        let upload = AttachmentService::process("levels.png", data).unwrap();
+
+        let stripped = Png::from_bytes(upload.data.clone()).unwrap();
+        assert_eq!(stripped.chunks_by_type(*b"tEXt").count(), 0);
+        assert_eq!(decode(&upload.data).dimensions(), (100, 50));
+
+        // Transparency keeps the thumbnail a PNG
+        let (thumbnail, content_type) = upload.thumbnail.unwrap();
+        assert_eq!(content_type, "image/png");
+        assert_eq!(decode(&thumbnail).dimensions(), (100, 50));
+    }
+
+    #[test]
+    fn text_passes_through_and_binary_is_rejected() {
+        let upload = AttachmentService::process("../fills.csv", Bytes::from_static(b"symbol,pnl\nES,100\n")).unwrap();
+        assert_eq!(upload.file_name, "fills.csv");
+        assert_eq!(upload.content_type, "text/csv");
+        assert!(upload.thumbnail.is_none());
+
+        let rejected = AttachmentService::process("blob.bin", Bytes::from_static(&[0x00, 0x01, 0x02]));
+        assert!(matches!(rejected, Err(AppError::ValidationError(_))));
+    }
+}
@@ -0,0 +1,381 @@
+use crate::{
+    error::{AppError, Result},
+    importers::journal::{encode_list, EXPORT_COLUMNS},
+    models::Trade,
+};
+use axum::body::{Body, Bytes};
+use futures_util::stream;
+use rust_decimal::{prelude::ToPrimitive, Decimal};
+use rust_xlsxwriter::{Workbook, XlsxError};
+use std::io::Seek;
+use tokio::sync::mpsc;
+use tokio_util::io::ReaderStream;
+
+/// Rows written per streamed chunk
+const CHUNK_ROWS: usize = 128;
+
+/// Supported export formats
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExportFormat {
+    Csv,
+    Json,
+    Xlsx,
+}
+
+impl ExportFormat {
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "text/csv; charset=utf-8",
+            ExportFormat::Json => "application/json",
+            ExportFormat::Xlsx => "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Json => "json",
+            ExportFormat::Xlsx => "xlsx",
+        }
+    }
+}
+
+impl TryFrom<&str> for ExportFormat {
+    type Error = AppError;
+
+    fn try_from(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "csv" => Ok(ExportFormat::Csv),
+            "json" => Ok(ExportFormat::Json),
+            "xlsx" => Ok(ExportFormat::Xlsx),
+            _ => Err(AppError::ValidationError(
+                "Format must be 'csv', 'json' or 'xlsx'".to_string(),
+            )),
+        }
+    }
+}
+
+/// Where the streaming encoder is in its output
+enum StreamState {
+    Start,
+    Rows { first: bool },
+    Done,
+}
+
+pub struct ExportService;
+
+impl ExportService {
+    /// Stream CSV or JSON as trades arrive from the repository.
+    ///
+    /// CSV uses the `EXPORT_COLUMNS` layout with array columns encoded by
+    /// `encode_list`, so the file can be imported back with the `journal`
+    /// importer. JSON is an array of `Trade` objects.
+    pub fn stream(rows: mpsc::Receiver<Result<Trade>>, format: ExportFormat) -> Body {
+        let chunks = stream::unfold((rows, StreamState::Start), move |(mut rows, state)| async move {
+            match state {
+                StreamState::Start => {
+                    let opening = match format {
+                        ExportFormat::Json => Bytes::from_static(b"["),
+                        _ => Bytes::from(Self::csv_line(EXPORT_COLUMNS.iter().copied())),
+                    };
+                    Some((Ok(opening), (rows, StreamState::Rows { first: true })))
+                }
+                StreamState::Rows { first } => {
+                    let mut batch = Vec::with_capacity(CHUNK_ROWS);
+                    if rows.recv_many(&mut batch, CHUNK_ROWS).await == 0 {
+                        let closing = match format {
+                            ExportFormat::Json => Bytes::from_static(b"]"),
+                            _ => Bytes::new(),
+                        };
+                        return Some((Ok(closing), (rows, StreamState::Done)));
+                    }
+
+                    match Self::encode_batch(batch, format, first) {
+                        Ok(chunk) => Some((Ok(chunk), (rows, StreamState::Rows { first: false }))),
+                        Err(e) => Some((Err(e), (rows, StreamState::Done))),
+                    }
+                }
+                StreamState::Done => None,
+            }
+        });
+
+        Body::from_stream(chunks)
+    }
+
+    /// Build an XLSX workbook row by row and stream the finished file.
+    ///
+    /// The XLSX container is a zip archive that can only be finished once
+    /// every row is known. The worksheet is written in constant-memory mode,
+    /// which moves each row to disk once written, and the workbook is saved
+    /// to a temporary file that is streamed from there, so memory use stays
+    /// flat however many trades are exported.
+    pub async fn xlsx(rows: mpsc::Receiver<Result<Trade>>) -> Result<Body> {
+        let file = tokio::task::spawn_blocking(move || Self::write_xlsx(rows))
+            .await
+            .map_err(|e| AppError::InternalServerError(format!("XLSX export failed: {}", e)))??;
+
+        Ok(Body::from_stream(ReaderStream::new(tokio::fs::File::from_std(file))))
+    }
+
+    /// Write the workbook to an unnamed temporary file, which is removed
+    /// once the returned handle is dropped
+    fn write_xlsx(mut rows: mpsc::Receiver<Result<Trade>>) -> Result<std::fs::File> {
+        let mut workbook = Workbook::new();
+        let worksheet = workbook.add_worksheet_with_constant_memory();
+
+        for (col, name) in EXPORT_COLUMNS.iter().enumerate() {
+            worksheet.write_string(0, col as u16, *name).map_err(xlsx_error)?;
+        }
+
+        let mut row_index: u32 = 1;
+        while let Some(trade) = rows.blocking_recv() {
+            for (col, value) in Self::fields(&trade?).into_iter().enumerate() {
+                let col = col as u16;
+                match value {
+                    Field::Number(n) => worksheet.write_number(row_index, col, decimal_to_f64(n)),
+                    Field::Text(t) => worksheet.write_string(row_index, col, t),
+                    Field::Empty => continue,
+                }
+                .map_err(xlsx_error)?;
+            }
+            row_index += 1;
+        }
+
+        let mut file = tempfile::tempfile().map_err(io_error)?;
+        workbook.save_to_writer(&mut file).map_err(xlsx_error)?;
+        file.rewind().map_err(io_error)?;
+
+        Ok(file)
+    }
+
+    fn encode_batch(batch: Vec<Result<Trade>>, format: ExportFormat, first: bool) -> Result<Bytes> {
+        let mut out = Vec::new();
+
+        for (i, trade) in batch.into_iter().enumerate() {
+            let trade = trade?;
+            match format {
+                ExportFormat::Json => {
+                    if !(first && i == 0) {
+                        out.push(b',');
+                    }
+                    serde_json::to_writer(&mut out, &trade)
+                        .map_err(|e| AppError::InternalServerError(format!("JSON export failed: {}", e)))?;
+                }
+                _ => {
+                    let cells: Vec<String> = Self::fields(&trade).iter().map(Field::to_cell).collect();
+                    out.extend(Self::csv_line(cells.iter().map(String::as_str)));
+                }
+            }
+        }
+
+        Ok(Bytes::from(out))
+    }
+
+    fn csv_line<'a>(fields: impl Iterator<Item = &'a str>) -> Vec<u8> {
+        let mut writer = csv::Writer::from_writer(Vec::new());
+        // Writing to a Vec cannot fail
+        let _ = writer.write_record(fields);
+        writer.into_inner().unwrap_or_default()
+    }
+
+    /// Trade values in `EXPORT_COLUMNS` order
+    fn fields(trade: &Trade) -> Vec<Field> {
+        use Field::{Empty, Number, Text};
+
+        let decimal = |d: Decimal| Number(d.normalize());
+        let opt_decimal = |d: Option<Decimal>| d.map(decimal).unwrap_or(Empty);
+        let opt_text = |s: &Option<String>| s.clone().map(Text).unwrap_or(Empty);
+
+        vec![
+            Text(trade.id.to_string()),
+            Text(trade.symbol.clone()),
+            opt_text(&trade.underlying),
+            Text(trade.direction.clone()),
+            decimal(trade.entry_price),
+            opt_decimal(trade.exit_price),
+            decimal(trade.quantity),
+            decimal(trade.open_quantity),
+            decimal(trade.multiplier),
+            Text(trade.entry_time.to_rfc3339()),
+            trade.exit_time.map(|t| Text(t.to_rfc3339())).unwrap_or(Empty),
+            opt_decimal(trade.pnl),
+            opt_decimal(trade.pnl_percentage),
+            decimal(trade.fees),
+            Text(trade.currency.clone()),
+            Text(trade.fee_currency.clone()),
+            decimal(trade.fee_fx_rate),
+            trade.fee_fx_date.map(|d| Text(d.to_string())).unwrap_or(Empty),
+            opt_decimal(trade.stop_loss),
+            opt_decimal(trade.initial_stop),
+            Text(encode_list(
+                &trade.take_profit.iter().map(|t| t.normalize().to_string()).collect::<Vec<_>>(),
+            )),
+            opt_decimal(trade.planned_risk),
+            opt_decimal(trade.r_multiple),
+            opt_decimal(trade.planned_r_multiple),
+            opt_decimal(trade.mae),
+            opt_decimal(trade.mfe),
+            opt_decimal(trade.entry_efficiency),
+            opt_decimal(trade.exit_efficiency),
+            opt_text(&trade.notes),
+            Text(encode_list(&trade.tags)),
+            opt_text(&trade.setup_type),
+            Text(encode_list(&trade.checklist_met)),
+            opt_text(&trade.strategy),
+            Text(encode_list(&trade.mistakes)),
+            Text(encode_list(&trade.emotions)),
+            Text(encode_list(&trade.screenshots)),
+            opt_text(&trade.broker),
+            trade.account_id.map(|id| Text(id.to_string())).unwrap_or(Empty),
+            opt_text(&trade.external_id),
+            Text(trade.status.clone()),
+            Text(trade.created_at.to_rfc3339()),
+            Text(trade.updated_at.to_rfc3339()),
+        ]
+    }
+}
+
+/// Cell value of an exported trade
+enum Field {
+    Number(Decimal),
+    Text(String),
+    Empty,
+}
+
+impl Field {
+    /// Exact textual form used in CSV
+    fn to_cell(&self) -> String {
+        match self {
+            Field::Number(n) => n.to_string(),
+            Field::Text(t) => t.clone(),
+            Field::Empty => String::new(),
+        }
+    }
+}
+
+/// XLSX only stores doubles; precision beyond ~15 digits is lost there
+fn decimal_to_f64(d: Decimal) -> f64 {
+    d.to_f64().unwrap_or(0.0)
+}
+
+fn xlsx_error(e: XlsxError) -> AppError {
+    AppError::InternalServerError(format!("XLSX export failed: {}", e))
+}
+
+fn io_error(e: std::io::Error) -> AppError {
+    AppError::InternalServerError(format!("XLSX export failed: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        importers::{JournalImporter, TradeImporter},
+        test_support::trade,
+    };
+    use std::io::Read;
+    use uuid::Uuid;
+
+    /// Trades whose text and list columns need quoting and escaping
+    fn trades() -> Vec<Trade> {
+        let mut first = trade();
+        first.notes = Some("Entered early, \"chased\"\nExit at target".to_string());
+        first.tags = vec!["breakout".to_string(), "a|b".to_string(), "back\\slash".to_string()];
+        first.setup_type = Some("Opening range".to_string());
+        first.checklist_met = vec!["Volume".to_string()];
+        first.strategy = Some("momentum".to_string());
+        first.mistakes = vec!["FOMO".to_string()];
+        first.emotions = vec!["calm".to_string()];
+        first.screenshots = vec![
+            format!("/api/trades/{}/attachments/{}", first.id, Uuid::new_v4()),
+            format!("/api/trades/{}/attachments/{}", first.id, Uuid::new_v4()),
+        ];
+        first.account_id = Some(Uuid::new_v4());
+        first.take_profit = vec![Decimal::from(160), Decimal::new(1655, 1)];
+        first.planned_risk = Some(Decimal::from(50));
+
+        let mut second = trade();
+        second.id = Uuid::new_v4();
+        second.direction = "short".to_string();
+        second.exit_price = None;
+        second.exit_time = None;
+        second.external_id = None;
+        second.broker = None;
+        second.tags = Vec::new();
+        second.take_profit = Vec::new();
+
+        vec![first, second]
+    }
+
+    fn channel(trades: Vec<Trade>) -> mpsc::Receiver<Result<Trade>> {
+        let (sender, receiver) = mpsc::channel(trades.len().max(1));
+        for trade in trades {
+            sender.try_send(Ok(trade)).unwrap();
+        }
+        receiver
+    }
+
+    #[tokio::test]
+    async fn csv_export_imports_back_unchanged() {
+        let trades = trades();
+        let body = ExportService::stream(channel(trades.clone()), ExportFormat::Csv);
+        let csv = String::from_utf8(axum::body::to_bytes(body, usize::MAX).await.unwrap().to_vec()).unwrap();
+
+        let parsed = JournalImporter.parse(&csv);
+
+        assert!(parsed.errors.is_empty(), "{:?}", parsed.errors);
+        assert_eq!(parsed.trades.len(), trades.len());
+        for (exported, imported) in trades.iter().zip(&parsed.trades) {
+            let imported = &imported.trade;
+            assert_eq!(imported.symbol, exported.symbol);
+            assert_eq!(imported.direction, exported.direction);
+            assert_eq!(imported.entry_price, exported.entry_price);
+            assert_eq!(imported.exit_price, exported.exit_price);
+            assert_eq!(imported.quantity, exported.quantity);
+            assert_eq!(imported.entry_time, exported.entry_time);
+            assert_eq!(imported.exit_time, exported.exit_time);
+            assert_eq!(imported.fees, Some(exported.fees));
+            assert_eq!(imported.currency.as_ref(), Some(&exported.currency));
+            assert_eq!(imported.fee_currency.as_ref(), Some(&exported.fee_currency));
+            assert_eq!(imported.stop_loss, exported.stop_loss);
+            assert_eq!(imported.initial_stop, exported.initial_stop);
+            assert_eq!(imported.take_profit.clone().unwrap_or_default(), exported.take_profit);
+            assert_eq!(imported.planned_risk, exported.planned_risk);
+            assert_eq!(imported.notes, exported.notes);
+            assert_eq!(imported.tags.clone().unwrap_or_default(), exported.tags);
+            assert_eq!(imported.setup_type, exported.setup_type);
+            assert_eq!(imported.checklist_met.clone().unwrap_or_default(), exported.checklist_met);
+            assert_eq!(imported.strategy, exported.strategy);
+            assert_eq!(imported.mistakes.clone().unwrap_or_default(), exported.mistakes);
+            assert_eq!(imported.emotions.clone().unwrap_or_default(), exported.emotions);
+            assert_eq!(imported.screenshots.clone().unwrap_or_default(), exported.screenshots);
+            assert_eq!(imported.broker, exported.broker);
+            assert_eq!(imported.account_id, exported.account_id);
+        }
+
+        // Trades entered by hand are recognized by their ID
+        assert_eq!(parsed.trades[0].trade.external_id.as_deref(), Some("T-1"));
+        assert_eq!(parsed.trades[1].trade.external_id, Some(trades[1].id.to_string()));
+    }
+
+    #[test]
+    fn xlsx_export_is_written_to_a_file() {
+        let mut file = ExportService::write_xlsx(channel(trades())).unwrap();
+
+        let mut content = Vec::new();
+        file.read_to_end(&mut content).unwrap();
+        // A zip archive, read from the start
+        assert!(content.starts_with(b"PK\x03\x04"));
+    }
+
+    #[test]
+    fn xlsx_export_fails_on_a_failed_row() {
+        let (sender, receiver) = mpsc::channel(1);
+        sender
+            .try_send(Err(AppError::InternalServerError("connection lost".to_string())))
+            .unwrap();
+        drop(sender);
+
+        assert!(ExportService::write_xlsx(receiver).is_err());
+    }
+}
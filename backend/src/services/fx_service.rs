@@ -0,0 +1,125 @@
+use crate::{
+    error::Result,
+    importers::{detect_delimiter, parse_decimal, CsvTable},
+    models::{CreateFxRateRequest, FxImportReport, ImportRowError},
+    repositories::FxRateRepository,
+};
+use chrono::NaiveDate;
+use csv::StringRecord;
+use rust_decimal::Decimal;
+use uuid::Uuid;
+
+/// Date formats accepted in rate files
+const DATE_FORMATS: &[&str] = &["%Y-%m-%d", "%Y/%m/%d", "%m/%d/%Y", "%d.%m.%Y"];
+
+pub struct FxService;
+
+impl FxService {
+    /// Import daily rates from CSV.
+    ///
+    /// Expects a `date` and a `rate` column, and the pair either as `base`
+    /// and `quote` columns or as one `pair` column (`EUR/USD` or `EURUSD`).
+    /// Rows for a pair and date already stored replace the old rate.
+    pub async fn import(fx_repo: &FxRateRepository, user_id: Uuid, content: &str) -> Result<FxImportReport> {
+        let mut report = FxImportReport {
+            total_rows: 0,
+            imported: 0,
+            failed: 0,
+            errors: Vec::new(),
+        };
+
+        let table = match Self::read_table(content) {
+            Ok(table) => table,
+            Err(error) => {
+                report.failed = 1;
+                report.errors.push(error);
+                return Ok(report);
+            }
+        };
+
+        report.total_rows = table.rows.len();
+        for (row, record) in &table.rows {
+            match Self::parse_row(&table, record) {
+                Ok(rate) => {
+                    fx_repo.upsert(user_id, &rate, "import").await?;
+                    report.imported += 1;
+                }
+                Err(message) => report.errors.push(ImportRowError { row: *row, message }),
+            }
+        }
+        report.failed = report.errors.len();
+
+        Ok(report)
+    }
+
+    fn read_table(content: &str) -> std::result::Result<CsvTable, ImportRowError> {
+        let table = CsvTable::read(content, detect_delimiter(content))?;
+        table.require(&["date", "rate"])?;
+
+        let has_pair = table.has_any(&["pair"]) || (table.has_any(&["base"]) && table.has_any(&["quote"]));
+        if !has_pair {
+            return Err(ImportRowError {
+                row: 1,
+                message: "Missing columns: pair, or base and quote".to_string(),
+            });
+        }
+
+        Ok(table)
+    }
+
+    fn parse_row(table: &CsvTable, record: &StringRecord) -> std::result::Result<CreateFxRateRequest, String> {
+        let (base, quote) = match table.optional(record, "pair") {
+            Some(pair) => Self::split_pair(pair)?,
+            None => (
+                table.field(record, "base")?.to_string(),
+                table.field(record, "quote")?.to_string(),
+            ),
+        };
+
+        let base_currency = Self::currency_code(&base)?;
+        let quote_currency = Self::currency_code(&quote)?;
+        if base_currency == quote_currency {
+            return Err("Base and quote currency must differ".to_string());
+        }
+
+        let date = table.field(record, "date")?;
+        let rate_date = DATE_FORMATS
+            .iter()
+            .find_map(|format| NaiveDate::parse_from_str(date, format).ok())
+            .ok_or_else(|| format!("Invalid date '{}' in 'date'", date))?;
+
+        let rate = parse_decimal(table.field(record, "rate")?, "rate", table.delimiter)?;
+        if rate <= Decimal::ZERO {
+            return Err("Rate must be positive".to_string());
+        }
+
+        Ok(CreateFxRateRequest {
+            base_currency,
+            quote_currency,
+            rate_date,
+            rate,
+        })
+    }
+
+    /// Split `EUR/USD`, `EUR-USD` or `EURUSD` into its currencies
+    fn split_pair(pair: &str) -> std::result::Result<(String, String), String> {
+        if let Some((base, quote)) = pair.split_once(['/', '-']) {
+            return Ok((base.trim().to_string(), quote.trim().to_string()));
+        }
+
+        if pair.len() == 6 && pair.is_ascii() {
+            return Ok((pair[..3].to_string(), pair[3..].to_string()));
+        }
+
+        Err(format!("Invalid currency pair '{}'", pair))
+    }
+
+    /// Check a 3-letter code and return it upper-cased
+    fn currency_code(code: &str) -> std::result::Result<String, String> {
+        if code.len() != 3 || !code.chars().all(|c| c.is_ascii_alphabetic()) {
+            return Err(format!("Invalid currency '{}'", code));
+        }
+
+        Ok(code.to_uppercase())
+    }
+}
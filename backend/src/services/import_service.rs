@@ -0,0 +1,296 @@
+use crate::{
+    error::{AppError, Result},
+    importers::{ImportedTrade, TradeImporter},
+    models::{
+        round_trip_pnl, CreateTradeRequest, ExecutionSummary, ImportPreview, ImportReport, ImportRowError,
+    },
+    repositories::{InstrumentRepository, TradeRepository},
+    services::InstrumentService,
+};
+use rust_decimal::Decimal;
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+pub struct ImportService;
+
+impl ImportService {
+    /// Parse a broker export and create its trades.
+    ///
+    /// Trades whose external ID is already stored for the same broker (or
+    /// appears twice in the file) are skipped. With `dry_run` nothing is written and
+    /// the report previews what would be imported.
+    pub async fn import(
+        trade_repo: &TradeRepository,
+        instrument_repo: &InstrumentRepository,
+        user_id: Uuid,
+        importer: &dyn TradeImporter,
+        content: &str,
+        account_id: Option<Uuid>,
+        dry_run: bool,
+    ) -> Result<ImportReport> {
+        let parsed = importer.parse(content);
+        let broker = importer.broker();
+
+        let external_ids: Vec<String> = parsed
+            .trades
+            .iter()
+            .filter_map(|t| t.trade.external_id.clone())
+            .collect();
+        let mut seen = trade_repo.find_external_ids(user_id, &external_ids).await?;
+
+        let mut report = ImportReport {
+            broker: broker.to_string(),
+            dry_run,
+            total_rows: parsed.total_rows,
+            imported: 0,
+            duplicates: 0,
+            failed: 0,
+            trades: Vec::new(),
+            errors: parsed.errors,
+        };
+
+        let mut multipliers: HashMap<String, Decimal> = HashMap::new();
+
+        for ImportedTrade { row, mut trade } in parsed.trades {
+            let multiplier = match multipliers.get(&trade.symbol) {
+                Some(multiplier) => *multiplier,
+                None => {
+                    let instrument = instrument_repo.resolve(user_id, &trade.symbol).await?;
+                    let multiplier = InstrumentService::multiplier(instrument.as_ref(), &trade.symbol);
+                    multipliers.insert(trade.symbol.clone(), multiplier);
+                    multiplier
+                }
+            };
+
+            let mut preview = match Self::preview(row, &trade, multiplier) {
+                Ok(preview) => preview,
+                Err(message) => {
+                    report.errors.push(ImportRowError { row, message });
+                    continue;
+                }
+            };
+
+            let is_duplicate = match &trade.external_id {
+                Some(id) => !seen.insert((trade.broker.clone(), id.clone())),
+                None => false,
+            };
+
+            if is_duplicate {
+                report.duplicates += 1;
+                preview.status = "duplicate".to_string();
+            } else if dry_run {
+                preview.status = "new".to_string();
+            } else {
+                if trade.account_id.is_none() {
+                    trade.account_id = account_id;
+                }
+
+                match trade_repo.create(user_id, trade).await {
+                    Ok(_) => {
+                        report.imported += 1;
+                        preview.status = "imported".to_string();
+                    }
+                    // Lost a race with a concurrent import of the same file
+                    Err(AppError::DatabaseError(sqlx::Error::Database(e))) if e.is_unique_violation() => {
+                        report.duplicates += 1;
+                        preview.status = "duplicate".to_string();
+                    }
+                    Err(AppError::ValidationError(message)) => {
+                        report.errors.push(ImportRowError { row, message });
+                        continue;
+                    }
+                    Err(e) => return Err(e),
+                }
+            }
+
+            report.trades.push(preview);
+        }
+
+        report.failed = report
+            .errors
+            .iter()
+            .map(|e| e.row)
+            .collect::<HashSet<_>>()
+            .len();
+
+        Ok(report)
+    }
+
+    /// Check a parsed trade like the trade handler does and derive the
+    /// values it would be stored with
+    fn preview(
+        row: usize,
+        trade: &CreateTradeRequest,
+        multiplier: Decimal,
+    ) -> std::result::Result<ImportPreview, String> {
+        let message = |e: AppError| match e {
+            AppError::ValidationError(message) => message,
+            other => other.to_string(),
+        };
+
+        trade.validate().map_err(message)?;
+
+        let (entry_price, exit_price, quantity, pnl) = match &trade.executions {
+            Some(executions) => {
+                let summary = ExecutionSummary::from_executions(
+                    &trade.direction,
+                    multiplier,
+                    Decimal::ONE,
+                    executions,
+                )
+                .map_err(message)?;
+                (
+                    summary.entry_price,
+                    summary.exit_price,
+                    summary.quantity,
+                    summary.pnl,
+                )
+            }
+            None => {
+                let pnl = trade.exit_price.map(|exit_price| {
+                    let fees = trade.fees.unwrap_or_default();
+                    round_trip_pnl(
+                        &trade.direction,
+                        trade.entry_price,
+                        exit_price,
+                        trade.quantity,
+                        multiplier,
+                        fees,
+                    )
+                    .0
+                });
+                (trade.entry_price, trade.exit_price, trade.quantity, pnl)
+            }
+        };
+
+        Ok(ImportPreview {
+            row,
+            external_id: trade.external_id.clone(),
+            symbol: trade.symbol.clone(),
+            direction: trade.direction.clone(),
+            entry_time: trade.entry_time,
+            entry_price,
+            exit_price,
+            quantity,
+            pnl,
+            status: String::new(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{importers::JournalImporter, test_support};
+
+    /// Journal export with one trade, a row repeating it, and three bad rows
+    const EXPORT: &str = "\
+symbol,direction,entry_price,quantity,entry_time,exit_price,exit_time,fees,external_id
+AAPL,long,150,10,2025-01-02T15:00:00Z,155,2025-01-02T18:00:00Z,2,J1
+MSFT,sideways,400,5,2025-01-03T15:00:00Z,,,,J2
+TSLA,long,250,0,2025-01-03T15:00:00Z,,,,J3
+AAPL,long,150,10,2025-01-02T15:00:00Z,155,2025-01-02T18:00:00Z,2,J1
+NVDA,long,abc,1,2025-01-03T15:00:00Z,,,,J4
+";
+
+    async fn import(pool: &sqlx::PgPool, user_id: Uuid, dry_run: bool) -> ImportReport {
+        ImportService::import(
+            &TradeRepository::new(pool.clone()),
+            &InstrumentRepository::new(pool.clone()),
+            user_id,
+            &JournalImporter,
+            EXPORT,
+            None,
+            dry_run,
+        )
+        .await
+        .unwrap()
+    }
+
+    async fn trade_count(pool: &sqlx::PgPool, user_id: Uuid) -> i64 {
+        sqlx::query_scalar("SELECT COUNT(*) FROM trades WHERE user_id = $1")
+            .bind(user_id)
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    #[test]
+    fn preview_derives_pnl_and_rejects_invalid_trades() {
+        let parsed = JournalImporter.parse(EXPORT);
+        let trades: Vec<&ImportedTrade> = parsed.trades.iter().collect();
+
+        let preview = ImportService::preview(2, &trades[0].trade, Decimal::ONE).unwrap();
+        assert_eq!(preview.pnl, Some(Decimal::from(48)));
+        assert_eq!(preview.external_id.as_deref(), Some("J1"));
+
+        let error = ImportService::preview(3, &trades[1].trade, Decimal::ONE).unwrap_err();
+        assert_eq!(error, "Direction must be 'long' or 'short'");
+        let error = ImportService::preview(4, &trades[2].trade, Decimal::ONE).unwrap_err();
+        assert_eq!(error, "Quantity must be positive or executions or legs must be provided");
+    }
+
+    #[tokio::test]
+    #[ignore = "needs PostgreSQL: `docker compose up -d postgres`, then `cargo test -- --ignored`"]
+    async fn dry_run_previews_without_writing() {
+        let pool = test_support::pool().await;
+        let user_id = test_support::user(&pool).await;
+
+        let report = import(&pool, user_id, true).await;
+
+        assert!(report.dry_run);
+        assert_eq!(report.total_rows, 5);
+        assert_eq!(report.imported, 0);
+        let statuses: Vec<(usize, &str)> = report.trades.iter().map(|t| (t.row, t.status.as_str())).collect();
+        assert_eq!(statuses, [(2, "new"), (5, "duplicate")]);
+        assert_eq!(report.duplicates, 1);
+        assert_eq!(trade_count(&pool, user_id).await, 0);
+
+        test_support::delete_user(&pool, user_id).await;
+    }
+
+    #[tokio::test]
+    #[ignore = "needs PostgreSQL: `docker compose up -d postgres`, then `cargo test -- --ignored`"]
+    async fn importing_again_skips_stored_trades() {
+        let pool = test_support::pool().await;
+        let user_id = test_support::user(&pool).await;
+
+        let first = import(&pool, user_id, false).await;
+        assert_eq!(first.imported, 1);
+        assert_eq!(first.duplicates, 1);
+        assert_eq!(first.trades[0].status, "imported");
+
+        let second = import(&pool, user_id, false).await;
+        assert_eq!(second.imported, 0);
+        assert_eq!(second.duplicates, 2);
+        assert!(second.trades.iter().all(|t| t.status == "duplicate"));
+        assert_eq!(trade_count(&pool, user_id).await, 1);
+
+        test_support::delete_user(&pool, user_id).await;
+    }
+
+    #[tokio::test]
+    #[ignore = "needs PostgreSQL: `docker compose up -d postgres`, then `cargo test -- --ignored`"]
+    async fn bad_rows_are_reported_by_line() {
+        let pool = test_support::pool().await;
+        let user_id = test_support::user(&pool).await;
+
+        let report = import(&pool, user_id, false).await;
+
+        let mut errors: Vec<(usize, &str)> = report.errors.iter().map(|e| (e.row, e.message.as_str())).collect();
+        errors.sort();
+        assert_eq!(
+            errors,
+            [
+                (3, "Direction must be 'long' or 'short'"),
+                (4, "Quantity must be positive or executions or legs must be provided"),
+                (6, "Invalid number 'abc' in 'entry_price'"),
+            ]
+        );
+        assert_eq!(report.failed, 3);
+        // Bad rows don't stop the good one
+        assert_eq!(report.imported, 1);
+
+        test_support::delete_user(&pool, user_id).await;
+    }
+}
@@ -0,0 +1,80 @@
+use crate::models::{Instrument, OPTION_MULTIPLIER};
+use rust_decimal::Decimal;
+
+/// Futures month codes, January to December
+const MONTH_CODES: &str = "FGHJKMNQUVXZ";
+
+pub struct InstrumentService;
+
+impl InstrumentService {
+    /// Catalog symbols that may describe a traded symbol, most specific first.
+    ///
+    /// Dated futures resolve to their root: `ESH5`, `ESZ24` and NinjaTrader's
+    /// `ES 03-25` all fall back to `ES`.
+    pub fn symbol_candidates(symbol: &str) -> Vec<String> {
+        let symbol = symbol.trim().to_uppercase();
+        let mut candidates = vec![symbol.clone()];
+
+        if let Some((root, _)) = symbol.split_once(' ') {
+            candidates.push(root.to_string());
+        } else if let Some(root) = Self::futures_root(&symbol) {
+            candidates.push(root.to_string());
+        }
+
+        candidates
+    }
+
+    /// Multiplier to use for a symbol.
+    ///
+    /// Falls back to 100 for OCC option symbols and to 1 for anything else
+    /// that isn't in the catalog.
+    pub fn multiplier(instrument: Option<&Instrument>, symbol: &str) -> Decimal {
+        match instrument {
+            Some(instrument) => instrument.multiplier,
+            None if Self::is_occ_option(symbol) => Decimal::from(OPTION_MULTIPLIER),
+            None => Decimal::ONE,
+        }
+    }
+
+    /// Strip a month code and one- or two-digit year (`ESH5` -> `ES`)
+    fn futures_root(symbol: &str) -> Option<&str> {
+        let digits = symbol.chars().rev().take_while(|c| c.is_ascii_digit()).count();
+        if !(1..=2).contains(&digits) {
+            return None;
+        }
+
+        let without_year = &symbol[..symbol.len() - digits];
+        let month = without_year.chars().last()?;
+        if !MONTH_CODES.contains(month) {
+            return None;
+        }
+
+        let root = &without_year[..without_year.len() - 1];
+        (!root.is_empty() && root.len() <= 3).then_some(root)
+    }
+
+    /// Underlying of an OCC option symbol (`AAPL  250117C00150000` -> `AAPL`)
+    pub fn option_underlying(symbol: &str) -> Option<String> {
+        let compact: String = symbol.chars().filter(|c| !c.is_whitespace()).collect();
+        if compact.len() < 16 || !compact.is_ascii() {
+            return None;
+        }
+
+        let (head, strike) = compact.split_at(compact.len() - 8);
+        let (head, right) = head.split_at(head.len() - 1);
+        let (root, expiry) = head.split_at(head.len() - 6);
+
+        let valid = !root.is_empty()
+            && root.chars().all(|c| c.is_ascii_alphanumeric())
+            && expiry.chars().all(|c| c.is_ascii_digit())
+            && matches!(right, "C" | "P")
+            && strike.chars().all(|c| c.is_ascii_digit());
+
+        valid.then(|| root.to_uppercase())
+    }
+
+    /// OCC option symbol: root, `YYMMDD`, `C` or `P`, and an 8-digit strike
+    fn is_occ_option(symbol: &str) -> bool {
+        Self::option_underlying(symbol).is_some()
+    }
+}
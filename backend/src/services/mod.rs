@@ -1,8 +1,31 @@
 pub mod analytics_service;
+pub mod attachment_service;
+pub mod export_service;
+pub mod fx_service;
+pub mod import_service;
+pub mod instrument_service;
+pub mod price_bar_service;
+pub mod quote_service;
+pub mod revision_service;
 pub mod stripe_service;
 
 pub use analytics_service::{
-    AnalyticsService, MistakeAnalysis, SetupPerformance, SymbolPerformance, TradeAnalytics,
+    AccountEquityPoint, AccountPerformance, AnalyticsService, CalendarBucket, CalendarDay, CalendarMonth,
+    CalendarTotals, CalendarWeek, ChecklistItemPerformance, ComplianceTotals,
+    DrawdownPeriod, EquityBucket, EquityCurve, EquityInterval, EquityPoint, ExcursionAnalytics,
+    ExcursionPoint, Exposure, GroupTotals, JournalAnalytics, JournalDay, JournalGroup, MistakeAnalysis,
+    MistakeTotals, OpenPosition, OpenPositions, OverviewTotals, PlaybookPerformance, PlaybookTrade, RBucket,
+    RMultipleAnalytics, RMultipleTotals,
+    RiskMetrics, SetupPerformance, SymbolPerformance, TimeBreakdowns, TimeBucket, TimeUnderWater, TimedTrade,
+    TradeAnalytics, TradingSession,
 };
+pub use attachment_service::AttachmentService;
+pub use export_service::{ExportFormat, ExportService};
+pub use fx_service::FxService;
+pub use import_service::ImportService;
+pub use instrument_service::InstrumentService;
+pub use price_bar_service::PriceBarService;
+pub use quote_service::QuoteService;
+pub use revision_service::RevisionService;
 pub use stripe_service::{StripeService, WebhookAction};
 
@@ -0,0 +1,210 @@
+use crate::{
+    error::Result,
+    importers::{detect_delimiter, parse_decimal, parse_utc, CsvTable},
+    models::{CreatePriceBarRequest, ImportRowError, PriceBarImportReport},
+    repositories::{PriceBarRepository, TradeRepository},
+};
+use axum::body::Bytes;
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use csv::StringRecord;
+use parquet::{
+    file::reader::{FileReader, SerializedFileReader},
+    record::Field,
+};
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+/// Columns holding the bar start time, in order of preference
+const TIME_COLUMNS: &[&str] = &["time", "timestamp", "datetime", "date"];
+
+/// Timestamp formats accepted besides RFC 3339; times without an offset are
+/// taken as UTC
+const TIME_FORMATS: &[&str] = &["%Y-%m-%d %H:%M:%S", "%Y-%m-%d %H:%M", "%Y-%m-%dT%H:%M:%S", "%Y.%m.%d %H:%M"];
+
+/// Date formats of daily bars
+const DATE_FORMATS: &[&str] = &["%Y-%m-%d", "%Y/%m/%d", "%m/%d/%Y"];
+
+/// Magic bytes a Parquet file starts with
+const PARQUET_MAGIC: &[u8] = b"PAR1";
+
+pub struct PriceBarService;
+
+impl PriceBarService {
+    /// Import OHLC bars of one symbol and timeframe from CSV or Parquet.
+    ///
+    /// Expects a time column (`time`, `timestamp`, `datetime` or `date`),
+    /// `open`, `high`, `low` and `close`, and optionally `volume`. Times may
+    /// be timestamps, dates or Unix seconds or milliseconds. Bars already
+    /// stored for the same start time are replaced, and the excursions of
+    /// closed trades the bars cover are recalculated.
+    pub async fn import(
+        bar_repo: &PriceBarRepository,
+        trade_repo: &TradeRepository,
+        user_id: Uuid,
+        symbol: &str,
+        timeframe: &str,
+        content: Bytes,
+    ) -> Result<PriceBarImportReport> {
+        let mut report = PriceBarImportReport {
+            total_rows: 0,
+            imported: 0,
+            failed: 0,
+            errors: Vec::new(),
+            trades_updated: 0,
+        };
+
+        let table = match Self::read_table(content) {
+            Ok(table) => table,
+            Err(error) => {
+                report.failed = 1;
+                report.errors.push(error);
+                return Ok(report);
+            }
+        };
+
+        // Keyed by start time so a repeated bar keeps the last row
+        let mut bars = BTreeMap::new();
+        report.total_rows = table.rows.len();
+        for (row, record) in &table.rows {
+            match Self::parse_row(&table, record) {
+                Ok(bar) => {
+                    bars.insert(bar.bar_time, bar);
+                    report.imported += 1;
+                }
+                Err(message) => report.errors.push(ImportRowError { row: *row, message }),
+            }
+        }
+        report.failed = report.errors.len();
+
+        let (Some(first), Some(last)) = (bars.keys().next().copied(), bars.keys().next_back().copied()) else {
+            return Ok(report);
+        };
+
+        let bars: Vec<CreatePriceBarRequest> = bars.into_values().collect();
+        bar_repo.upsert_many(user_id, symbol, timeframe, &bars).await?;
+
+        // No timeframe is longer than a day, so this covers the last bar
+        report.trades_updated = trade_repo
+            .refresh_excursions(user_id, symbol, Some(first), Some(last + Duration::days(1)))
+            .await?;
+
+        Ok(report)
+    }
+
+    fn read_table(content: Bytes) -> std::result::Result<CsvTable, ImportRowError> {
+        let table = if content.starts_with(PARQUET_MAGIC) {
+            Self::read_parquet(content)?
+        } else {
+            let content = std::str::from_utf8(&content).map_err(|_| ImportRowError {
+                row: 1,
+                message: "File is neither Parquet nor UTF-8 CSV".to_string(),
+            })?;
+            CsvTable::read(content, detect_delimiter(content))?
+        };
+
+        table.require(&["open", "high", "low", "close"])?;
+        if !table.has_any(TIME_COLUMNS) {
+            return Err(ImportRowError {
+                row: 1,
+                message: format!("Missing columns: one of {}", TIME_COLUMNS.join(", ")),
+            });
+        }
+
+        Ok(table)
+    }
+
+    /// Read a Parquet file's top-level columns as text; rows are numbered
+    /// from 1
+    fn read_parquet(content: Bytes) -> std::result::Result<CsvTable, ImportRowError> {
+        let invalid = |e: parquet::errors::ParquetError| ImportRowError {
+            row: 1,
+            message: format!("Invalid Parquet file: {}", e),
+        };
+
+        let reader = SerializedFileReader::new(content).map_err(invalid)?;
+        let headers: Vec<String> = reader
+            .metadata()
+            .file_metadata()
+            .schema_descr()
+            .root_schema()
+            .get_fields()
+            .iter()
+            .map(|field| field.name().to_string())
+            .collect();
+
+        let mut rows = Vec::new();
+        for (i, row) in reader.get_row_iter(None).map_err(invalid)?.enumerate() {
+            let row = row.map_err(|e| ImportRowError {
+                row: i + 1,
+                message: format!("Invalid Parquet row: {}", e),
+            })?;
+            let record: StringRecord = row.get_column_iter().map(|(_, field)| Self::parquet_text(field)).collect();
+            rows.push((i + 1, record));
+        }
+
+        Ok(CsvTable::from_records(&headers, rows))
+    }
+
+    /// Text of a Parquet value as it would appear in a CSV cell
+    fn parquet_text(field: &Field) -> String {
+        match field {
+            Field::Null => String::new(),
+            Field::Str(value) => value.clone(),
+            Field::Float(value) => value.to_string(),
+            Field::Double(value) => value.to_string(),
+            Field::TimestampMillis(value) => DateTime::from_timestamp_millis(*value)
+                .map(|t| t.to_rfc3339())
+                .unwrap_or_default(),
+            Field::TimestampMicros(value) => DateTime::from_timestamp_micros(*value)
+                .map(|t| t.to_rfc3339())
+                .unwrap_or_default(),
+            other => other.to_string(),
+        }
+    }
+
+    fn parse_row(table: &CsvTable, record: &StringRecord) -> std::result::Result<CreatePriceBarRequest, String> {
+        let bar_time = Self::parse_time(table.field_any(record, TIME_COLUMNS)?)?;
+        let price = |column: &str| parse_decimal(table.field(record, column)?, column, table.delimiter);
+
+        let bar = CreatePriceBarRequest {
+            bar_time,
+            open: price("open")?,
+            high: price("high")?,
+            low: price("low")?,
+            close: price("close")?,
+            volume: table
+                .optional(record, "volume")
+                .map(|v| parse_decimal(v, "volume", table.delimiter))
+                .transpose()?,
+        };
+
+        let within = |value| bar.low <= value && value <= bar.high;
+        if !within(bar.open) || !within(bar.close) {
+            return Err("Open and close must lie between low and high".to_string());
+        }
+
+        Ok(bar)
+    }
+
+    /// Parse a bar start time: a timestamp, a date (midnight UTC), or Unix
+    /// seconds or milliseconds
+    fn parse_time(value: &str) -> std::result::Result<DateTime<Utc>, String> {
+        if let Ok(epoch) = value.parse::<i64>() {
+            let time = if epoch.abs() < 100_000_000_000 {
+                DateTime::from_timestamp(epoch, 0)
+            } else {
+                DateTime::from_timestamp_millis(epoch)
+            };
+            return time.ok_or_else(|| format!("Invalid timestamp '{}' in 'time'", value));
+        }
+
+        parse_utc(value, TIME_FORMATS, "time").or_else(|error| {
+            DATE_FORMATS
+                .iter()
+                .find_map(|format| NaiveDate::parse_from_str(value, format).ok())
+                .and_then(|date| date.and_hms_opt(0, 0, 0))
+                .map(|naive| naive.and_utc())
+                .ok_or(error)
+        })
+    }
+}
@@ -0,0 +1,100 @@
+use crate::{
+    error::Result,
+    importers::{detect_delimiter, parse_decimal, parse_utc, CsvTable},
+    models::{ImportRowError, Quote, QuoteImportReport},
+    repositories::QuoteRepository,
+};
+use chrono::Utc;
+use csv::StringRecord;
+use rust_decimal::Decimal;
+use uuid::Uuid;
+
+/// Columns holding the quote time, in order of preference
+const TIME_COLUMNS: &[&str] = &["as_of", "time", "timestamp", "datetime"];
+
+/// Timestamp formats accepted besides RFC 3339; times without an offset are
+/// taken as UTC
+const TIME_FORMATS: &[&str] = &["%Y-%m-%d %H:%M:%S", "%Y-%m-%d %H:%M", "%Y-%m-%dT%H:%M:%S"];
+
+pub struct QuoteService;
+
+impl QuoteService {
+    /// Import prices from CSV.
+    ///
+    /// Expects `symbol` and `price` (or `last`) columns and optionally a
+    /// time column (`as_of`, `time`, `timestamp` or `datetime`); rows
+    /// without a time are taken as current. Rows older than the stored
+    /// quote of their symbol are skipped.
+    pub async fn import(quote_repo: &QuoteRepository, user_id: Uuid, content: &str) -> Result<QuoteImportReport> {
+        let mut report = QuoteImportReport {
+            total_rows: 0,
+            imported: 0,
+            skipped: 0,
+            failed: 0,
+            errors: Vec::new(),
+        };
+
+        let table = match Self::read_table(content) {
+            Ok(table) => table,
+            Err(error) => {
+                report.failed = 1;
+                report.errors.push(error);
+                return Ok(report);
+            }
+        };
+
+        report.total_rows = table.rows.len();
+        for (row, record) in &table.rows {
+            match Self::parse_row(&table, record) {
+                Ok(quote) => {
+                    if quote_repo.upsert(user_id, &quote, "import").await? {
+                        report.imported += 1;
+                    } else {
+                        report.skipped += 1;
+                    }
+                }
+                Err(message) => report.errors.push(ImportRowError { row: *row, message }),
+            }
+        }
+        report.failed = report.errors.len();
+
+        Ok(report)
+    }
+
+    fn read_table(content: &str) -> std::result::Result<CsvTable, ImportRowError> {
+        let table = CsvTable::read(content, detect_delimiter(content))?;
+        table.require(&["symbol"])?;
+
+        if !table.has_any(&["price", "last"]) {
+            return Err(ImportRowError {
+                row: 1,
+                message: "Missing columns: price or last".to_string(),
+            });
+        }
+
+        Ok(table)
+    }
+
+    fn parse_row(table: &CsvTable, record: &StringRecord) -> std::result::Result<Quote, String> {
+        let symbol = table.field(record, "symbol")?.trim();
+        if symbol.is_empty() || symbol.len() > 50 {
+            return Err("Symbol must be 1 to 50 characters".to_string());
+        }
+
+        let price = parse_decimal(table.field_any(record, &["price", "last"])?, "price", table.delimiter)?;
+        if price <= Decimal::ZERO {
+            return Err("Price must be positive".to_string());
+        }
+
+        let as_of = match TIME_COLUMNS.iter().find_map(|column| table.optional(record, column)) {
+            Some(time) => parse_utc(time, TIME_FORMATS, "as_of")?,
+            None => Utc::now(),
+        };
+
+        Ok(Quote {
+            symbol: symbol.to_string(),
+            price,
+            as_of,
+        })
+    }
+}
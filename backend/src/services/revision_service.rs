@@ -0,0 +1,177 @@
+use crate::{
+    error::{AppError, Result},
+    models::{Trade, TradeRevision},
+};
+use serde_json::{json, Map, Value};
+
+/// Fields that change on every write and say nothing about the trade
+const IGNORED_FIELDS: &[&str] = &["updated_at"];
+
+/// Fields a restore never takes from a revision
+const KEPT_FIELDS: &[&str] = &["id", "user_id", "created_at", "updated_at"];
+
+pub struct RevisionService;
+
+impl RevisionService {
+    /// Fields that differ between two states of a trade, as
+    /// `{"field": {"from": old, "to": new}}`. A missing state counts as
+    /// every field being null, so creates and deletes list all fields that
+    /// are set.
+    pub fn diff(before: Option<&Trade>, after: Option<&Trade>) -> Result<Map<String, Value>> {
+        let before = Self::fields(before)?;
+        let after = Self::fields(after)?;
+
+        let mut names: Vec<&String> = before.keys().chain(after.keys()).collect();
+        names.sort();
+        names.dedup();
+
+        let changes = names
+            .into_iter()
+            .filter(|name| !IGNORED_FIELDS.contains(&name.as_str()))
+            .filter_map(|name| {
+                let from = before.get(name).unwrap_or(&Value::Null);
+                let to = after.get(name).unwrap_or(&Value::Null);
+                (from != to).then(|| (name.clone(), json!({ "from": from, "to": to })))
+            })
+            .collect();
+
+        Ok(changes)
+    }
+
+    /// The trade as it was right after `revision`: the current trade with
+    /// every later change rolled back, newest first. Identity fields are
+    /// kept, and so are changes made outside the recorded revisions.
+    pub fn restore(current: &Trade, revisions: &[TradeRevision], revision: i32) -> Result<Trade> {
+        let target = revisions
+            .iter()
+            .find(|r| r.revision == revision)
+            .ok_or(AppError::ValidationError("Revision not found".to_string()))?;
+        if target.action == "delete" {
+            return Err(AppError::ValidationError(
+                "The trade was deleted at that revision".to_string(),
+            ));
+        }
+
+        let mut later: Vec<&TradeRevision> = revisions.iter().filter(|r| r.revision > revision).collect();
+        later.sort_by_key(|r| std::cmp::Reverse(r.revision));
+
+        let mut fields = Self::fields(Some(current))?;
+        for r in later {
+            let Some(changes) = r.changes.as_object() else {
+                continue;
+            };
+            for (name, change) in changes {
+                if KEPT_FIELDS.contains(&name.as_str()) {
+                    continue;
+                }
+                if let Some(from) = change.get("from") {
+                    fields.insert(name.clone(), from.clone());
+                }
+            }
+        }
+
+        serde_json::from_value(Value::Object(fields))
+            .map_err(|e| AppError::InternalServerError(format!("Failed to rebuild trade revision: {}", e)))
+    }
+
+    /// The trade as it was when it was deleted, rebuilt from the fields
+    /// its delete revision recorded. Fails unless the newest revision is
+    /// a delete.
+    pub fn deleted(revisions: &[TradeRevision]) -> Result<Trade> {
+        let Some(delete) = revisions
+            .iter()
+            .max_by_key(|r| r.revision)
+            .filter(|r| r.action == "delete")
+        else {
+            return Err(AppError::ValidationError("Trade not found".to_string()));
+        };
+
+        let mut fields: Map<String, Value> = delete
+            .changes
+            .as_object()
+            .into_iter()
+            .flatten()
+            .filter_map(|(name, change)| Some((name.clone(), change.get("from")?.clone())))
+            .collect();
+        fields.insert("updated_at".to_string(), json!(delete.created_at));
+
+        serde_json::from_value(Value::Object(fields))
+            .map_err(|e| AppError::InternalServerError(format!("Failed to rebuild deleted trade: {}", e)))
+    }
+
+    fn fields(trade: Option<&Trade>) -> Result<Map<String, Value>> {
+        let Some(trade) = trade else {
+            return Ok(Map::new());
+        };
+
+        match serde_json::to_value(trade) {
+            Ok(Value::Object(fields)) => Ok(fields),
+            Ok(_) => Ok(Map::new()),
+            Err(e) => Err(AppError::InternalServerError(format!("Failed to serialize trade: {}", e))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::trade;
+    use chrono::{TimeZone, Utc};
+    use uuid::Uuid;
+
+    fn revision(trade: &Trade, revision: i32, action: &str, changes: Map<String, Value>) -> TradeRevision {
+        TradeRevision {
+            id: Uuid::new_v4(),
+            trade_id: trade.id,
+            user_id: trade.user_id,
+            revision,
+            action: action.to_string(),
+            changes: Value::Object(changes),
+            request_id: None,
+            restored_from: None,
+            created_at: Utc.with_ymd_and_hms(2025, 1, 4, 12, 0, 0).unwrap(),
+        }
+    }
+
+    #[test]
+    fn deleted_trade_is_rebuilt_from_its_delete_revision() {
+        let trade = trade();
+        let revisions = vec![
+            revision(&trade, 2, "delete", RevisionService::diff(Some(&trade), None).unwrap()),
+            revision(&trade, 1, "create", RevisionService::diff(None, Some(&trade)).unwrap()),
+        ];
+
+        let rebuilt = RevisionService::deleted(&revisions).unwrap();
+
+        assert!(RevisionService::diff(Some(&trade), Some(&rebuilt)).unwrap().is_empty());
+        assert_eq!(rebuilt.updated_at, revisions[0].created_at);
+    }
+
+    #[test]
+    fn deleted_trade_restores_to_an_earlier_revision() {
+        let created = trade();
+        let mut edited = created.clone();
+        edited.notes = Some("Took profit early".to_string());
+        edited.tags = Vec::new();
+        let revisions = vec![
+            revision(&edited, 3, "delete", RevisionService::diff(Some(&edited), None).unwrap()),
+            revision(&edited, 2, "update", RevisionService::diff(Some(&created), Some(&edited)).unwrap()),
+            revision(&created, 1, "create", RevisionService::diff(None, Some(&created)).unwrap()),
+        ];
+
+        let deleted = RevisionService::deleted(&revisions).unwrap();
+        assert_eq!(deleted.notes.as_deref(), Some("Took profit early"));
+
+        let restored = RevisionService::restore(&deleted, &revisions, 1).unwrap();
+        assert!(RevisionService::diff(Some(&created), Some(&restored)).unwrap().is_empty());
+    }
+
+    #[test]
+    fn only_a_trade_whose_last_revision_is_a_delete_is_rebuilt() {
+        let trade = trade();
+        let revisions = vec![revision(&trade, 1, "create", RevisionService::diff(None, Some(&trade)).unwrap())];
+
+        assert!(RevisionService::deleted(&revisions).is_err());
+        assert!(RevisionService::deleted(&[]).is_err());
+    }
+}
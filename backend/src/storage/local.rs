@@ -0,0 +1,79 @@
+use super::BlobStore;
+use crate::error::{AppError, Result};
+use async_trait::async_trait;
+use axum::body::Bytes;
+use std::{
+    io::ErrorKind,
+    path::{Path, PathBuf},
+};
+use uuid::Uuid;
+
+/// Blob store keeping each blob as a file under a root directory
+pub struct LocalBlobStore {
+    root: PathBuf,
+}
+
+impl LocalBlobStore {
+    pub fn new(root: impl AsRef<Path>) -> Self {
+        Self {
+            root: root.as_ref().to_path_buf(),
+        }
+    }
+
+    /// File a key maps to; keys may not climb out of the root
+    fn path(&self, key: &str) -> Result<PathBuf> {
+        let valid = !key.is_empty()
+            && key
+                .split('/')
+                .all(|part| !part.is_empty() && part != "." && part != ".." && !part.contains('\\'));
+        if !valid {
+            return Err(AppError::InternalServerError(format!("Invalid blob key '{}'", key)));
+        }
+
+        Ok(self.root.join(key))
+    }
+}
+
+fn io_error(action: &str, key: &str, error: std::io::Error) -> AppError {
+    AppError::InternalServerError(format!("Failed to {} blob '{}': {}", action, key, error))
+}
+
+#[async_trait]
+impl BlobStore for LocalBlobStore {
+    async fn put(&self, key: &str, _content_type: &str, data: Bytes) -> Result<()> {
+        let path = self.path(key)?;
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .map_err(|e| io_error("store", key, e))?;
+        }
+
+        // Write aside and rename so readers never see a partial file
+        let partial = path.with_file_name(format!(".{}.partial", Uuid::new_v4()));
+        tokio::fs::write(&partial, &data)
+            .await
+            .map_err(|e| io_error("store", key, e))?;
+        if let Err(e) = tokio::fs::rename(&partial, &path).await {
+            let _ = tokio::fs::remove_file(&partial).await;
+            return Err(io_error("store", key, e));
+        }
+
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<Bytes>> {
+        match tokio::fs::read(self.path(key)?).await {
+            Ok(data) => Ok(Some(Bytes::from(data))),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(io_error("read", key, e)),
+        }
+    }
+
+    async fn delete(&self, key: &str) -> Result<()> {
+        match tokio::fs::remove_file(self.path(key)?).await {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(io_error("delete", key, e)),
+        }
+    }
+}
@@ -0,0 +1,44 @@
+pub mod local;
+pub mod s3;
+
+pub use local::LocalBlobStore;
+pub use s3::S3BlobStore;
+
+use crate::{error::Result, Config};
+use async_trait::async_trait;
+use axum::body::Bytes;
+use std::sync::Arc;
+
+/// Storage for the bytes of uploaded files, addressed by slash-separated keys
+#[async_trait]
+pub trait BlobStore: Send + Sync {
+    /// Store a blob, replacing any blob with the same key
+    async fn put(&self, key: &str, content_type: &str, data: Bytes) -> Result<()>;
+
+    /// Read a blob; `None` when no blob has the key
+    async fn get(&self, key: &str) -> Result<Option<Bytes>>;
+
+    /// Remove a blob; removing a missing key is not an error
+    async fn delete(&self, key: &str) -> Result<()>;
+}
+
+/// Build the blob store selected by the configuration
+pub fn from_config(config: &Config) -> std::result::Result<Arc<dyn BlobStore>, String> {
+    match config.storage_backend.as_str() {
+        "local" => Ok(Arc::new(LocalBlobStore::new(&config.storage_path))),
+        "s3" => {
+            let required = |value: &Option<String>, name: &str| {
+                value.clone().ok_or_else(|| format!("{} must be set", name))
+            };
+            let store = S3BlobStore::new(
+                &required(&config.s3_endpoint, "S3_ENDPOINT")?,
+                &required(&config.s3_bucket, "S3_BUCKET")?,
+                &config.s3_region,
+                &required(&config.s3_access_key, "S3_ACCESS_KEY")?,
+                &required(&config.s3_secret_key, "S3_SECRET_KEY")?,
+            )?;
+            Ok(Arc::new(store))
+        }
+        other => Err(format!("Unknown storage backend '{}'", other)),
+    }
+}
@@ -0,0 +1,231 @@
+use super::BlobStore;
+use crate::error::{AppError, Result};
+use async_trait::async_trait;
+use axum::body::Bytes;
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use reqwest::{header, Client, Method, RequestBuilder, StatusCode, Url};
+use sha2::{Digest, Sha256};
+use std::time::Duration;
+
+/// Blob store backed by an S3-compatible service such as AWS S3 or MinIO.
+///
+/// Objects are addressed path-style (`{endpoint}/{bucket}/{key}`), which
+/// MinIO requires, and requests are signed with AWS Signature Version 4.
+pub struct S3BlobStore {
+    client: Client,
+    /// Base URL; buckets are addressed below its path
+    endpoint: Url,
+    /// Host header value the signature covers
+    host: String,
+    bucket: String,
+    region: String,
+    access_key: String,
+    secret_key: String,
+}
+
+impl S3BlobStore {
+    pub fn new(
+        endpoint: &str,
+        bucket: &str,
+        region: &str,
+        access_key: &str,
+        secret_key: &str,
+    ) -> std::result::Result<Self, String> {
+        let endpoint = Url::parse(endpoint.trim_end_matches('/'))
+            .map_err(|e| format!("S3_ENDPOINT is not a valid URL: {}", e))?;
+        let host = match (endpoint.host_str(), endpoint.port()) {
+            (Some(host), Some(port)) => format!("{}:{}", host, port),
+            (Some(host), None) => host.to_string(),
+            (None, _) => return Err("S3_ENDPOINT must include a host".to_string()),
+        };
+        let client = Client::builder()
+            .timeout(Duration::from_secs(60))
+            .build()
+            .map_err(|e| format!("Failed to build S3 client: {}", e))?;
+
+        Ok(Self {
+            client,
+            endpoint,
+            host,
+            bucket: bucket.to_string(),
+            region: region.to_string(),
+            access_key: access_key.to_string(),
+            secret_key: secret_key.to_string(),
+        })
+    }
+
+    /// Build a request for an object, signed with Signature Version 4
+    fn request(&self, method: Method, key: &str, body: Bytes) -> RequestBuilder {
+        let path = format!(
+            "{}/{}/{}",
+            self.endpoint.path().trim_end_matches('/'),
+            uri_encode(&self.bucket),
+            uri_encode(key)
+        );
+        let mut url = self.endpoint.clone();
+        url.set_path(&path);
+
+        let now = Utc::now();
+        let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+        let date = now.format("%Y%m%d").to_string();
+        let payload_hash = hex::encode(Sha256::digest(&body));
+
+        let signed_headers = "host;x-amz-content-sha256;x-amz-date";
+        let canonical_request = format!(
+            "{}\n{}\n\nhost:{}\nx-amz-content-sha256:{}\nx-amz-date:{}\n\n{}\n{}",
+            method, path, self.host, payload_hash, amz_date, signed_headers, payload_hash
+        );
+        let scope = format!("{}/{}/s3/aws4_request", date, self.region);
+        let string_to_sign = format!(
+            "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+            amz_date,
+            scope,
+            hex::encode(Sha256::digest(canonical_request.as_bytes()))
+        );
+
+        let signing_key = [self.region.as_str(), "s3", "aws4_request"]
+            .iter()
+            .fold(hmac(format!("AWS4{}", self.secret_key).as_bytes(), date.as_bytes()), |key, part| {
+                hmac(&key, part.as_bytes())
+            });
+        let signature = hex::encode(hmac(&signing_key, string_to_sign.as_bytes()));
+
+        self.client
+            .request(method, url)
+            .header(header::HOST, &self.host)
+            .header("x-amz-content-sha256", payload_hash)
+            .header("x-amz-date", amz_date)
+            .header(
+                header::AUTHORIZATION,
+                format!(
+                    "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+                    self.access_key, scope, signed_headers, signature
+                ),
+            )
+            .body(body)
+    }
+
+    async fn send(&self, action: &str, key: &str, request: RequestBuilder) -> Result<reqwest::Response> {
+        request
+            .send()
+            .await
+            .map_err(|e| AppError::InternalServerError(format!("Failed to {} blob '{}': {}", action, key, e)))
+    }
+}
+
+/// Turn an unexpected S3 response into an error carrying its status and body
+async fn failure(action: &str, key: &str, response: reqwest::Response) -> AppError {
+    let status = response.status();
+    let body = response.text().await.unwrap_or_default();
+    AppError::InternalServerError(format!("Failed to {} blob '{}': S3 returned {}: {}", action, key, status, body))
+}
+
+fn hmac(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+/// Percent-encode a key the way Signature Version 4 expects, keeping slashes
+fn uri_encode(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' => encoded.push(byte as char),
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+#[async_trait]
+impl BlobStore for S3BlobStore {
+    async fn put(&self, key: &str, content_type: &str, data: Bytes) -> Result<()> {
+        let request = self.request(Method::PUT, key, data).header(header::CONTENT_TYPE, content_type);
+        let response = self.send("store", key, request).await?;
+        if !response.status().is_success() {
+            return Err(failure("store", key, response).await);
+        }
+
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<Bytes>> {
+        let response = self.send("read", key, self.request(Method::GET, key, Bytes::new())).await?;
+        match response.status() {
+            StatusCode::NOT_FOUND => Ok(None),
+            status if status.is_success() => {
+                let data = response
+                    .bytes()
+                    .await
+                    .map_err(|e| AppError::InternalServerError(format!("Failed to read blob '{}': {}", key, e)))?;
+                Ok(Some(data))
+            }
+            _ => Err(failure("read", key, response).await),
+        }
+    }
+
+    async fn delete(&self, key: &str) -> Result<()> {
+        let response = self.send("delete", key, self.request(Method::DELETE, key, Bytes::new())).await?;
+        if !response.status().is_success() && response.status() != StatusCode::NOT_FOUND {
+            return Err(failure("delete", key, response).await);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    /// Store on the docker-compose MinIO; `S3_TEST_ENDPOINT`,
+    /// `S3_TEST_BUCKET`, `S3_TEST_ACCESS_KEY` and `S3_TEST_SECRET_KEY`
+    /// point it elsewhere
+    fn minio(secret_key: Option<&str>) -> S3BlobStore {
+        let var = |name: &str, default: &str| std::env::var(name).unwrap_or_else(|_| default.to_string());
+        S3BlobStore::new(
+            &var("S3_TEST_ENDPOINT", "http://localhost:9000"),
+            &var("S3_TEST_BUCKET", "trading-journal"),
+            "us-east-1",
+            &var("S3_TEST_ACCESS_KEY", "minioadmin"),
+            &secret_key.map_or_else(|| var("S3_TEST_SECRET_KEY", "minioadmin"), str::to_string),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn keys_are_encoded_except_slashes() {
+        assert_eq!(uri_encode("user/trade/a b+ü.png"), "user/trade/a%20b%2B%C3%BC.png");
+    }
+
+    #[tokio::test]
+    #[ignore = "needs MinIO: `docker compose up -d minio minio-init`, then `cargo test -- --ignored`"]
+    async fn put_get_delete_round_trip() {
+        let store = minio(None);
+        let key = format!("tests/{}/chart 1+2.png", Uuid::new_v4());
+
+        store.put(&key, "image/png", Bytes::from_static(b"first")).await.unwrap();
+        assert_eq!(store.get(&key).await.unwrap(), Some(Bytes::from_static(b"first")));
+
+        store.put(&key, "text/plain", Bytes::from_static(b"second")).await.unwrap();
+        assert_eq!(store.get(&key).await.unwrap(), Some(Bytes::from_static(b"second")));
+
+        store.delete(&key).await.unwrap();
+        assert_eq!(store.get(&key).await.unwrap(), None);
+        // Deleting a missing key is not an error
+        store.delete(&key).await.unwrap();
+    }
+
+    #[tokio::test]
+    #[ignore = "needs MinIO: `docker compose up -d minio minio-init`, then `cargo test -- --ignored`"]
+    async fn bad_signature_is_rejected() {
+        let store = minio(Some("not-the-secret"));
+        let key = format!("tests/{}/rejected.txt", Uuid::new_v4());
+
+        let result = store.put(&key, "text/plain", Bytes::from_static(b"data")).await;
+        assert!(matches!(result, Err(AppError::InternalServerError(_))));
+    }
+}